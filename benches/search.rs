@@ -0,0 +1,133 @@
+use leetcode_largest_rectangle::generate::{self, Pattern};
+use leetcode_largest_rectangle::histogram::FnHistogram;
+use leetcode_largest_rectangle::square_search::{self, BatchSolver};
+use leetcode_largest_rectangle::{ConcreteHistogram, Histogram};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+fn main() {
+    let small_histograms = random_histograms(100_000, 16);
+    bench("one-shot solver, 100k small histograms", || {
+        small_histograms
+            .iter()
+            .map(square_search::compute_area_of_largest_rectangle)
+            .sum::<u64>()
+    });
+    let tiny_histograms = random_histograms(100_000, 32);
+    bench("one-shot solver, 100k histograms of up to 32 bars", || {
+        tiny_histograms
+            .iter()
+            .map(square_search::compute_area_of_largest_rectangle)
+            .sum::<u64>()
+    });
+    let mut solver = BatchSolver::new();
+    bench("batch solver, 100k small histograms", || {
+        solver.solve_all(&small_histograms).iter().sum::<u64>()
+    });
+    bench("parallel batch, 100k small histograms", || {
+        square_search::compute_areas_parallel(&small_histograms)
+            .iter()
+            .sum::<u64>()
+    });
+    let mut next = xorshift();
+    let long_histogram =
+        ConcreteHistogram::new((0..1_000_000).map(|_| (next() % 100) as i32).collect());
+    bench("sliding window of 10k bars, 1M bars", || {
+        square_search::sliding_window_largest_rectangle(&long_histogram, 10_000)
+    });
+    let huge_histogram =
+        ConcreteHistogram::new((0..10_000_000).map(|_| (next() % 1000) as i32).collect());
+    bench("serial search, 10M bars", || {
+        square_search::compute_area_of_largest_rectangle(&huge_histogram)
+    });
+    let bars = huge_histogram.heights();
+    let generic_histogram = FnHistogram::new(bars.len(), |x_pos| bars[x_pos]);
+    bench("generic search through height_at, 10M bars", || {
+        square_search::compute_area_of_largest_rectangle(&generic_histogram)
+    });
+    for chunks in [1, 2, 4, 8] {
+        bench(
+            &format!("parallel search in {chunks} chunks, 10M bars"),
+            || square_search::compute_area_parallel_with(&huge_histogram, chunks),
+        );
+    }
+    let sawtooth = generate::pattern(Pattern::Sawtooth, 10_000_000, 1000);
+    bench("stack of indices only, 10M-bar sawtooth", || {
+        area_with_stack_of_indices(&sawtooth)
+    });
+    bench("stack of indices and heights, 10M-bar sawtooth", || {
+        square_search::compute_area_of_largest_rectangle(&sawtooth)
+    });
+    for pattern in Pattern::ALL {
+        let max_height = match pattern {
+            Pattern::Sawtooth => 1000,
+            _ => 10_000_000,
+        };
+        let histogram = generate::pattern(pattern, 10_000_000, max_height);
+        bench(&format!("one-shot solver, 10M bars, {pattern:?}"), || {
+            square_search::compute_area_of_largest_rectangle(&histogram)
+        });
+    }
+}
+
+/// The search as it was before the stack kept each recorded bar's height,
+/// reading heights back from the histogram every time it compares against
+/// the stack, as a baseline.
+fn area_with_stack_of_indices<H: Histogram>(histogram: &H) -> u64 {
+    let width = histogram.width() as i64;
+    let height_at = |x_pos: i64| {
+        if x_pos >= 0 && x_pos < width {
+            histogram.height_at(x_pos as usize).max(0)
+        } else {
+            0
+        }
+    };
+    let mut recorded_bars_of_increasing_height = vec![-1_i64];
+    let mut area_of_largest_rectangle = 0;
+    for x_pos in 0..=width {
+        let top = |recorded: &[i64]| recorded[recorded.len() - 1];
+        while height_at(top(&recorded_bars_of_increasing_height)) > height_at(x_pos) {
+            let last = recorded_bars_of_increasing_height.pop().unwrap();
+            let left = top(&recorded_bars_of_increasing_height) + 1;
+            let area = height_at(last) as u64 * (x_pos - left) as u64;
+            area_of_largest_rectangle = area_of_largest_rectangle.max(area);
+        }
+        if height_at(top(&recorded_bars_of_increasing_height)) == height_at(x_pos) {
+            recorded_bars_of_increasing_height.pop();
+        }
+        recorded_bars_of_increasing_height.push(x_pos);
+    }
+    area_of_largest_rectangle
+}
+
+fn bench<R, F: FnMut() -> R>(name: &str, mut f: F) {
+    const ITERATIONS: u32 = 10;
+    black_box(f());
+    let mut total = Duration::ZERO;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        black_box(f());
+        total += start.elapsed();
+    }
+    println!("{name}: {:?} per iteration", total / ITERATIONS);
+}
+
+fn xorshift() -> impl FnMut() -> u64 {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    }
+}
+
+fn random_histograms(count: usize, max_width: u64) -> Vec<ConcreteHistogram> {
+    let mut next = xorshift();
+    (0..count)
+        .map(|_| {
+            let width = next() % (max_width + 1);
+            ConcreteHistogram::new((0..width).map(|_| (next() % 100) as i32).collect())
+        })
+        .collect()
+}
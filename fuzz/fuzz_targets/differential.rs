@@ -0,0 +1,18 @@
+//! Checks the stack search against the naive search on histograms built from
+//! the fuzzer's bytes.
+
+#![no_main]
+
+use leetcode_largest_rectangle::generate;
+use leetcode_largest_rectangle::square_search::{self, naive};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| {
+    let histogram = generate::from_bytes(bytes);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram),
+        naive::compute_area_of_largest_rectangle(&histogram),
+        "bars {:?}",
+        histogram.heights()
+    );
+});
@@ -0,0 +1,49 @@
+//! Feeds the fuzzer's bytes to every parser the command-line tool reads with,
+//! none of which may panic, and checks that what they read is read again the
+//! same once written back out.
+
+#![no_main]
+
+use leetcode_largest_rectangle::parse::{csv::CsvReader, json, plain};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| {
+    if let Ok(bars) = plain::read_heights(bytes) {
+        let text: Vec<String> = bars.iter().map(i32::to_string).collect();
+        assert_eq!(plain::read_heights(text.join(" ").as_bytes()).ok(), Some(bars));
+    }
+    let _ = plain::read_rows(bytes);
+
+    if let Ok(value) = json::parse(bytes) {
+        if let Ok(bars) = json::bars(value) {
+            let text: Vec<String> = bars.iter().map(i32::to_string).collect();
+            let value = json::parse(format!("[{}]", text.join(",")).as_bytes()).unwrap();
+            assert_eq!(json::bars(value), Ok(bars));
+        }
+    }
+    if let Ok(value) = json::parse(bytes) {
+        let _ = json::rows(value);
+    }
+
+    let records: Result<Vec<Vec<String>>, _> = CsvReader::new(bytes)
+        .map(|record| record.map(|record| record.fields))
+        .collect();
+    if let Ok(records) = records {
+        // Quoting every field keeps blank records, which the reader skips,
+        // from being written.
+        let text: String = records
+            .iter()
+            .map(|fields| {
+                let quoted: Vec<String> = fields
+                    .iter()
+                    .map(|field| format!("\"{}\"", field.replace('"', "\"\"")))
+                    .collect();
+                quoted.join(",") + "\n"
+            })
+            .collect();
+        let reread: Vec<Vec<String>> = CsvReader::new(text.as_bytes())
+            .map(|record| record.unwrap().fields)
+            .collect();
+        assert_eq!(reread, records);
+    }
+});
@@ -0,0 +1,48 @@
+/*
+ * The largest rectangle in a histogram, from C and C++.
+ *
+ * Build the library with `cargo build --release --features ffi` and link
+ * against the shared library it leaves in target/release. The declarations
+ * here match src/ffi.rs, which documents each function.
+ */
+
+#ifndef LRH_H
+#define LRH_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Statuses: zero or positive on success, negative on failure, when the
+ * output is left untouched. */
+#define LRH_OK 0
+#define LRH_NO_RECTANGLE 1
+#define LRH_NULL_POINTER (-1)
+#define LRH_INVALID_LENGTH (-2)
+#define LRH_OVERFLOW (-3)
+#define LRH_PANIC (-4)
+
+/* The largest rectangle, spanning the bars from left to right inclusive. */
+typedef struct LrhRect {
+    size_t left;
+    size_t right;
+    int32_t height;
+    int64_t area;
+} LrhRect;
+
+/* Writes the area of the largest rectangle under the len bars at bars, which
+ * may be null if len is 0, to out_area. */
+int32_t lrh_largest_rectangle_area(const int32_t *bars, size_t len, int64_t *out_area);
+
+/* Writes the largest rectangle under the len bars at bars, which may be null
+ * if len is 0, to out_rect. Every field is 0 if there is none. */
+int32_t lrh_largest_rectangle(const int32_t *bars, size_t len, LrhRect *out_rect);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* LRH_H */
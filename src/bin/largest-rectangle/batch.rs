@@ -0,0 +1,182 @@
+//! Answering many histograms, one per line of the input.
+
+use crate::error::CliError;
+use crate::output::{Answer, Format};
+use crate::source::InputArgs;
+use leetcode_largest_rectangle::parse::json;
+use leetcode_largest_rectangle::parse::plain::{self, InputError};
+use leetcode_largest_rectangle::ConcreteHistogram;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+/// The help for the options that `BatchArgs` takes, for each command's usage.
+macro_rules! batch_options_help {
+    () => {
+        "  --batch PATH    Read one histogram per line of a file, or of standard
+                  input if PATH is -, and print one answer per line in the
+                  same order; an empty line is an empty histogram, whose
+                  answer is 0, and with --json each line is a JSON array or
+                  object with `bars`
+  --strict        Stop at the first line of a batch that cannot be parsed,
+                  rather than reporting it, printing an empty answer in its
+                  place and failing once the rest are answered
+"
+    };
+}
+pub(crate) use batch_options_help;
+
+/// The batch options seen so far in a command's arguments.
+#[derive(Default)]
+pub struct BatchArgs {
+    path: Option<String>,
+    strict: bool,
+}
+
+impl BatchArgs {
+    /// Takes `arg` if it is a batch option, along with any value it needs
+    /// from `args`, and returns whether it did.
+    pub fn accept(
+        &mut self,
+        arg: &str,
+        args: &mut impl Iterator<Item = String>,
+        usage: &'static str,
+    ) -> Result<bool, CliError> {
+        match arg {
+            "--batch" => match args.next() {
+                Some(path) => self.path = Some(path),
+                None => return Err(CliError::usage("`--batch` needs a path", usage)),
+            },
+            "--strict" => self.strict = true,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Settles whether the command runs in batch mode once every argument
+    /// has been seen, which leaves no room for other input.
+    pub fn finish(self, input: &InputArgs, usage: &'static str) -> Result<Option<Batch>, CliError> {
+        let Some(path) = self.path else {
+            if self.strict {
+                return Err(CliError::usage("`--strict` needs `--batch`", usage));
+            }
+            return Ok(None);
+        };
+        if !input.is_empty() {
+            return Err(CliError::usage(
+                "`--batch` cannot be combined with other input",
+                usage,
+            ));
+        }
+        Ok(Some(Batch {
+            path,
+            strict: self.strict,
+            json: input.json(),
+        }))
+    }
+}
+
+/// A batch of histograms, ready to be answered.
+pub struct Batch {
+    path: String,
+    strict: bool,
+    json: bool,
+}
+
+impl Batch {
+    /// Prints `answer` for each line's histogram in `format` as soon as the
+    /// line is read, so the input is never held in memory at once. A CSV or
+    /// TSV header is printed once, before the first answer.
+    pub fn run<A: Answer>(
+        self,
+        format: Format,
+        answer: impl Fn(&ConcreteHistogram) -> A,
+    ) -> Result<(), CliError> {
+        if self.path == "-" {
+            self.answer_lines("stdin", Ok(io::stdin().lock()), format, answer)
+        } else {
+            let file = File::open(&self.path).map(BufReader::new);
+            self.answer_lines(&self.path, file, format, answer)
+        }
+    }
+
+    fn answer_lines<R: BufRead, A: Answer>(
+        &self,
+        name: &str,
+        reader: io::Result<R>,
+        format: Format,
+        answer: impl Fn(&ConcreteHistogram) -> A,
+    ) -> Result<(), CliError> {
+        let json = self.json;
+        let reader = reader.map_err(|error| CliError::io(name, error, json))?;
+        let mut out = BufWriter::new(io::stdout().lock());
+        if let Some(header) = format.header::<A>() {
+            if writeln!(out, "{header}").is_err() {
+                return Ok(());
+            }
+        }
+        let (mut lines, mut failed) = (0, 0);
+        for (index, text) in reader.split(b'\n').enumerate() {
+            let text = text.map_err(|error| CliError::io(name, error, json))?;
+            let line = index + 1;
+            lines += 1;
+            let written = match self.parse_line(&text, name, line) {
+                Ok(bars) => {
+                    let answer = answer(&ConcreteHistogram::new(bars));
+                    writeln!(out, "{}", format.line(&answer))
+                }
+                Err(error) if self.strict => {
+                    let _ = out.flush();
+                    return Err(error);
+                }
+                Err(error) => {
+                    let _ = out.flush();
+                    error.report();
+                    failed += 1;
+                    writeln!(out, "{}", format.missing::<A>())
+                }
+            };
+            if written.is_err() {
+                // Stdout is closed, as when piped into `head`, so nothing more
+                // can be printed.
+                return Ok(());
+            }
+        }
+        let _ = out.flush();
+        if failed > 0 {
+            return Err(CliError::parse(
+                "parse",
+                format!("{failed} of {lines} lines could not be parsed"),
+                json,
+            )
+            .at(name, None, None));
+        }
+        Ok(())
+    }
+
+    fn parse_line(&self, text: &[u8], name: &str, line: usize) -> Result<Vec<i32>, CliError> {
+        let json = self.json;
+        if json {
+            if text.iter().all(u8::is_ascii_whitespace) {
+                return Ok(Vec::new());
+            }
+            let value = json::parse(text).map_err(|error| {
+                CliError::parse("syntax", error.message, json).at(
+                    name,
+                    Some(line),
+                    Some(error.column),
+                )
+            })?;
+            return json::bars(value).map_err(|message| {
+                CliError::parse("schema", message, json).at(name, Some(line), None)
+            });
+        }
+        plain::read_heights(text).map_err(|error| match error {
+            InputError::Parse { column, token, .. } => {
+                CliError::parse("parse", format!("`{token}` is not a bar height"), json)
+                    .at(name, Some(line), Some(column))
+                    .token(token)
+            }
+            error => CliError::internal(format!("reading a line failed with {error}")),
+        })
+    }
+}
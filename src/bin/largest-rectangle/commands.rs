@@ -0,0 +1,8 @@
+//! The subcommands, each with its own options and help.
+
+pub mod area;
+pub mod generate;
+pub mod matrix;
+pub mod rect;
+pub mod render;
+pub mod repl;
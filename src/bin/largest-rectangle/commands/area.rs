@@ -0,0 +1,56 @@
+use crate::batch::{batch_options_help, BatchArgs};
+use crate::commands::rect;
+use crate::error::CliError;
+use crate::output::{self, output_options_help, Area, Format};
+use crate::source::{input_options_help, InputArgs};
+use leetcode_largest_rectangle::{compute_area_of_largest_rectangle, ConcreteHistogram};
+
+pub const USAGE: &str = concat!(
+    "\
+Usage: largest-rectangle [area] [OPTIONS] HEIGHT...
+       largest-rectangle [area] [OPTIONS] (--stdin | --input PATH | --csv PATH)
+       largest-rectangle [area] [OPTIONS] --batch PATH
+
+Prints the area of the largest rectangle under the histogram with the given
+bar heights. This is the command run when none is named, and then its JSON
+output is that of `rect`, with the area, bars and height.
+
+Options:
+",
+    input_options_help!(),
+    batch_options_help!(),
+    output_options_help!(),
+    "  -h, --help      Print this help
+
+Example: largest-rectangle 2 1 5 6 2 3"
+);
+
+/// Runs the command, as the default one if it was not `named`.
+pub fn run(mut args: impl Iterator<Item = String>, named: bool) -> Result<(), CliError> {
+    let (mut input, mut batch) = (InputArgs::default(), BatchArgs::default());
+    let mut format = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                return output::print_line(USAGE);
+            }
+            _ if batch.accept(&arg, &mut args, USAGE)? => {}
+            "--output-format" => format = Some(Format::parse(args.next(), USAGE)?),
+            _ if input.accept(&arg, &mut args, USAGE)? => {}
+            _ => input.positional(arg, USAGE)?,
+        }
+    }
+    let format = Format::chosen(format, input.json());
+    // The default command printed these keys before it was named `area`.
+    if !named && format == Format::Json {
+        return rect::print(input, batch, format, USAGE);
+    }
+    if let Some(batch) = batch.finish(&input, USAGE)? {
+        return batch.run(format, |histogram| {
+            Area(compute_area_of_largest_rectangle(histogram))
+        });
+    }
+    let input = input.finish(USAGE)?;
+    let area = compute_area_of_largest_rectangle(&ConcreteHistogram::new(input.read_bars()?));
+    format.print(&Area(area))
+}
@@ -0,0 +1,136 @@
+use crate::error::CliError;
+use crate::output;
+use leetcode_largest_rectangle::generate::{self, Distribution, Pattern, RandomHistogram};
+use leetcode_largest_rectangle::ConcreteHistogram;
+
+pub const USAGE: &str = "\
+Usage: largest-rectangle generate --width N [--min H] [--max H]
+                                  [--distribution NAME] [--seed S]
+       largest-rectangle generate --width N --pattern NAME [--max H]
+
+Prints the bar heights of a random or patterned histogram on one line, ready
+to be read back with `--stdin`. The same options and seed always print the
+same heights.
+
+Options:
+  --width N       The number of bars
+  --min H         The lowest random height, 0 by default
+  --max H         The highest height, 100 by default
+  --distribution NAME
+                  How random heights are spread between --min and --max:
+                  uniform (the default), zipf or gaussian
+  --seed S        The seed of the random heights, 0 by default
+  --pattern NAME  Draw a shape instead of random heights: increasing,
+                  decreasing, sawtooth, plateaus, spike or organ-pipe
+  --json          Print the heights as a JSON array
+  -h, --help      Print this help
+
+Example: largest-rectangle generate --width 20 --seed 7 | largest-rectangle --stdin";
+
+pub fn run(mut args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let (mut width, mut min, mut max) = (None, None, None);
+    let (mut distribution, mut seed, mut pattern) = (None, None, None);
+    let mut json = false;
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| CliError::usage(format!("`{arg}` needs a value"), USAGE))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => {
+                return output::print_line(USAGE);
+            }
+            "--width" => width = Some(number(&arg, value()?)?),
+            "--min" => min = Some(number(&arg, value()?)?),
+            "--max" => max = Some(number(&arg, value()?)?),
+            "--seed" => seed = Some(number(&arg, value()?)?),
+            "--distribution" => {
+                distribution = Some(match value()?.as_str() {
+                    "uniform" => Distribution::Uniform,
+                    "zipf" => Distribution::Zipf,
+                    "gaussian" => Distribution::Gaussian,
+                    other => {
+                        return Err(CliError::usage(
+                            format!("`{other}` is not a distribution"),
+                            USAGE,
+                        ))
+                    }
+                })
+            }
+            "--pattern" => {
+                pattern = Some(match value()?.as_str() {
+                    "increasing" => Pattern::Increasing,
+                    "decreasing" => Pattern::Decreasing,
+                    "sawtooth" => Pattern::Sawtooth,
+                    "plateaus" => Pattern::Plateaus,
+                    "spike" => Pattern::Spike,
+                    "organ-pipe" => Pattern::OrganPipe,
+                    other => {
+                        return Err(CliError::usage(
+                            format!("`{other}` is not a pattern"),
+                            USAGE,
+                        ))
+                    }
+                })
+            }
+            "--json" => json = true,
+            _ => return Err(CliError::usage(format!("unknown argument `{arg}`"), USAGE)),
+        }
+    }
+    let Some(width) = width else {
+        return Err(CliError::usage("`--width` is required", USAGE));
+    };
+    if Vec::<i32>::new().try_reserve_exact(width).is_err() {
+        return Err(CliError::usage(
+            format!("`--width` {width} is more bars than fit in memory"),
+            USAGE,
+        ));
+    }
+    let max = max.unwrap_or(100);
+    let histogram: ConcreteHistogram = match pattern {
+        Some(_) if min.is_some() || distribution.is_some() || seed.is_some() => {
+            return Err(CliError::usage(
+                "`--pattern` cannot be combined with `--min`, `--distribution` or `--seed`",
+                USAGE,
+            ))
+        }
+        Some(_) if max < 0 => {
+            return Err(CliError::usage(
+                "a pattern's `--max` cannot be negative",
+                USAGE,
+            ))
+        }
+        Some(pattern) => generate::pattern(pattern, width, max),
+        None => {
+            let min = min.unwrap_or(0);
+            if min > max {
+                return Err(CliError::usage(
+                    format!("`--min` {min} is above `--max` {max}"),
+                    USAGE,
+                ));
+            }
+            RandomHistogram::builder()
+                .width(width)
+                .height_range(min..=max)
+                .distribution(distribution.unwrap_or_default())
+                .seed(seed.unwrap_or(0))
+                .build()
+        }
+    };
+    let heights: Vec<String> = histogram
+        .heights()
+        .iter()
+        .map(|height| height.to_string())
+        .collect();
+    if json {
+        output::print_line(format!("[{}]", heights.join(",")))
+    } else {
+        output::print_line(heights.join(" "))
+    }
+}
+
+fn number<T: std::str::FromStr>(option: &str, value: String) -> Result<T, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError::usage(format!("`{option}` needs a number, not `{value}`"), USAGE))
+}
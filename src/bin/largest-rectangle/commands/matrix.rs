@@ -0,0 +1,46 @@
+use crate::error::CliError;
+use crate::output::{self, output_options_help, Format};
+use crate::source::{input_options_help, InputArgs};
+use leetcode_largest_rectangle::matrix::maximal_rectangle;
+
+pub const USAGE: &str = concat!(
+    "\
+Usage: largest-rectangle matrix [OPTIONS] ROW...
+       largest-rectangle matrix [OPTIONS] (--stdin | --input PATH | --csv PATH)
+
+Prints the area of the largest rectangle of 1 cells in a grid of 0 and 1
+cells, with the rows and columns it spans, or 0 if there is none. Each row is
+an argument or a line of input, with its cells optionally separated by
+whitespace; in JSON, an array of rows or an object with a `rows` array, each
+row an array of 0, 1 or booleans; in CSV, a record with a cell per column.
+
+Options:
+",
+    input_options_help!(),
+    output_options_help!(),
+    "  -h, --help      Print this help
+
+Example: largest-rectangle matrix 10100 10111 11111 10010"
+);
+
+pub fn run(mut args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let (mut input, mut format) = (InputArgs::default(), None);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                return output::print_line(USAGE);
+            }
+            "--output-format" => format = Some(Format::parse(args.next(), USAGE)?),
+            _ if input.accept(&arg, &mut args, USAGE)? => {}
+            _ => input.positional(arg, USAGE)?,
+        }
+    }
+    if input.has_column() {
+        return Err(CliError::usage(
+            "a grid is read from every CSV column, so `--column` does not apply",
+            USAGE,
+        ));
+    }
+    let input = input.finish(USAGE)?;
+    Format::chosen(format, input.json).print(&maximal_rectangle(&input.read_grid()?))
+}
@@ -0,0 +1,58 @@
+use crate::batch::{batch_options_help, BatchArgs};
+use crate::error::CliError;
+use crate::output::{self, output_options_help, Format};
+use crate::source::{input_options_help, InputArgs};
+use leetcode_largest_rectangle::{compute_largest_rectangle, ConcreteHistogram};
+
+pub const USAGE: &str = concat!(
+    "\
+Usage: largest-rectangle rect [OPTIONS] HEIGHT...
+       largest-rectangle rect [OPTIONS] (--stdin | --input PATH | --csv PATH)
+       largest-rectangle rect [OPTIONS] --batch PATH
+
+Prints the area of the largest rectangle under the histogram with the given
+bar heights, with the bars it spans and its height, or 0 if there is none.
+
+Options:
+",
+    input_options_help!(),
+    batch_options_help!(),
+    output_options_help!(),
+    "  -h, --help      Print this help
+
+Example: largest-rectangle rect 2 1 5 6 2 3"
+);
+
+pub fn run(mut args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let (mut input, mut batch) = (InputArgs::default(), BatchArgs::default());
+    let mut format = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                return output::print_line(USAGE);
+            }
+            _ if batch.accept(&arg, &mut args, USAGE)? => {}
+            "--output-format" => format = Some(Format::parse(args.next(), USAGE)?),
+            _ if input.accept(&arg, &mut args, USAGE)? => {}
+            _ => input.positional(arg, USAGE)?,
+        }
+    }
+    let format = Format::chosen(format, input.json());
+    print(input, batch, format, USAGE)
+}
+
+/// Prints the largest rectangle of the histogram, or of each in the batch,
+/// that the arguments name, reporting wrong arguments with `usage`.
+pub fn print(
+    input: InputArgs,
+    batch: BatchArgs,
+    format: Format,
+    usage: &'static str,
+) -> Result<(), CliError> {
+    if let Some(batch) = batch.finish(&input, usage)? {
+        return batch.run(format, compute_largest_rectangle);
+    }
+    let input = input.finish(usage)?;
+    let rectangle = compute_largest_rectangle(&ConcreteHistogram::new(input.read_bars()?));
+    format.print(&rectangle)
+}
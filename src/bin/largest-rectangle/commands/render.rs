@@ -0,0 +1,150 @@
+use crate::error::CliError;
+use crate::output::{self, Format};
+use crate::source::{input_options_help, InputArgs};
+use leetcode_largest_rectangle::render::{self, AsciiOptions, Overflow};
+use leetcode_largest_rectangle::{
+    compute_largest_rectangle, ConcreteHistogram, Histogram, Rectangle,
+};
+use std::env;
+use std::io::{self, IsTerminal};
+
+pub const USAGE: &str = concat!(
+    "\
+Usage: largest-rectangle render [OPTIONS] HEIGHT...
+       largest-rectangle render [OPTIONS] (--stdin | --input PATH | --csv PATH)
+
+Draws the histogram with the given bar heights, with its largest rectangle
+drawn in `*` and in colour on a terminal, followed by the rectangle as `rect`
+prints it. Each unit of height is a row, so a histogram with a bar taller
+than 200 is not drawn. Giving no command and `--render` among the arguments
+runs this command too.
+
+Options:
+  --no-color      Draw the rectangle without colour
+  --width-limit N Draw at most N columns, cutting off wider histograms
+                  with `...`; wider histograms wrap at 80 columns if no
+                  limit is given
+  --output-format plain
+                  Accepted for symmetry with the other commands; a drawing
+                  is only printed as plain text
+",
+    input_options_help!(),
+    "  -h, --help      Print this help
+
+Example: largest-rectangle render 2 1 5 6 2 3"
+);
+
+pub fn run(mut args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let mut input = InputArgs::default();
+    let (mut no_color, mut width_limit) = (false, None);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                return output::print_line(USAGE);
+            }
+            "--no-color" => no_color = true,
+            "--width-limit" => match args.next().map(|limit| limit.parse::<usize>()) {
+                Some(Ok(limit)) if limit > 0 => width_limit = Some(limit),
+                _ => {
+                    return Err(CliError::usage(
+                        "`--width-limit` needs a positive number",
+                        USAGE,
+                    ))
+                }
+            },
+            "--output-format" => {
+                if Format::parse(args.next(), USAGE)? != Format::Plain {
+                    return Err(CliError::usage(
+                        "a drawing can only be printed as plain text, so `--output-format` must be plain",
+                        USAGE,
+                    ));
+                }
+            }
+            _ if input.accept(&arg, &mut args, USAGE)? => {}
+            _ => input.positional(arg, USAGE)?,
+        }
+    }
+    let input = input.finish(USAGE)?;
+    if input.json {
+        return Err(CliError::usage(
+            "a drawing cannot be printed as JSON, so `--json` does not apply",
+            USAGE,
+        ));
+    }
+    let histogram = ConcreteHistogram::new(input.read_bars()?);
+    let rectangle = compute_largest_rectangle(&histogram);
+    print(&histogram, rectangle, width_limit, no_color)
+}
+
+/// Prints the histogram with its largest rectangle drawn in, in colour on a
+/// terminal unless `no_color` is set, followed by the rectangle as `rect`
+/// prints it.
+pub fn print(
+    histogram: &ConcreteHistogram,
+    rectangle: Option<Rectangle>,
+    width_limit: Option<usize>,
+    no_color: bool,
+) -> Result<(), CliError> {
+    let color = !no_color && env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal();
+    let drawing = draw(histogram, rectangle.as_ref(), width_limit, color)?;
+    output::print_line(format!("{drawing}{}", Format::Plain.line(&rectangle)))
+}
+
+/// The most rows a drawing can take, one per unit of the tallest bar's height.
+const MAX_ROWS: i32 = 200;
+
+/// Draws the histogram with the rectangle highlighted, in at most
+/// `width_limit` columns if given. A histogram taller than `MAX_ROWS` is
+/// refused rather than drawn a row at a time.
+fn draw(
+    histogram: &ConcreteHistogram,
+    rectangle: Option<&Rectangle>,
+    width_limit: Option<usize>,
+    color: bool,
+) -> Result<String, CliError> {
+    let tallest = histogram.heights().iter().copied().max().unwrap_or(0);
+    if tallest > MAX_ROWS {
+        // The arguments are fine; it is the input that cannot be drawn.
+        return Err(CliError::parse(
+            "too_tall",
+            format!("a bar of height {tallest} is too tall to draw; at most {MAX_ROWS} rows can be drawn"),
+            false,
+        ));
+    }
+    let options = match width_limit {
+        Some(limit) if histogram.width() > limit => AsciiOptions {
+            // Leaves room for the `...` after the axis.
+            max_columns: Some(limit.saturating_sub(3).max(1)),
+            overflow: Overflow::Truncate,
+            ..AsciiOptions::default()
+        },
+        Some(_) => AsciiOptions {
+            max_columns: None,
+            ..AsciiOptions::default()
+        },
+        None => AsciiOptions::default(),
+    };
+    let Some(rectangle) = rectangle else {
+        return Ok(render::to_ascii_with_options(histogram, &options));
+    };
+    // The largest rectangle always lies under its histogram.
+    let drawing = render::to_ascii_with_rectangle_and_options(histogram, rectangle, &options)
+        .map_err(|misfit| CliError::internal(misfit.to_string()))?;
+    if !color {
+        return Ok(drawing);
+    }
+    let mut colored = String::with_capacity(drawing.len());
+    let mut in_rectangle = false;
+    for c in drawing.chars() {
+        if (c == '*') != in_rectangle {
+            in_rectangle = !in_rectangle;
+            colored.push_str(if in_rectangle {
+                "\x1b[1;33m"
+            } else {
+                "\x1b[0m"
+            });
+        }
+        colored.push(c);
+    }
+    Ok(colored)
+}
@@ -0,0 +1,225 @@
+use crate::commands::render;
+use crate::error::CliError;
+use crate::output::{self, Format};
+use leetcode_largest_rectangle::cached_search::CachedLargestRectangle;
+use leetcode_largest_rectangle::histogram_concrete::OutOfRange;
+use leetcode_largest_rectangle::{ConcreteHistogram, Histogram};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+pub const USAGE: &str = "\
+Usage: largest-rectangle repl
+
+Reads commands from stdin, one per line, to edit a histogram that starts
+with no bars. Each command that changes the histogram prints its largest
+rectangle as `rect` does. A command that cannot be carried out prints an
+error to stderr and changes nothing, and the next line is read as usual.
+
+Commands:
+  set HEIGHT...   Replace every bar with the given bars
+  push HEIGHT     Add a bar after the last one
+  pop             Remove the last bar
+  height N H      Change the height of bar N, counting from 0, to H
+  area            Print the area of the largest rectangle
+  rect            Print the largest rectangle's area, bars and height
+  render          Draw the histogram with its largest rectangle
+  undo            Take back the last change, back to the empty histogram
+  help            Print this help
+  quit            Stop reading commands, as the end of stdin does
+
+Options:
+  -h, --help      Print this help
+
+Example: printf 'set 2 1 5 6 2 3\\nheight 1 4\\nundo\\n' | largest-rectangle repl";
+
+pub fn run(mut args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    if let Some(arg) = args.next() {
+        return match arg.as_str() {
+            "-h" | "--help" => output::print_line(USAGE),
+            _ => Err(CliError::usage(format!("unknown argument `{arg}`"), USAGE)),
+        };
+    }
+    let prompt = io::stdin().is_terminal();
+    let mut session = Session::new();
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        if prompt {
+            let mut stdout = io::stdout().lock();
+            // A failed prompt shows up as a failed answer once one is printed.
+            let _ = write!(stdout, "> ").and_then(|()| stdout.flush());
+        }
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+        let line = line.map_err(|error| CliError::io("stdin", error, false))?;
+        match session.execute(&line) {
+            Ok(Flow::Continue) => {}
+            Ok(Flow::Quit) => return Ok(()),
+            Err(CliError::Validation { message, .. } | CliError::Parse { message, .. }) => {
+                let _ = writeln!(io::stderr(), "error: {message}");
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Whether to read another command.
+enum Flow {
+    Continue,
+    Quit,
+}
+
+/// A change to the histogram. Making one gives back the change that takes it
+/// back again.
+enum Edit {
+    Replace(Vec<i32>),
+    Push(i32),
+    Pop,
+    SetHeight(usize, i32),
+}
+
+/// The histogram being edited, with the changes that led to it.
+struct Session {
+    histogram: CachedLargestRectangle<ConcreteHistogram>,
+    undo: Vec<Edit>,
+}
+
+impl Session {
+    fn new() -> Self {
+        Self {
+            histogram: CachedLargestRectangle::new(ConcreteHistogram::new(Vec::new())),
+            undo: Vec::new(),
+        }
+    }
+
+    /// Carries out one line of input. A mistake in it is a usage error, which
+    /// leaves the histogram as it was.
+    fn execute(&mut self, line: &str) -> Result<Flow, CliError> {
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok(Flow::Continue);
+        };
+        let arguments: Vec<&str> = words.collect();
+        match (command, arguments.as_slice()) {
+            ("set", heights) => {
+                let bars = heights
+                    .iter()
+                    .map(|arg| height(arg))
+                    .collect::<Result<_, _>>()?;
+                self.change(Edit::Replace(bars))?;
+            }
+            ("push", [arg]) => self.change(Edit::Push(height(arg)?))?,
+            ("pop", []) => self.change(Edit::Pop)?,
+            ("height", [position, arg]) => {
+                self.change(Edit::SetHeight(bar_position(position)?, height(arg)?))?
+            }
+            ("area", []) => {
+                let area = self
+                    .histogram
+                    .largest_rectangle()
+                    .map_or(0, |largest| largest.area);
+                output::print_line(area)?
+            }
+            ("rect", []) => self.print_rectangle()?,
+            ("render", []) => {
+                let rectangle = self.histogram.largest_rectangle();
+                render::print(self.histogram.histogram(), rectangle, None, false)?
+            }
+            ("undo", []) => {
+                let Some(edit) = self.undo.pop() else {
+                    return Err(CliError::usage("there is nothing to undo", USAGE));
+                };
+                self.make(edit)?;
+                self.print_rectangle()?
+            }
+            ("help", []) => output::print_line(USAGE)?,
+            ("quit", []) => return Ok(Flow::Quit),
+            _ => {
+                let Some(synopsis) = synopsis(command) else {
+                    return Err(CliError::usage(
+                        format!("`{command}` is not a command; `help` lists them"),
+                        USAGE,
+                    ));
+                };
+                return Err(CliError::usage(
+                    format!(
+                        "`{}` has the wrong arguments; use `{synopsis}`",
+                        line.trim()
+                    ),
+                    USAGE,
+                ));
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Makes a change that `undo` can take back, and prints the new answer.
+    fn change(&mut self, edit: Edit) -> Result<(), CliError> {
+        let undo = self.make(edit)?;
+        self.undo.push(undo);
+        self.print_rectangle()
+    }
+
+    /// Makes `edit` and returns the edit that takes it back, or fails without
+    /// changing anything.
+    fn make(&mut self, edit: Edit) -> Result<Edit, CliError> {
+        Ok(match edit {
+            Edit::Replace(bars) => {
+                let fresh = CachedLargestRectangle::new(ConcreteHistogram::new(bars));
+                let previous = std::mem::replace(&mut self.histogram, fresh).into_inner();
+                Edit::Replace(previous.heights().to_vec())
+            }
+            Edit::Push(height) => {
+                self.histogram.push(height);
+                Edit::Pop
+            }
+            Edit::Pop => match self.histogram.pop() {
+                Some(height) => Edit::Push(height),
+                None => return Err(CliError::usage("there is no bar to pop", USAGE)),
+            },
+            Edit::SetHeight(horizontal_position, height) => {
+                let histogram = self.histogram.histogram();
+                let Some(&previous) = histogram.heights().get(horizontal_position) else {
+                    let width = histogram.width();
+                    let misfit = OutOfRange {
+                        horizontal_position,
+                        width,
+                    };
+                    return Err(CliError::usage(misfit.to_string(), USAGE));
+                };
+                self.histogram.set_height(horizontal_position, height);
+                Edit::SetHeight(horizontal_position, previous)
+            }
+        })
+    }
+
+    fn print_rectangle(&mut self) -> Result<(), CliError> {
+        output::print_line(Format::Plain.line(&self.histogram.largest_rectangle()))
+    }
+}
+
+/// How a command is used, or `None` if there is no such command.
+fn synopsis(command: &str) -> Option<&'static str> {
+    Some(match command {
+        "set" => "set HEIGHT...",
+        "push" => "push HEIGHT",
+        "pop" => "pop",
+        "height" => "height N H",
+        "area" => "area",
+        "rect" => "rect",
+        "render" => "render",
+        "undo" => "undo",
+        "help" => "help",
+        "quit" => "quit",
+        _ => return None,
+    })
+}
+
+fn height(arg: &str) -> Result<i32, CliError> {
+    arg.parse()
+        .map_err(|error| CliError::usage(format!("`{arg}` is not a bar height: {error}"), USAGE))
+}
+
+fn bar_position(arg: &str) -> Result<usize, CliError> {
+    arg.parse()
+        .map_err(|error| CliError::usage(format!("`{arg}` is not a bar position: {error}"), USAGE))
+}
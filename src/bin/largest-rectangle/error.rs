@@ -0,0 +1,179 @@
+//! Reporting what went wrong, and the exit code that says so.
+
+use leetcode_largest_rectangle::parse::json;
+use std::io::{self, Write};
+
+/// Why a command failed. Each kind has its own exit code, which the
+/// top-level help documents.
+#[derive(Debug)]
+pub enum CliError {
+    /// Wrong arguments, always reported as text followed by the help of the
+    /// command they were given to. An empty message reports only the help.
+    Validation {
+        message: String,
+        usage: &'static str,
+    },
+    /// Input that could not be parsed.
+    Parse {
+        /// A short name for the kind of failure, reported under `--json`.
+        kind: &'static str,
+        /// The input at fault, if there is one to name.
+        source: Option<String>,
+        /// The 1-based line within the input, and the column within that
+        /// line.
+        line: Option<usize>,
+        column: Option<usize>,
+        /// The text that could not be parsed, if the failure is about one
+        /// piece of it.
+        token: Option<String>,
+        message: String,
+        /// Whether to report the failure as a JSON object.
+        json: bool,
+    },
+    /// An input that could not be opened or read, or output that could not
+    /// be written.
+    Io {
+        source: String,
+        error: io::Error,
+        json: bool,
+    },
+    /// A bug: something the tool relies on did not hold.
+    Internal { message: String },
+}
+
+impl CliError {
+    pub fn usage(message: impl Into<String>, usage: &'static str) -> Self {
+        CliError::Validation {
+            message: message.into(),
+            usage,
+        }
+    }
+
+    /// Input that could not be parsed, reported as JSON if `json` is set.
+    pub fn parse(kind: &'static str, message: impl Into<String>, json: bool) -> Self {
+        CliError::Parse {
+            kind,
+            source: None,
+            line: None,
+            column: None,
+            token: None,
+            message: message.into(),
+            json,
+        }
+    }
+
+    pub fn io(source: &str, error: io::Error, json: bool) -> Self {
+        CliError::Io {
+            source: source.to_string(),
+            error,
+            json,
+        }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        CliError::Internal {
+            message: message.into(),
+        }
+    }
+
+    /// Places a parse failure within an input. Other failures are left as
+    /// they are.
+    pub fn at(mut self, input: &str, at_line: Option<usize>, at_column: Option<usize>) -> Self {
+        if let CliError::Parse {
+            source,
+            line,
+            column,
+            ..
+        } = &mut self
+        {
+            *source = Some(input.to_string());
+            (*line, *column) = (at_line, at_column);
+        }
+        self
+    }
+
+    /// Names the text a parse failure is about. Other failures are left as
+    /// they are.
+    pub fn token(mut self, text: impl Into<String>) -> Self {
+        if let CliError::Parse { token, .. } = &mut self {
+            *token = Some(text.into());
+        }
+        self
+    }
+
+    pub fn code(&self) -> u8 {
+        match self {
+            CliError::Validation { .. } => 1,
+            CliError::Parse { .. } => 2,
+            CliError::Io { .. } => 3,
+            CliError::Internal { .. } => 4,
+        }
+    }
+
+    /// Writes the failure to stderr, never stdout, so it cannot be mistaken
+    /// for an answer.
+    pub fn report(&self) {
+        let text = match self {
+            CliError::Validation { message, usage } if message.is_empty() => usage.to_string(),
+            CliError::Validation { message, usage } => format!("error: {message}\n\n{usage}"),
+            CliError::Parse {
+                kind,
+                source,
+                line,
+                column,
+                token,
+                message,
+                json: true,
+            } => {
+                let mut text = format!("{{\"error\":{}", json::quote(kind));
+                if let Some(source) = source {
+                    text += &format!(",\"source\":{}", json::quote(source));
+                }
+                if let Some(line) = line {
+                    text += &format!(",\"line\":{line}");
+                }
+                if let Some(column) = column {
+                    text += &format!(",\"column\":{column}");
+                }
+                if let Some(token) = token {
+                    text += &format!(",\"token\":{}", json::quote(token));
+                }
+                text + &format!(",\"message\":{}}}", json::quote(message))
+            }
+            CliError::Parse {
+                source,
+                line,
+                column,
+                message,
+                ..
+            } => {
+                let mut text = "error: ".to_string();
+                if let Some(source) = source {
+                    text += &format!("{source}: ");
+                }
+                match (line, column) {
+                    (Some(line), Some(column)) => {
+                        text += &format!("line {line}, column {column}: ")
+                    }
+                    (Some(line), None) => text += &format!("line {line}: "),
+                    _ => {}
+                }
+                text + message
+            }
+            CliError::Io {
+                source,
+                error,
+                json: true,
+            } => format!(
+                "{{\"error\":\"io\",\"source\":{},\"message\":{}}}",
+                json::quote(source),
+                json::quote(&error.to_string())
+            ),
+            CliError::Io { source, error, .. } => format!("error: {source}: {error}"),
+            CliError::Internal { message } => {
+                format!("error: internal error, which is a bug in largest-rectangle: {message}")
+            }
+        };
+        let _ = writeln!(io::stderr(), "{text}");
+    }
+}
@@ -0,0 +1,80 @@
+mod batch;
+mod commands;
+mod error;
+mod output;
+mod source;
+
+use commands::{area, generate, matrix, rect, render, repl};
+use error::CliError;
+use std::env;
+use std::ffi::OsString;
+use std::panic;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: largest-rectangle [COMMAND] [OPTIONS] [INPUT...]
+
+Finds the largest rectangle under a histogram, or of 1 cells in a grid.
+
+Commands:
+  area      Print the area of the largest rectangle (the default)
+  rect      Print the largest rectangle's area, bars and height
+  render    Draw the histogram with its largest rectangle
+  matrix    Print the largest rectangle of 1 cells in a grid
+  generate  Print the heights of a random or patterned histogram
+  repl      Edit a histogram with commands read from stdin, printing its
+            largest rectangle after every change
+
+Run `largest-rectangle COMMAND --help` for a command's options. Exits with 1
+for wrong arguments, 2 for input that cannot be parsed or drawn, 3 for input
+that cannot be read or output that cannot be written, and 4 for an internal
+error, which is a bug. Errors are printed to stderr, never stdout.
+
+Example: largest-rectangle 2 1 5 6 2 3";
+
+fn main() -> ExitCode {
+    // A panic is a bug, so it is reported as an internal error rather than
+    // with a backtrace.
+    panic::set_hook(Box::new(|info| {
+        CliError::internal(info.to_string()).report();
+    }));
+    match panic::catch_unwind(|| run(env::args_os().skip(1))) {
+        Ok(Ok(())) => ExitCode::SUCCESS,
+        Ok(Err(error)) => {
+            error.report();
+            ExitCode::from(error.code())
+        }
+        // The hook has already reported the panic.
+        Err(_) => ExitCode::from(CliError::internal("").code()),
+    }
+}
+
+fn run(args: impl Iterator<Item = OsString>) -> Result<(), CliError> {
+    let args: Vec<String> = args
+        .map(|arg| {
+            arg.into_string().map_err(|arg| {
+                CliError::usage(
+                    format!("`{}` is not valid UTF-8", arg.to_string_lossy()),
+                    USAGE,
+                )
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    let mut args = args.into_iter().peekable();
+    match args.peek().cloned().as_deref() {
+        None => Err(CliError::usage("", USAGE)),
+        Some("-h" | "--help") => output::print_line(USAGE),
+        Some("area") => area::run(args.skip(1), true),
+        Some("rect") => rect::run(args.skip(1)),
+        Some("render") => render::run(args.skip(1)),
+        Some("matrix") => matrix::run(args.skip(1)),
+        Some("generate") => generate::run(args.skip(1)),
+        Some("repl") => repl::run(args.skip(1)),
+        // Drawing was the `--render` option before it was a command, and
+        // still works that way without one.
+        Some(_) if args.clone().any(|arg| arg == "--render") => {
+            render::run(args.filter(|arg| arg != "--render"))
+        }
+        Some(_) => area::run(args, false),
+    }
+}
@@ -0,0 +1,318 @@
+//! Printing answers as plain text, JSON, CSV or TSV.
+
+use crate::error::CliError;
+use leetcode_largest_rectangle::matrix::MatrixRectangle;
+use leetcode_largest_rectangle::Rectangle;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+/// The help for `--output-format`, for each command's usage.
+macro_rules! output_options_help {
+    () => {
+        "  --output-format FORMAT
+                  Print each answer as plain text, json, csv or tsv; csv
+                  and tsv start with a header naming the columns. The
+                  default is plain, or json with --json
+"
+    };
+}
+pub(crate) use output_options_help;
+
+/// The shape of the answers printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Plain,
+    Json,
+    Csv,
+    Tsv,
+}
+
+impl Format {
+    /// Parses the value of `--output-format`.
+    pub fn parse(name: Option<String>, usage: &'static str) -> Result<Self, CliError> {
+        match name.as_deref() {
+            Some("plain") => Ok(Format::Plain),
+            Some("json") => Ok(Format::Json),
+            Some("csv") => Ok(Format::Csv),
+            Some("tsv") => Ok(Format::Tsv),
+            Some(other) => Err(CliError::usage(
+                format!("`{other}` is not an output format; use plain, json, csv or tsv"),
+                usage,
+            )),
+            None => Err(CliError::usage("`--output-format` needs a format", usage)),
+        }
+    }
+
+    /// The format chosen with `--output-format`, or else JSON if the input
+    /// is, and plain text otherwise.
+    pub fn chosen(format: Option<Format>, json: bool) -> Self {
+        match format {
+            Some(format) => format,
+            None if json => Format::Json,
+            None => Format::Plain,
+        }
+    }
+
+    /// Prints a single answer, after its header if the format has one.
+    pub fn print<A: Answer>(self, answer: &A) -> Result<(), CliError> {
+        if let Some(header) = self.header::<A>() {
+            print_line(header)?;
+        }
+        print_line(self.line(answer))
+    }
+
+    /// The line printed once before the answers, naming their columns.
+    pub fn header<A: Answer>(self) -> Option<String> {
+        let separator = self.separator()?;
+        Some(A::COLUMNS.join(separator))
+    }
+
+    /// The line for one answer.
+    pub fn line<A: Answer>(self, answer: &A) -> String {
+        let values = answer.values();
+        match self {
+            Format::Plain => answer.plain(),
+            Format::Json => {
+                let members: Vec<String> = A::COLUMNS
+                    .iter()
+                    .zip(&values)
+                    .map(|(column, value)| {
+                        format!("\"{column}\":{}", value.as_deref().unwrap_or("null"))
+                    })
+                    .collect();
+                format!("{{{}}}", members.join(","))
+            }
+            Format::Csv | Format::Tsv => {
+                let cells: Vec<&str> = values
+                    .iter()
+                    .map(|value| value.as_deref().unwrap_or(""))
+                    .collect();
+                cells.join(self.separator().expect("CSV and TSV have a separator"))
+            }
+        }
+    }
+
+    /// The line standing in for an answer that could not be found, which
+    /// keeps each answer in a batch on the same line as its input: empty in
+    /// plain text and JSON, and a row of empty cells in CSV and TSV.
+    pub fn missing<A: Answer>(self) -> String {
+        match self.separator() {
+            Some(separator) => vec![""; A::COLUMNS.len()].join(separator),
+            None => String::new(),
+        }
+    }
+
+    fn separator(self) -> Option<&'static str> {
+        match self {
+            Format::Plain | Format::Json => None,
+            Format::Csv => Some(","),
+            Format::Tsv => Some("\t"),
+        }
+    }
+}
+
+/// Prints `text` and a newline. Stdout having been closed, as when piped
+/// into `head`, is not a failure: nothing more can be printed, so the command
+/// just stops.
+pub fn print_line(text: impl Display) -> Result<(), CliError> {
+    match writeln!(io::stdout().lock(), "{text}") {
+        Err(error) if error.kind() != io::ErrorKind::BrokenPipe => {
+            Err(CliError::io("stdout", error, false))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A command's answer, as named values and as plain text.
+pub trait Answer {
+    /// The names of the values, as CSV and TSV columns and JSON keys.
+    const COLUMNS: &'static [&'static str];
+
+    /// The values in the order of `COLUMNS`, with `None` for a value that
+    /// does not exist.
+    fn values(&self) -> Vec<Option<String>>;
+
+    fn plain(&self) -> String;
+}
+
+/// The area of the largest rectangle.
+pub struct Area(pub u64);
+
+impl Answer for Area {
+    const COLUMNS: &'static [&'static str] = &["area"];
+
+    fn values(&self) -> Vec<Option<String>> {
+        vec![Some(self.0.to_string())]
+    }
+
+    fn plain(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// The largest rectangle under a histogram. Without one the area is zero and
+/// the other values do not exist.
+impl Answer for Option<Rectangle> {
+    const COLUMNS: &'static [&'static str] = &["area", "left", "right", "height"];
+
+    fn values(&self) -> Vec<Option<String>> {
+        match self {
+            Some(rectangle) => vec![
+                Some(rectangle.area.to_string()),
+                Some(rectangle.left.to_string()),
+                Some(rectangle.right.to_string()),
+                Some(rectangle.height.to_string()),
+            ],
+            None => vec![Some("0".to_string()), None, None, None],
+        }
+    }
+
+    fn plain(&self) -> String {
+        match self {
+            Some(rectangle) => format!(
+                "{} (bars {}..={}, height {})",
+                rectangle.area, rectangle.left, rectangle.right, rectangle.height
+            ),
+            None => "0".to_string(),
+        }
+    }
+}
+
+/// The largest rectangle of set cells in a grid. Without one the area is zero
+/// and the other values do not exist.
+impl Answer for Option<MatrixRectangle> {
+    const COLUMNS: &'static [&'static str] = &["area", "top", "left", "bottom", "right"];
+
+    fn values(&self) -> Vec<Option<String>> {
+        match self {
+            Some(rectangle) => vec![
+                Some(rectangle.area.to_string()),
+                Some(rectangle.top.to_string()),
+                Some(rectangle.left.to_string()),
+                Some(rectangle.bottom.to_string()),
+                Some(rectangle.right.to_string()),
+            ],
+            None => vec![Some("0".to_string()), None, None, None, None],
+        }
+    }
+
+    fn plain(&self) -> String {
+        match self {
+            Some(rectangle) => format!(
+                "{} (rows {}..={}, columns {}..={})",
+                rectangle.area, rectangle.top, rectangle.bottom, rectangle.left, rectangle.right
+            ),
+            None => "0".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USAGE: &str = "usage";
+
+    fn leetcode() -> Option<Rectangle> {
+        Some(Rectangle {
+            left: 2,
+            right: 3,
+            height: 5,
+            area: 10,
+        })
+    }
+
+    #[test]
+    fn test_formats_are_parsed_by_name() {
+        for (name, format) in [
+            ("plain", Format::Plain),
+            ("json", Format::Json),
+            ("csv", Format::Csv),
+            ("tsv", Format::Tsv),
+        ] {
+            assert_eq!(
+                Format::parse(Some(name.to_string()), USAGE).ok(),
+                Some(format)
+            );
+        }
+        assert!(Format::parse(Some("xml".to_string()), USAGE).is_err());
+        assert!(Format::parse(None, USAGE).is_err());
+    }
+
+    #[test]
+    fn test_plain_format() {
+        let format = Format::Plain;
+        assert_eq!(format.header::<Option<Rectangle>>(), None);
+        assert_eq!(format.line(&leetcode()), "10 (bars 2..=3, height 5)");
+        assert_eq!(format.line(&None::<Rectangle>), "0");
+        assert_eq!(format.line(&Area(10)), "10");
+        assert_eq!(format.missing::<Option<Rectangle>>(), "");
+    }
+
+    #[test]
+    fn test_json_format() {
+        let format = Format::Json;
+        assert_eq!(format.header::<Option<Rectangle>>(), None);
+        assert_eq!(
+            format.line(&leetcode()),
+            r#"{"area":10,"left":2,"right":3,"height":5}"#
+        );
+        assert_eq!(
+            format.line(&None::<Rectangle>),
+            r#"{"area":0,"left":null,"right":null,"height":null}"#
+        );
+        assert_eq!(format.line(&Area(7)), r#"{"area":7}"#);
+        assert_eq!(format.missing::<Area>(), "");
+    }
+
+    #[test]
+    fn test_csv_format() {
+        let format = Format::Csv;
+        assert_eq!(
+            format.header::<Option<Rectangle>>().as_deref(),
+            Some("area,left,right,height")
+        );
+        assert_eq!(format.line(&leetcode()), "10,2,3,5");
+        assert_eq!(format.line(&None::<Rectangle>), "0,,,");
+        assert_eq!(format.missing::<Option<Rectangle>>(), ",,,");
+        assert_eq!(format.header::<Area>().as_deref(), Some("area"));
+        assert_eq!(format.missing::<Area>(), "");
+    }
+
+    #[test]
+    fn test_tsv_format() {
+        let format = Format::Tsv;
+        assert_eq!(
+            format.header::<Option<Rectangle>>().as_deref(),
+            Some("area\tleft\tright\theight")
+        );
+        assert_eq!(format.line(&leetcode()), "10\t2\t3\t5");
+        assert_eq!(format.line(&None::<Rectangle>), "0\t\t\t");
+        assert_eq!(format.missing::<Option<Rectangle>>(), "\t\t\t");
+    }
+
+    #[test]
+    fn test_matrix_rectangles_in_every_format() {
+        let rectangle = Some(MatrixRectangle {
+            top: 1,
+            left: 2,
+            bottom: 2,
+            right: 4,
+            area: 6,
+        });
+        assert_eq!(
+            Format::Plain.line(&rectangle),
+            "6 (rows 1..=2, columns 2..=4)"
+        );
+        assert_eq!(
+            Format::Json.line(&rectangle),
+            r#"{"area":6,"top":1,"left":2,"bottom":2,"right":4}"#
+        );
+        assert_eq!(Format::Csv.line(&rectangle), "6,1,2,2,4");
+        assert_eq!(Format::Tsv.line(&None::<MatrixRectangle>), "0\t\t\t\t");
+        assert_eq!(
+            Format::Csv.header::<Option<MatrixRectangle>>().as_deref(),
+            Some("area,top,left,bottom,right")
+        );
+    }
+}
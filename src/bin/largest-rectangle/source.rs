@@ -0,0 +1,485 @@
+//! The input options shared by every command that reads a histogram or a
+//! grid, and reading that input from arguments, stdin, a file, JSON or CSV.
+
+use crate::error::CliError;
+use leetcode_largest_rectangle::matrix::BinaryMatrix;
+use leetcode_largest_rectangle::parse::csv::{CsvError, CsvReader, Record};
+use leetcode_largest_rectangle::parse::json;
+use leetcode_largest_rectangle::parse::plain::{self, InputError};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+
+/// The help for the options that `InputArgs` takes, for each command's usage.
+macro_rules! input_options_help {
+    () => {
+        "  --stdin         Read the input from standard input
+  --input PATH    Read the input from a file, or from standard input if PATH
+                  is -
+  --csv PATH      Read the input from a CSV file, or from standard input if
+                  PATH is -
+  --column COL    The CSV column to read, by 1-based index or, with
+                  --has-header, by name; the first column by default
+  --has-header    Treat the first CSV row as column names, not input
+  --skip-invalid  Skip CSV rows holding a cell that is empty or invalid,
+                  rather than failing
+  --json          Read the input as JSON, from standard input if no other
+                  input is given, and print the result and any error as a
+                  JSON object on one line
+"
+    };
+}
+pub(crate) use input_options_help;
+
+/// Where the input comes from.
+enum Source {
+    Arguments(Vec<String>),
+    Stdin,
+    File(String),
+    Csv(CsvOptions),
+}
+
+struct CsvOptions {
+    path: String,
+    column: Column,
+    has_header: bool,
+    skip_invalid: bool,
+}
+
+/// A CSV column, by 0-based index or by the name in its header.
+enum Column {
+    Index(usize),
+    Name(String),
+}
+
+/// The input options and values seen so far in a command's arguments.
+#[derive(Default)]
+pub struct InputArgs {
+    source: Option<Source>,
+    arguments: Vec<String>,
+    json: bool,
+    csv_path: Option<String>,
+    column: Option<String>,
+    has_header: bool,
+    skip_invalid: bool,
+}
+
+impl InputArgs {
+    /// Takes `arg` if it is an input option, along with any value it needs
+    /// from `args`, and returns whether it did.
+    pub fn accept(
+        &mut self,
+        arg: &str,
+        args: &mut impl Iterator<Item = String>,
+        usage: &'static str,
+    ) -> Result<bool, CliError> {
+        let mut value = |what: &str| {
+            args.next()
+                .ok_or_else(|| CliError::usage(format!("`{arg}` needs {what}"), usage))
+        };
+        match arg {
+            "--stdin" => (self.source, self.csv_path) = (Some(Source::Stdin), None),
+            "--input" => {
+                let path = value("a path")?;
+                self.source = Some(if path == "-" {
+                    Source::Stdin
+                } else {
+                    Source::File(path)
+                });
+                self.csv_path = None;
+            }
+            "--csv" => {
+                self.csv_path = Some(value("a path")?);
+                self.source = None;
+            }
+            "--column" => self.column = Some(value("a column")?),
+            "--has-header" => self.has_header = true,
+            "--skip-invalid" => self.skip_invalid = true,
+            "--json" => self.json = true,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Takes an argument that no option took as a value of the input, or
+    /// fails if it looks like an option.
+    pub fn positional(&mut self, arg: String, usage: &'static str) -> Result<(), CliError> {
+        if arg.starts_with("--") {
+            return Err(CliError::usage(format!("unknown option `{arg}`"), usage));
+        }
+        self.arguments.push(arg);
+        Ok(())
+    }
+
+    /// Whether no input option but `--json`, and no value, was given.
+    pub fn is_empty(&self) -> bool {
+        self.source.is_none()
+            && self.arguments.is_empty()
+            && self.csv_path.is_none()
+            && self.column.is_none()
+            && !self.has_header
+            && !self.skip_invalid
+    }
+
+    pub fn json(&self) -> bool {
+        self.json
+    }
+
+    /// Whether `--column` was given, for commands that read every column.
+    pub fn has_column(&self) -> bool {
+        self.column.is_some()
+    }
+
+    /// Settles where the input comes from once every argument has been seen.
+    pub fn finish(self, usage: &'static str) -> Result<Input, CliError> {
+        let csv_only = self.column.is_some() || self.has_header || self.skip_invalid;
+        let source = match (self.csv_path, self.source) {
+            (Some(path), None) => {
+                let column = match self.column {
+                    None => Column::Index(0),
+                    Some(column) => match column.parse::<usize>() {
+                        Ok(0) => {
+                            return Err(CliError::usage("CSV columns are numbered from 1", usage))
+                        }
+                        Ok(index) => Column::Index(index - 1),
+                        Err(_) if self.has_header => Column::Name(column),
+                        Err(_) => {
+                            return Err(CliError::usage(
+                                "a column can only be named with `--has-header`",
+                                usage,
+                            ))
+                        }
+                    },
+                };
+                Some(Source::Csv(CsvOptions {
+                    path,
+                    column,
+                    has_header: self.has_header,
+                    skip_invalid: self.skip_invalid,
+                }))
+            }
+            _ if csv_only => {
+                return Err(CliError::usage(
+                    "`--column`, `--has-header` and `--skip-invalid` need `--csv`",
+                    usage,
+                ));
+            }
+            (_, source) => source,
+        };
+        let source = match source {
+            Some(_) if !self.arguments.is_empty() => {
+                return Err(CliError::usage(
+                    "input cannot be given both as arguments and from elsewhere",
+                    usage,
+                ))
+            }
+            Some(source) => source,
+            None if self.arguments.is_empty() && self.json => Source::Stdin,
+            None if self.arguments.is_empty() => return Err(CliError::usage("", usage)),
+            None => Source::Arguments(self.arguments),
+        };
+        Ok(Input {
+            source,
+            json: self.json,
+        })
+    }
+}
+
+/// A command's input, ready to be read.
+pub struct Input {
+    source: Source,
+    /// Whether the input is JSON, and the output and errors should be too.
+    pub json: bool,
+}
+
+impl Input {
+    /// Reads bar heights: whitespace-separated in arguments, stdin or a file,
+    /// a JSON array of heights or an object with a `bars` array, or one
+    /// column of a CSV file.
+    pub fn read_bars(self) -> Result<Vec<i32>, CliError> {
+        let json = self.json;
+        match self.source {
+            Source::Arguments(arguments) => arguments
+                .iter()
+                .map(|arg| {
+                    arg.parse().map_err(|error| {
+                        CliError::parse(
+                            "parse",
+                            format!("`{arg}` is not a bar height: {error}"),
+                            json,
+                        )
+                        .token(arg)
+                    })
+                })
+                .collect(),
+            Source::Stdin => read_bars("stdin", Ok(io::stdin().lock()), json),
+            Source::File(path) => read_bars(&path, open(&path), json),
+            Source::Csv(options) if options.path == "-" => {
+                read_csv_bars("stdin", Ok(io::stdin().lock()), &options, json)
+            }
+            Source::Csv(options) => {
+                read_csv_bars(&options.path, open(&options.path), &options, json)
+            }
+        }
+    }
+
+    /// Reads a grid of cells written as `0` or `1`: one row per argument or
+    /// line of stdin or a file, optionally separated by whitespace, a JSON
+    /// array of rows or an object with a `rows` array, each row an array of
+    /// `0`, `1` or booleans, or the rows of a CSV file.
+    pub fn read_grid(self) -> Result<BinaryMatrix, CliError> {
+        let json = self.json;
+        let rows = match self.source {
+            Source::Arguments(arguments) => plain::read_rows(arguments.join("\n").as_bytes())
+                .map_err(|error| match input_error(error, "arguments", json) {
+                    // Each argument is a row, so there is no input to name.
+                    CliError::Parse {
+                        kind,
+                        line: Some(line),
+                        column,
+                        token,
+                        message,
+                        json,
+                        ..
+                    } => {
+                        let place = match column {
+                            Some(column) => format!("row {line}, column {column}"),
+                            None => format!("row {line}"),
+                        };
+                        CliError::Parse {
+                            kind,
+                            source: None,
+                            line: None,
+                            column: None,
+                            token,
+                            message: format!("{place}: {message}"),
+                            json,
+                        }
+                    }
+                    error => error,
+                }),
+            Source::Stdin => read_grid("stdin", Ok(io::stdin().lock()), json),
+            Source::File(path) => read_grid(&path, open(&path), json),
+            Source::Csv(options) if options.path == "-" => {
+                read_csv_grid("stdin", Ok(io::stdin().lock()), &options, json)
+            }
+            Source::Csv(options) => {
+                read_csv_grid(&options.path, open(&options.path), &options, json)
+            }
+        }?;
+        // Ragged rows were rejected as they were read.
+        BinaryMatrix::new(rows).map_err(|error| CliError::internal(error.to_string()))
+    }
+}
+
+fn open(path: &str) -> io::Result<BufReader<File>> {
+    File::open(path).map(BufReader::new)
+}
+
+/// Reads the bars from an opened input, as whitespace-separated heights or
+/// as JSON. A bad height or bad JSON fails with exit code 2, and an input that
+/// could not be opened or read with exit code 3.
+fn read_bars<R: BufRead>(
+    name: &str,
+    reader: io::Result<R>,
+    json: bool,
+) -> Result<Vec<i32>, CliError> {
+    let reader = reader.map_err(|error| CliError::io(name, error, json))?;
+    if json {
+        let value = read_json(name, reader)?;
+        return json::bars(value)
+            .map_err(|message| CliError::parse("schema", message, json).at(name, None, None));
+    }
+    plain::read_heights(reader).map_err(|error| input_error(error, name, json))
+}
+
+/// Reads a grid from an opened input, as lines of cells or as JSON.
+fn read_grid<R: BufRead>(
+    name: &str,
+    reader: io::Result<R>,
+    json: bool,
+) -> Result<Vec<Vec<bool>>, CliError> {
+    let reader = reader.map_err(|error| CliError::io(name, error, json))?;
+    if json {
+        let value = read_json(name, reader)?;
+        return json::rows(value)
+            .map_err(|message| CliError::parse("schema", message, json).at(name, None, None));
+    }
+    plain::read_rows(reader).map_err(|error| input_error(error, name, json))
+}
+
+fn read_json<R: BufRead>(name: &str, mut reader: R) -> Result<json::Value, CliError> {
+    let mut text = Vec::new();
+    reader
+        .read_to_end(&mut text)
+        .map_err(|error| CliError::io(name, error, true))?;
+    json::parse(&text).map_err(|error| {
+        CliError::parse("syntax", error.message, true).at(
+            name,
+            Some(error.line),
+            Some(error.column),
+        )
+    })
+}
+
+fn input_error(error: InputError, name: &str, json: bool) -> CliError {
+    let (message, line, column, token) = match error {
+        InputError::Io(error) => return CliError::io(name, error, json),
+        InputError::Parse {
+            line,
+            column,
+            token,
+        } => (
+            format!("`{token}` is not a bar height"),
+            line,
+            Some(column),
+            Some(token),
+        ),
+        InputError::Cell { line, column, cell } => (
+            format!("`{cell}` is not a cell, which must be 0 or 1"),
+            line,
+            Some(column),
+            Some(cell.to_string()),
+        ),
+        InputError::Ragged {
+            line,
+            cells,
+            expected,
+        } => (
+            format!("row has {cells} cells, but the first row has {expected}"),
+            line,
+            None,
+            None,
+        ),
+    };
+    let error = CliError::parse("parse", message, json).at(name, Some(line), column);
+    match token {
+        Some(token) => error.token(token),
+        None => error,
+    }
+}
+
+/// Reads a CSV input's records, numbered from one, after the header if it
+/// has one.
+struct CsvRecords<'a, R> {
+    name: &'a str,
+    json: bool,
+    records: std::iter::Enumerate<CsvReader<R>>,
+}
+
+impl<'a, R: BufRead> CsvRecords<'a, R> {
+    fn new(name: &'a str, reader: io::Result<R>, json: bool) -> Result<Self, CliError> {
+        let reader = reader.map_err(|error| CliError::io(name, error, json))?;
+        Ok(Self {
+            name,
+            json,
+            records: CsvReader::new(reader).enumerate(),
+        })
+    }
+
+    fn next(&mut self) -> Result<Option<(usize, Record)>, CliError> {
+        let (message, line) = match self.records.next() {
+            None => return Ok(None),
+            Some((row, Ok(record))) => return Ok(Some((row + 1, record))),
+            Some((_, Err(CsvError::Io(error)))) => {
+                return Err(CliError::io(self.name, error, self.json))
+            }
+            Some((_, Err(CsvError::UnterminatedQuote { line }))) => {
+                ("quoted field has no closing quote", line)
+            }
+            Some((_, Err(CsvError::TextAfterQuote { line }))) => {
+                ("text after a quoted field's closing quote", line)
+            }
+        };
+        Err(self.error(Some(line), message))
+    }
+
+    fn error(&self, line: Option<usize>, message: impl Into<String>) -> CliError {
+        CliError::parse("csv", message, self.json).at(self.name, line, None)
+    }
+}
+
+/// Reads the bars from one column of a CSV input. A bad cell in that column,
+/// or a row without it, fails with exit code 2 unless such rows are skipped.
+fn read_csv_bars<R: BufRead>(
+    name: &str,
+    reader: io::Result<R>,
+    options: &CsvOptions,
+    json: bool,
+) -> Result<Vec<i32>, CliError> {
+    let mut records = CsvRecords::new(name, reader, json)?;
+    let index = match &options.column {
+        Column::Index(index) => {
+            if options.has_header {
+                records.next()?;
+            }
+            *index
+        }
+        Column::Name(column) => {
+            let header = records.next()?.map(|(_, header)| header.fields);
+            header
+                .and_then(|fields| fields.iter().position(|field| field == column))
+                .ok_or_else(|| records.error(None, format!("no column is named `{column}`")))?
+        }
+    };
+    let mut bars = Vec::new();
+    while let Some((row, record)) = records.next()? {
+        let height = match record.fields.get(index).map(|cell| cell.trim()) {
+            None => Err(format!("row {row} has no column {}", index + 1)),
+            Some("") => Err(format!("row {row} has an empty cell")),
+            Some(cell) => cell
+                .parse()
+                .map_err(|_| format!("row {row}: `{cell}` is not a bar height")),
+        };
+        match height {
+            Ok(height) => bars.push(height),
+            Err(_) if options.skip_invalid => {}
+            Err(message) => return Err(records.error(Some(record.line), message)),
+        }
+    }
+    Ok(bars)
+}
+
+/// Reads a grid with one row per CSV record, each cell `0` or `1`. A row with
+/// another cell, or a different number of cells from the first row, fails
+/// with exit code 2 unless such rows are skipped.
+fn read_csv_grid<R: BufRead>(
+    name: &str,
+    reader: io::Result<R>,
+    options: &CsvOptions,
+    json: bool,
+) -> Result<Vec<Vec<bool>>, CliError> {
+    let mut records = CsvRecords::new(name, reader, json)?;
+    if options.has_header {
+        records.next()?;
+    }
+    let mut rows: Vec<Vec<bool>> = Vec::new();
+    while let Some((row, record)) = records.next()? {
+        let cells: Result<Vec<bool>, String> = record
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| match cell.trim() {
+                "0" => Ok(false),
+                "1" => Ok(true),
+                cell => Err(format!(
+                    "row {row}, column {}: `{cell}` is not a cell, which must be 0 or 1",
+                    index + 1
+                )),
+            })
+            .collect();
+        let cells = cells.and_then(|cells| match rows.first() {
+            Some(first) if first.len() != cells.len() => Err(format!(
+                "row {row} has {} cells, but the first row has {}",
+                cells.len(),
+                first.len()
+            )),
+            _ => Ok(cells),
+        });
+        match cells {
+            Ok(cells) => rows.push(cells),
+            Err(_) if options.skip_invalid => {}
+            Err(message) => return Err(records.error(Some(record.line), message)),
+        }
+    }
+    Ok(rows)
+}
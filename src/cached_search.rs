@@ -0,0 +1,111 @@
+use crate::histogram::{Height, Histogram, HistogramMut};
+use crate::histogram_concrete::ConcreteHistogram;
+use crate::rectangle::Rectangle;
+use crate::square_search::{compute_largest_rectangle, largest_rectangle_through, rank};
+use std::cmp::Ordering;
+
+/// Keeps the largest rectangle of a histogram that is edited one bar at a time.
+///
+/// An edit that cannot change the answer, such as lowering a bar outside the
+/// largest rectangle, leaves the cached rectangle in place. Raising a bar
+/// outside it only needs the best rectangle through that bar, which is found
+/// by walking outwards from it. Any other edit clears the cache, and the next
+/// query recomputes the answer.
+pub struct CachedLargestRectangle<H, T: Height = i32> {
+    histogram: H,
+    largest_rectangle: Option<Option<Rectangle<T>>>,
+}
+
+impl<T: Height, H: HistogramMut<T>> CachedLargestRectangle<H, T> {
+    pub fn new(histogram: H) -> Self {
+        Self {
+            histogram,
+            largest_rectangle: None,
+        }
+    }
+
+    pub fn histogram(&self) -> &H {
+        &self.histogram
+    }
+
+    pub fn into_inner(self) -> H {
+        self.histogram
+    }
+
+    /// Returns the same rectangle as `compute_largest_rectangle` on the
+    /// histogram as it currently stands.
+    pub fn largest_rectangle(&mut self) -> Option<Rectangle<T>> {
+        let histogram = &self.histogram;
+        *self
+            .largest_rectangle
+            .get_or_insert_with(|| compute_largest_rectangle(histogram))
+    }
+
+    pub fn set_height(&mut self, horizontal_position: usize, height: T) {
+        let previous_height = self.histogram.height_at(horizontal_position);
+        self.histogram.set_height(horizontal_position, height);
+        if let Some(largest) = self.largest_rectangle {
+            self.largest_rectangle =
+                self.repair(largest, horizontal_position, height.cmp(&previous_height));
+        }
+    }
+
+    /// Returns the largest rectangle after the bar at `x_pos` moved in the
+    /// given direction, or `None` if it has to be recomputed.
+    fn repair(
+        &self,
+        largest: Option<Rectangle<T>>,
+        x_pos: usize,
+        change: Ordering,
+    ) -> Option<Option<Rectangle<T>>> {
+        let covers_bar =
+            largest.is_some_and(|largest| largest.left <= x_pos && x_pos <= largest.right);
+        match change {
+            Ordering::Equal => Some(largest),
+            Ordering::Less if covers_bar => None,
+            Ordering::Less => Some(largest),
+            Ordering::Greater => {
+                // Only rectangles through the raised bar have grown. When the
+                // old answer was one of them, rectangles avoiding the bar may
+                // tie with it, so only a strictly larger area is conclusive.
+                let through_bar = largest_rectangle_through(&self.histogram, x_pos);
+                match largest {
+                    None => Some(through_bar),
+                    Some(largest) if !covers_bar => Some(
+                        through_bar
+                            .filter(|through_bar| rank(through_bar) > rank(&largest))
+                            .or(Some(largest)),
+                    ),
+                    Some(largest) => through_bar
+                        .filter(|through_bar| through_bar.area > largest.area)
+                        .map(Some),
+                }
+            }
+        }
+    }
+}
+
+impl<T: Height> CachedLargestRectangle<ConcreteHistogram<T>, T> {
+    /// Appends a bar. Only rectangles through the new bar are new, so a
+    /// cached answer is kept unless the best of those beats it.
+    pub fn push(&mut self, height: T) {
+        self.histogram.push(height);
+        if let Some(largest) = self.largest_rectangle {
+            let through_bar =
+                largest_rectangle_through(&self.histogram, self.histogram.width() - 1);
+            self.largest_rectangle = Some(largest.into_iter().chain(through_bar).max_by_key(rank));
+        }
+    }
+
+    /// Removes and returns the last bar, or returns `None` if there are no
+    /// bars. A cached answer is kept unless it covered that bar.
+    pub fn pop(&mut self) -> Option<T> {
+        let height = self.histogram.pop()?;
+        if let Some(Some(largest)) = self.largest_rectangle {
+            if largest.right == self.histogram.width() {
+                self.largest_rectangle = None;
+            }
+        }
+        Some(height)
+    }
+}
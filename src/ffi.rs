@@ -0,0 +1,132 @@
+//! A C interface to the solver, declared for C and C++ in `include/lrh.h`.
+//!
+//! Every function returns a status: zero or positive when it succeeded and
+//! negative when it failed, in which case its output is left untouched. No
+//! panic crosses the boundary; one is caught and returned as `LRH_PANIC`.
+
+use crate::{compute_largest_rectangle, Rectangle, SliceHistogram};
+use std::{mem, panic, slice};
+
+/// The call succeeded.
+pub const LRH_OK: i32 = 0;
+/// The call succeeded, but no bar is taller than zero, so there is no
+/// rectangle. Its area is zero.
+pub const LRH_NO_RECTANGLE: i32 = 1;
+/// `bars` was null while `len` was not zero, or an output pointer was null.
+pub const LRH_NULL_POINTER: i32 = -1;
+/// `len` is more bars than can be addressed.
+pub const LRH_INVALID_LENGTH: i32 = -2;
+/// The answer does not fit in the output's fields.
+pub const LRH_OVERFLOW: i32 = -3;
+/// The solver panicked, which is a bug.
+pub const LRH_PANIC: i32 = -4;
+
+/// The largest rectangle under a histogram, spanning the bars from `left` to
+/// `right`, both inclusive.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LrhRect {
+    pub left: usize,
+    pub right: usize,
+    pub height: i32,
+    pub area: i64,
+}
+
+/// Writes the area of the largest rectangle under the `len` bars at `bars`
+/// to `out_area`. Without a rectangle the area is zero and the status is
+/// `LRH_NO_RECTANGLE`.
+///
+/// # Safety
+///
+/// Unless `len` is zero, `bars` must point to `len` readable `int32_t`s,
+/// which are not written during the call. `out_area` must be valid for a
+/// write.
+#[no_mangle]
+pub unsafe extern "C" fn lrh_largest_rectangle_area(
+    bars: *const i32,
+    len: usize,
+    out_area: *mut i64,
+) -> i32 {
+    if out_area.is_null() {
+        return LRH_NULL_POINTER;
+    }
+    // SAFETY: the caller guarantees `bars` points to `len` bars.
+    match unsafe { solve(bars, len) } {
+        Ok(rectangle) => {
+            let area = rectangle.map_or(0, |rectangle| rectangle.area);
+            // SAFETY: `out_area` is not null, and the caller guarantees it is
+            // valid for a write.
+            unsafe { out_area.write(area) };
+            status(rectangle)
+        }
+        Err(status) => status,
+    }
+}
+
+/// Writes the largest rectangle under the `len` bars at `bars` to `out_rect`.
+/// Without a rectangle every field is zero and the status is
+/// `LRH_NO_RECTANGLE`.
+///
+/// # Safety
+///
+/// Unless `len` is zero, `bars` must point to `len` readable `int32_t`s,
+/// which are not written during the call. `out_rect` must be valid for a
+/// write.
+#[no_mangle]
+pub unsafe extern "C" fn lrh_largest_rectangle(
+    bars: *const i32,
+    len: usize,
+    out_rect: *mut LrhRect,
+) -> i32 {
+    if out_rect.is_null() {
+        return LRH_NULL_POINTER;
+    }
+    // SAFETY: the caller guarantees `bars` points to `len` bars.
+    match unsafe { solve(bars, len) } {
+        Ok(rectangle) => {
+            // SAFETY: `out_rect` is not null, and the caller guarantees it is
+            // valid for a write.
+            unsafe { out_rect.write(rectangle.unwrap_or_default()) };
+            status(rectangle)
+        }
+        Err(status) => status,
+    }
+}
+
+fn status(rectangle: Option<LrhRect>) -> i32 {
+    match rectangle {
+        Some(_) => LRH_OK,
+        None => LRH_NO_RECTANGLE,
+    }
+}
+
+/// Solves the bars at `bars`, or returns the failing status.
+///
+/// # Safety
+///
+/// Unless `len` is zero, `bars` must point to `len` readable `i32`s.
+unsafe fn solve(bars: *const i32, len: usize) -> Result<Option<LrhRect>, i32> {
+    let bars: &[i32] = if len == 0 {
+        &[]
+    } else if bars.is_null() {
+        return Err(LRH_NULL_POINTER);
+    } else if len > isize::MAX as usize / mem::size_of::<i32>() {
+        return Err(LRH_INVALID_LENGTH);
+    } else {
+        // SAFETY: `bars` is not null, `len` bars fit in an allocation and the
+        // caller guarantees they are readable and not written meanwhile.
+        unsafe { slice::from_raw_parts(bars, len) }
+    };
+    let rectangle = panic::catch_unwind(|| compute_largest_rectangle(&SliceHistogram::new(bars)))
+        .map_err(|_| LRH_PANIC)?;
+    rectangle.map(to_c).transpose()
+}
+
+fn to_c(rectangle: Rectangle) -> Result<LrhRect, i32> {
+    Ok(LrhRect {
+        left: rectangle.left,
+        right: rectangle.right,
+        height: rectangle.height,
+        area: i64::try_from(rectangle.area).map_err(|_| LRH_OVERFLOW)?,
+    })
+}
@@ -0,0 +1,328 @@
+use crate::histogram_concrete::ConcreteHistogram;
+use crate::matrix::BinaryMatrix;
+use crate::square_search::SearchOptions;
+use std::cmp;
+use std::f64::consts::TAU;
+use std::iter;
+use std::ops::RangeInclusive;
+
+/// Random histograms for tests and benchmarks. The same settings and seed
+/// always give the same bars, so a failing case can be rebuilt from its seed.
+pub struct RandomHistogram;
+
+impl RandomHistogram {
+    pub fn builder() -> RandomHistogramBuilder {
+        RandomHistogramBuilder::default()
+    }
+}
+
+/// How heights are spread over a `RandomHistogramBuilder`'s height range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Distribution {
+    /// Every height in the range is equally likely.
+    #[default]
+    Uniform,
+    /// The `k`th height from the bottom of the range is roughly `1 / k` as
+    /// likely as the lowest, so most bars are short and a few are tall.
+    Zipf,
+    /// A normal distribution centred on the middle of the range, with a
+    /// standard deviation of a sixth of its span. Samples falling outside the
+    /// range are clamped to it.
+    Gaussian,
+}
+
+/// Settings for a random histogram. By default the histogram is empty, heights
+/// are uniform in `0..=100` and the seed is zero.
+#[derive(Debug, Clone)]
+pub struct RandomHistogramBuilder {
+    width: usize,
+    height_range: RangeInclusive<i32>,
+    distribution: Distribution,
+    seed: u64,
+}
+
+impl Default for RandomHistogramBuilder {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            height_range: 0..=100,
+            distribution: Distribution::default(),
+            seed: 0,
+        }
+    }
+}
+
+impl RandomHistogramBuilder {
+    pub fn width(&mut self, width: usize) -> &mut Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height_range(&mut self, height_range: RangeInclusive<i32>) -> &mut Self {
+        self.height_range = height_range;
+        self
+    }
+
+    pub fn distribution(&mut self, distribution: Distribution) -> &mut Self {
+        self.distribution = distribution;
+        self
+    }
+
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.seed = seed;
+        self
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the height range is empty.
+    pub fn build(&self) -> ConcreteHistogram {
+        let (low, high) = (*self.height_range.start(), *self.height_range.end());
+        assert!(low <= high, "height range {low}..={high} is empty");
+        let span = (i64::from(high) - i64::from(low) + 1) as u64;
+        let mut rng = SplitMix64(self.seed);
+        let bars = (0..self.width)
+            .map(|_| {
+                let offset = match self.distribution {
+                    Distribution::Uniform => rng.below(span),
+                    Distribution::Zipf => zipf(&mut rng, span),
+                    Distribution::Gaussian => gaussian(&mut rng, span),
+                };
+                (i64::from(low) + offset as i64) as i32
+            })
+            .collect();
+        ConcreteHistogram::new(bars)
+    }
+}
+
+/// An offset in `0..span`, with offset `k` drawn with probability close to
+/// `1 / (k + 1)` by inverting the continuous `1 / x` distribution.
+fn zipf(rng: &mut SplitMix64, span: u64) -> u64 {
+    let rank = ((span + 1) as f64).powf(rng.unit()).floor() as u64;
+    rank.clamp(1, span) - 1
+}
+
+/// An offset in `0..span` from a normal distribution around its middle, by
+/// the Box-Muller transform.
+fn gaussian(rng: &mut SplitMix64, span: u64) -> u64 {
+    let radius = (-2.0 * (1.0 - rng.unit()).ln()).sqrt();
+    let standard = radius * (TAU * rng.unit()).cos();
+    let offset = (span - 1) as f64 / 2.0 + standard * span as f64 / 6.0;
+    offset.round().clamp(0.0, (span - 1) as f64) as u64
+}
+
+/// The SplitMix64 generator. It is small, fast and well mixed for any seed,
+/// including zero.
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `0..bound`, for non-zero `bound`.
+    pub(crate) fn below(&mut self, bound: u64) -> u64 {
+        ((u128::from(self.next_u64()) * u128::from(bound)) >> 64) as u64
+    }
+
+    /// A value in `[0, 1)`.
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+/// Shapes with a known effect on the search's stack, for benchmarks and tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pattern {
+    /// Heights rising from the first bar to `max_height` at the last, so every
+    /// bar stays on the stack until the end. The rise is strict when there are
+    /// no more bars than `max_height`.
+    Increasing,
+    /// `Increasing` reversed, so every bar is popped as soon as the next one
+    /// arrives.
+    Decreasing,
+    /// Teeth of `max_height` bars, each rising from 1 to `max_height` and then
+    /// dropping back, so the stack fills and empties once per tooth.
+    Sawtooth,
+    /// Sixteen runs of equal bars, cycling through `max_height`, a quarter,
+    /// three quarters and half of it.
+    Plateaus,
+    /// Bars of height 1 with a single bar of `max_height` in the middle.
+    Spike,
+    /// Heights rising to `max_height` in the middle and falling back again.
+    OrganPipe,
+}
+
+impl Pattern {
+    pub const ALL: [Pattern; 6] = [
+        Pattern::Increasing,
+        Pattern::Decreasing,
+        Pattern::Sawtooth,
+        Pattern::Plateaus,
+        Pattern::Spike,
+        Pattern::OrganPipe,
+    ];
+}
+
+/// Returns a histogram of `width` bars in the given shape, with no bar taller
+/// than `max_height`.
+///
+/// # Panics
+///
+/// Panics if `max_height` is negative.
+pub fn pattern(pattern: Pattern, width: usize, max_height: i32) -> ConcreteHistogram {
+    assert!(max_height >= 0, "max_height {max_height} is negative");
+    let scale =
+        |step: usize, steps: usize| (step as u64 * max_height as u64 / steps.max(1) as u64) as i32;
+    let bars = (0..width).map(|x_pos| match pattern {
+        Pattern::Increasing => scale(x_pos + 1, width),
+        Pattern::Decreasing => scale(width - x_pos, width),
+        Pattern::Sawtooth => match max_height {
+            0 => 0,
+            _ => (x_pos % max_height as usize) as i32 + 1,
+        },
+        Pattern::Plateaus => {
+            const QUARTERS: [usize; 4] = [4, 1, 3, 2];
+            let run = x_pos / width.div_ceil(16);
+            scale(QUARTERS[run % 4], 4)
+        }
+        Pattern::Spike if x_pos == width / 2 => max_height,
+        Pattern::Spike => max_height.min(1),
+        Pattern::OrganPipe => {
+            let from_edge = x_pos.min(width - 1 - x_pos);
+            scale(from_edge + 1, width.div_ceil(2))
+        }
+    });
+    ConcreteHistogram::new(bars.collect())
+}
+
+/// The most bars `from_bytes` gives a histogram.
+pub const MAX_BYTES_WIDTH: usize = 256;
+/// The tallest bar `from_bytes` gives a histogram.
+pub const MAX_BYTES_HEIGHT: i32 = 1000;
+/// The most rows, and the most columns, `matrix_from_bytes` gives a matrix.
+pub const MAX_BYTES_SIDE: usize = 32;
+
+/// Builds a histogram from arbitrary bytes, as a fuzzer supplies them. The
+/// same bytes always give the same histogram, of at most `MAX_BYTES_WIDTH`
+/// bars no taller than `MAX_BYTES_HEIGHT`.
+///
+/// The bytes choose runs of structure rather than each height, so even short
+/// inputs reach the shapes that matter to the search: plateaus of equal
+/// bars, strictly rising and falling runs, runs of zeros and lone bars.
+/// Missing bytes read as zero, as they do for the `arbitrary` crate's
+/// `Unstructured`, so every input, even an empty one, gives a histogram.
+pub fn from_bytes(bytes: &[u8]) -> ConcreteHistogram {
+    let mut reader = ByteReader::new(bytes);
+    let width = reader.below(MAX_BYTES_WIDTH + 1);
+    let mut bars = Vec::with_capacity(width);
+    while bars.len() < width {
+        let run = cmp::min(reader.below(16) + 1, width - bars.len());
+        let height = reader.height();
+        match reader.byte() % 5 {
+            0 => bars.extend(iter::repeat_n(height, run)),
+            1 => bars.extend((0..run).map(|step| ramp(height, step))),
+            2 => bars.extend((0..run).rev().map(|step| ramp(height, step))),
+            3 => bars.extend(iter::repeat_n(0, run)),
+            _ => bars.push(height),
+        }
+    }
+    ConcreteHistogram::new(bars)
+}
+
+/// Builds a grid from arbitrary bytes, as `from_bytes` builds a histogram,
+/// with at most `MAX_BYTES_SIDE` rows and columns. Each row either repeats
+/// the one above, which makes tall rectangles, or takes fresh cells.
+pub fn matrix_from_bytes(bytes: &[u8]) -> BinaryMatrix {
+    let mut reader = ByteReader::new(bytes);
+    let rows = reader.below(MAX_BYTES_SIDE + 1);
+    let columns = reader.below(MAX_BYTES_SIDE + 1);
+    let mut grid: Vec<Vec<bool>> = Vec::with_capacity(rows);
+    for _ in 0..rows {
+        let row = match grid.last() {
+            Some(above) if reader.byte().is_multiple_of(2) => above.clone(),
+            _ => (0..columns)
+                .map(|_| !reader.byte().is_multiple_of(4))
+                .collect(),
+        };
+        grid.push(row);
+    }
+    BinaryMatrix::new(grid).expect("rows all have `columns` cells")
+}
+
+/// Builds search bounds from arbitrary bytes, to fuzz
+/// `compute_largest_rectangle_constrained` alongside `from_bytes`. Each bound
+/// is set or left unset, widths lie in `1..=MAX_BYTES_WIDTH` and heights in
+/// `0..=MAX_BYTES_HEIGHT`, and a set minimum never exceeds its maximum, so
+/// most bounds leave some rectangle to find.
+pub fn options_from_bytes(bytes: &[u8]) -> SearchOptions {
+    let mut reader = ByteReader::new(bytes);
+    let mut width = || {
+        let set = reader.byte().is_multiple_of(2);
+        let width = reader.below(MAX_BYTES_WIDTH) + 1;
+        set.then_some(width)
+    };
+    let (min_width, max_width) = ordered(width(), width());
+    let mut height = || {
+        let set = reader.byte().is_multiple_of(2);
+        let height = reader.height();
+        set.then_some(height)
+    };
+    let (min_height, max_height) = ordered(height(), height());
+    SearchOptions {
+        min_width,
+        max_width,
+        min_height,
+        max_height,
+    }
+}
+
+/// Swaps a minimum and maximum that are both set and out of order.
+fn ordered<T: Ord>(min: Option<T>, max: Option<T>) -> (Option<T>, Option<T>) {
+    match (min, max) {
+        (Some(min), Some(max)) if min > max => (Some(max), Some(min)),
+        bounds => bounds,
+    }
+}
+
+/// The bar `step` bars down a ramp falling by one per bar from `top`, which
+/// flattens out at zero.
+fn ramp(top: i32, step: usize) -> i32 {
+    let rise = i32::try_from(step).unwrap_or(i32::MAX);
+    (top - rise).max(0)
+}
+
+/// Reads fuzzer bytes in order, giving zeros once they run out.
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn byte(&mut self) -> u8 {
+        match self.bytes.split_first() {
+            Some((&byte, rest)) => {
+                self.bytes = rest;
+                byte
+            }
+            None => 0,
+        }
+    }
+
+    /// A value in `0..bound` from two bytes, for non-zero `bound`.
+    fn below(&mut self, bound: usize) -> usize {
+        let value = u16::from_le_bytes([self.byte(), self.byte()]);
+        usize::from(value) % bound
+    }
+
+    fn height(&mut self) -> i32 {
+        self.below(MAX_BYTES_HEIGHT as usize + 1) as i32
+    }
+}
@@ -0,0 +1,261 @@
+use crate::histogram_views::{
+    ChainHistogram, ClampHistogram, MapHistogram, OverlayHistogram, OverlayKind, ReversedHistogram,
+    SubHistogram,
+};
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+use std::sync::Arc;
+
+pub trait Histogram<T = i32> {
+    fn width(&self) -> usize;
+    fn height_at(&self, horizontal_position: usize) -> T;
+
+    /// Returns the height of the bar at `horizontal_position`, or `None` if
+    /// there is no such bar. Unlike `height_at`, this never panics for a
+    /// position past the last bar.
+    ///
+    /// The default checks the position against `width` before reading the
+    /// bar, so it trusts `width` to be right. Histograms that can look their
+    /// bars up directly, as the ones backed by slices do, override it to
+    /// report a missing bar whatever `width` says.
+    fn try_height_at(&self, horizontal_position: usize) -> Option<T> {
+        (horizontal_position < self.width()).then(|| self.height_at(horizontal_position))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.width() == 0
+    }
+
+    /// Returns `None` for an empty histogram.
+    fn max_height(&self) -> Option<T>
+    where
+        T: Ord,
+    {
+        (0..self.width()).map(|x_pos| self.height_at(x_pos)).max()
+    }
+
+    /// Returns `None` for an empty histogram.
+    fn min_height(&self) -> Option<T>
+    where
+        T: Ord,
+    {
+        (0..self.width()).map(|x_pos| self.height_at(x_pos)).min()
+    }
+
+    /// Returns the sum of the heights of all bars.
+    fn total_area(&self) -> i64
+    where
+        T: Into<i64>,
+    {
+        (0..self.width())
+            .map(|x_pos| self.height_at(x_pos).into())
+            .sum()
+    }
+
+    fn iter_heights(&self) -> impl Iterator<Item = T> + '_
+    where
+        Self: Sized,
+    {
+        (0..self.width()).map(|x_pos| self.height_at(x_pos))
+    }
+
+    /// Returns the bars as a slice, if the histogram keeps them side by side
+    /// in memory. Searches read such bars straight from the slice rather than
+    /// through `height_at`.
+    fn as_slice(&self) -> Option<&[T]> {
+        None
+    }
+
+    /// Returns a view of the bars in `range`, numbered from zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range runs backwards or past the last bar.
+    fn slice(&self, range: Range<usize>) -> SubHistogram<'_, Self>
+    where
+        Self: Sized,
+    {
+        SubHistogram::new(self, range)
+    }
+
+    /// Returns a view of the bars in reverse order.
+    fn reversed(&self) -> ReversedHistogram<'_, Self>
+    where
+        Self: Sized,
+    {
+        ReversedHistogram::new(self)
+    }
+
+    /// Returns a view of these bars followed by those of `other`.
+    fn chain<'a, H: Histogram<T> + ?Sized>(&'a self, other: &'a H) -> ChainHistogram<'a, Self, H>
+    where
+        Self: Sized,
+    {
+        ChainHistogram::new(self, other)
+    }
+
+    /// Returns a view in which each height is passed through `f` whenever it
+    /// is read.
+    fn map_heights<F: Fn(T) -> T>(&self, f: F) -> MapHistogram<'_, Self, F>
+    where
+        Self: Sized,
+    {
+        MapHistogram::new(self, f)
+    }
+
+    /// Returns a view with every bar lowered by `offset`, saturating at the
+    /// bounds of `i32`. Bars that end up negative count as height zero in the
+    /// searches.
+    fn offset(&self, offset: i32) -> MapHistogram<'_, Self, impl Fn(i32) -> i32>
+    where
+        Self: Histogram<i32> + Sized,
+    {
+        MapHistogram::new(self, move |height: i32| height.saturating_sub(offset))
+    }
+
+    /// Returns a view with every bar multiplied by `factor`, saturating at the
+    /// bounds of `i32`.
+    fn scale(&self, factor: i32) -> MapHistogram<'_, Self, impl Fn(i32) -> i32>
+    where
+        Self: Histogram<i32> + Sized,
+    {
+        MapHistogram::new(self, move |height: i32| height.saturating_mul(factor))
+    }
+
+    /// Returns a view with every height clamped into `min..=max`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min > max`.
+    fn clamp(&self, min: T, max: T) -> ClampHistogram<'_, Self, T>
+    where
+        Self: Sized,
+        T: Ord,
+    {
+        ClampHistogram::new(self, min, max)
+    }
+}
+
+/// Returns a view whose bars are the lower of the corresponding bars of
+/// `first` and `second`.
+///
+/// # Panics
+///
+/// Panics if the histograms have different widths.
+pub fn overlay_min<'a, T, A, B>(first: &'a A, second: &'a B) -> OverlayHistogram<'a, A, B>
+where
+    A: Histogram<T> + ?Sized,
+    B: Histogram<T> + ?Sized,
+{
+    OverlayHistogram::new(first, second, OverlayKind::Min)
+}
+
+/// Returns a view whose bars are the higher of the corresponding bars of
+/// `first` and `second`.
+///
+/// # Panics
+///
+/// Panics if the histograms have different widths.
+pub fn overlay_max<'a, T, A, B>(first: &'a A, second: &'a B) -> OverlayHistogram<'a, A, B>
+where
+    A: Histogram<T> + ?Sized,
+    B: Histogram<T> + ?Sized,
+{
+    OverlayHistogram::new(first, second, OverlayKind::Max)
+}
+
+macro_rules! impl_histogram_for_pointer {
+    ($($pointer:ty),*) => {
+        $(
+            impl<T, H: Histogram<T> + ?Sized> Histogram<T> for $pointer {
+                fn width(&self) -> usize {
+                    (**self).width()
+                }
+
+                fn height_at(&self, horizontal_position: usize) -> T {
+                    (**self).height_at(horizontal_position)
+                }
+
+                fn try_height_at(&self, horizontal_position: usize) -> Option<T> {
+                    (**self).try_height_at(horizontal_position)
+                }
+
+                fn as_slice(&self) -> Option<&[T]> {
+                    (**self).as_slice()
+                }
+            }
+        )*
+    };
+}
+
+impl_histogram_for_pointer!(&H, Box<H>, Rc<H>, Arc<H>);
+
+/// A histogram whose heights are computed by a closure from the bar position.
+///
+/// `compute_largest_rectangle` calls the closure once per bar, but some other
+/// searches read bars more than once, so the closure may be called several
+/// times for the same position. When it is expensive, evaluating it once per
+/// bar into a `ConcreteHistogram` is likely to be faster.
+pub struct FnHistogram<F> {
+    width: usize,
+    height_at: F,
+}
+
+impl<T, F: Fn(usize) -> T> FnHistogram<F> {
+    pub fn new(width: usize, height_at: F) -> Self {
+        Self { width, height_at }
+    }
+}
+
+impl<T, F: Fn(usize) -> T> Histogram<T> for FnHistogram<F> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> T {
+        (self.height_at)(horizontal_position)
+    }
+}
+
+/// A histogram whose bars each have their own width, rather than all being one
+/// unit wide. `width` is still the number of bars.
+pub trait WeightedHistogram: Histogram {
+    fn width_of(&self, horizontal_position: usize) -> u64;
+}
+
+/// A histogram whose bars can be changed in place.
+pub trait HistogramMut<T = i32>: Histogram<T> {
+    fn set_height(&mut self, horizontal_position: usize, height: T);
+}
+
+/// An integer type that can be used for bar heights.
+///
+/// `Area` is wide enough to hold the area of any rectangle over fewer than
+/// 2^32 bars.
+pub trait Height: Copy + Ord + fmt::Debug {
+    type Area: Copy + Ord + Default + fmt::Debug;
+
+    const ZERO: Self;
+
+    fn area(self, width: usize) -> Self::Area;
+}
+
+macro_rules! impl_height {
+    ($area:ty => $($height:ty),*) => {
+        $(
+            impl Height for $height {
+                type Area = $area;
+
+                const ZERO: Self = 0;
+
+                fn area(self, width: usize) -> $area {
+                    width as $area * self as $area
+                }
+            }
+        )*
+    };
+}
+
+impl_height!(u64 => i8, i16, i32, u8, u16, u32);
+impl_height!(u128 => i64, u64, isize, usize);
@@ -0,0 +1,149 @@
+use crate::histogram_concrete::ConcreteHistogram;
+use std::fmt;
+
+/// Collects bars for a `ConcreteHistogram` and checks them all in `build`.
+///
+/// By default negative heights are rejected and there is no limit on the width
+/// or the height. Runs of equal bars are stored as runs until `build`, so a
+/// long run that breaks a limit is rejected without being expanded. So is a
+/// total width too large for any histogram to hold, limit or no limit.
+#[derive(Debug, Clone, Default)]
+pub struct ConcreteHistogramBuilder {
+    runs: Vec<(i32, usize)>,
+    allow_negative_heights: bool,
+    max_width: Option<usize>,
+    max_height: Option<i32>,
+}
+
+impl ConcreteHistogramBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allow_negative_heights(&mut self, allow: bool) -> &mut Self {
+        self.allow_negative_heights = allow;
+        self
+    }
+
+    pub fn max_width(&mut self, max_width: usize) -> &mut Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn max_height(&mut self, max_height: i32) -> &mut Self {
+        self.max_height = Some(max_height);
+        self
+    }
+
+    pub fn push(&mut self, height: i32) -> &mut Self {
+        self.push_run(height, 1)
+    }
+
+    /// Appends `count` bars of the same height.
+    pub fn push_run(&mut self, height: i32, count: usize) -> &mut Self {
+        match self.runs.last_mut() {
+            Some(last) if last.0 == height && last.1.checked_add(count).is_some() => {
+                last.1 += count
+            }
+            // A run too long to merge stays separate, for `build` to reject.
+            _ if count > 0 => self.runs.push((height, count)),
+            _ => {}
+        }
+        self
+    }
+
+    pub fn build(&self) -> Result<ConcreteHistogram, BuildError> {
+        let mut width = 0_usize;
+        for &(height, count) in &self.runs {
+            if height < 0 && !self.allow_negative_heights {
+                return Err(BuildError::NegativeHeight {
+                    bar_index: width,
+                    height,
+                });
+            }
+            if let Some(max_height) = self.max_height {
+                if height > max_height {
+                    return Err(BuildError::TooTall {
+                        bar_index: width,
+                        height,
+                        max_height,
+                    });
+                }
+            }
+            if let Some(max_width) = self.max_width {
+                if count > max_width - width {
+                    return Err(BuildError::TooWide {
+                        bar_index: max_width,
+                        max_width,
+                    });
+                }
+            }
+            width = match width.checked_add(count).filter(|&width| width <= MAX_BARS) {
+                Some(width) => width,
+                None => return Err(BuildError::WidthOverflow { bar_index: width }),
+            };
+        }
+        let mut bars = Vec::with_capacity(width);
+        for &(height, count) in &self.runs {
+            bars.extend(std::iter::repeat_n(height, count));
+        }
+        Ok(ConcreteHistogram::new(bars))
+    }
+}
+
+/// The most bars a histogram can hold before their size in bytes overflows.
+const MAX_BARS: usize = isize::MAX as usize / std::mem::size_of::<i32>();
+
+/// The first bar that breaks one of a `ConcreteHistogramBuilder`'s limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    NegativeHeight {
+        bar_index: usize,
+        height: i32,
+    },
+    TooTall {
+        bar_index: usize,
+        height: i32,
+        max_height: i32,
+    },
+    TooWide {
+        bar_index: usize,
+        max_width: usize,
+    },
+    /// The bars from `bar_index` on are more than any histogram can hold,
+    /// whatever the maximum width.
+    WidthOverflow {
+        bar_index: usize,
+    },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::NegativeHeight { bar_index, height } => {
+                write!(f, "bar {bar_index} has negative height {height}")
+            }
+            BuildError::TooTall {
+                bar_index,
+                height,
+                max_height,
+            } => write!(
+                f,
+                "bar {bar_index} has height {height}, above the maximum of {max_height}"
+            ),
+            BuildError::TooWide {
+                bar_index,
+                max_width,
+            } => write!(
+                f,
+                "bar {bar_index} is beyond the maximum width of {max_width}"
+            ),
+            BuildError::WidthOverflow { bar_index } => write!(
+                f,
+                "bar {bar_index} is beyond the most bars a histogram can hold"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
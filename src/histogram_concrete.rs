@@ -0,0 +1,429 @@
+use crate::histogram::{Histogram, HistogramMut, WeightedHistogram};
+use crate::histogram_builder::ConcreteHistogramBuilder;
+use crate::histogram_run_length::RunLengthHistogram;
+use crate::render;
+#[cfg(feature = "serde")]
+use crate::{histogram::Height, square_search::NegativeHeight};
+use std::fmt;
+
+pub struct ConcreteHistogram<T = i32> {
+    bars: Vec<T>,
+}
+
+impl ConcreteHistogram {
+    pub fn new(bars: Vec<i32>) -> Self {
+        Self::from_bars(bars)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_bars(Vec::with_capacity(capacity))
+    }
+
+    pub fn builder() -> ConcreteHistogramBuilder {
+        ConcreteHistogramBuilder::new()
+    }
+
+    /// Returns the same bars stored as runs of equal heights.
+    pub fn to_run_length(&self) -> RunLengthHistogram {
+        RunLengthHistogram::new(self.bars.iter().map(|&height| (height, 1)).collect())
+    }
+
+    /// Rebuilds a histogram from its `render::outline`. Bars before the first
+    /// point have height zero, so a skyline that leaves out its leading ground
+    /// is also accepted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an `x` coordinate does not fit in a `usize`.
+    pub fn from_outline(points: &[(u64, i32)]) -> Result<Self, OutlineError> {
+        if let Some(index) = (1..points.len()).find(|&index| points[index - 1].0 >= points[index].0)
+        {
+            return Err(OutlineError::NotIncreasing { index });
+        }
+        let position = |x: u64| usize::try_from(x).expect("x coordinates must fit in a usize");
+        let mut bars = Vec::new();
+        if let Some(&(end, height)) = points.last() {
+            if height != 0 {
+                return Err(OutlineError::OpenEnd { height });
+            }
+            bars.reserve_exact(position(end));
+            bars.resize(position(points[0].0), 0);
+            for pair in points.windows(2) {
+                bars.resize(position(pair[1].0), pair[0].1);
+            }
+        }
+        Ok(Self::new(bars))
+    }
+}
+
+/// Draws the histogram with `render::to_ascii`.
+impl fmt::Display for ConcreteHistogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&render::to_ascii(self))
+    }
+}
+
+impl<T> ConcreteHistogram<T> {
+    pub fn from_bars(bars: Vec<T>) -> Self {
+        Self { bars }
+    }
+
+    pub fn as_view(&self) -> SliceHistogram<'_, T> {
+        SliceHistogram::from_bars(&self.bars)
+    }
+
+    pub fn heights(&self) -> &[T] {
+        &self.bars
+    }
+
+    pub fn push(&mut self, height: T) {
+        self.bars.push(height);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.bars.pop()
+    }
+
+    /// Changes the height of an existing bar, leaving the histogram untouched
+    /// if there is no bar at `horizontal_position`.
+    pub fn try_set_height(
+        &mut self,
+        horizontal_position: usize,
+        height: T,
+    ) -> Result<(), OutOfRange> {
+        let width = self.bars.len();
+        let bar = self.bars.get_mut(horizontal_position).ok_or(OutOfRange {
+            horizontal_position,
+            width,
+        })?;
+        *bar = height;
+        Ok(())
+    }
+
+    /// Keeps the first `width` bars, doing nothing if there are no more than
+    /// that.
+    pub fn truncate(&mut self, width: usize) {
+        self.bars.truncate(width);
+    }
+
+    /// Inserts a bar at `horizontal_position`, shifting the bars from there
+    /// onwards one place to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `horizontal_position` is greater than the width.
+    pub fn insert(&mut self, horizontal_position: usize, height: T) {
+        self.bars.insert(horizontal_position, height);
+    }
+
+    /// Removes and returns the bar at `horizontal_position`, shifting the bars
+    /// after it one place to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `horizontal_position` is not less than the width.
+    pub fn remove(&mut self, horizontal_position: usize) -> T {
+        self.bars.remove(horizontal_position)
+    }
+}
+
+/// The error from editing a bar that a histogram does not have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRange {
+    pub horizontal_position: usize,
+    pub width: usize,
+}
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "bar {} is out of range for a histogram of width {}",
+            self.horizontal_position, self.width
+        )
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+/// The error from rebuilding a histogram out of an invalid outline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineError {
+    /// The point at `index` is not to the right of the point before it.
+    NotIncreasing { index: usize },
+    /// The last point has a non-zero height, so the outline never closes.
+    OpenEnd { height: i32 },
+}
+
+impl fmt::Display for OutlineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutlineError::NotIncreasing { index } => write!(
+                f,
+                "point {index} is not to the right of the point before it"
+            ),
+            OutlineError::OpenEnd { height } => write!(
+                f,
+                "the last point has height {height} rather than closing the outline at 0"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OutlineError {}
+
+impl<T: Copy> Histogram<T> for ConcreteHistogram<T> {
+    fn height_at(&self, horizontal_position: usize) -> T {
+        self.bars[horizontal_position]
+    }
+
+    fn try_height_at(&self, horizontal_position: usize) -> Option<T> {
+        self.bars.get(horizontal_position).copied()
+    }
+
+    fn width(&self) -> usize {
+        self.bars.len()
+    }
+
+    fn iter_heights(&self) -> impl Iterator<Item = T> + '_ {
+        self.bars.iter().copied()
+    }
+
+    fn as_slice(&self) -> Option<&[T]> {
+        Some(&self.bars)
+    }
+}
+
+// Like `new`, this is only for `i32` bars, so that `ConcreteHistogram::from`
+// applied to integer literals still has an inferable area type.
+impl From<Vec<i32>> for ConcreteHistogram {
+    fn from(bars: Vec<i32>) -> Self {
+        Self::from_bars(bars)
+    }
+}
+
+impl<T> FromIterator<T> for ConcreteHistogram<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(bars: I) -> Self {
+        Self::from_bars(bars.into_iter().collect())
+    }
+}
+
+impl<'a, T: Copy + 'a> FromIterator<&'a T> for ConcreteHistogram<T> {
+    fn from_iter<I: IntoIterator<Item = &'a T>>(bars: I) -> Self {
+        Self::from_bars(bars.into_iter().copied().collect())
+    }
+}
+
+impl<T> Extend<T> for ConcreteHistogram<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, bars: I) {
+        self.bars.extend(bars);
+    }
+}
+
+impl<T: Copy> HistogramMut<T> for ConcreteHistogram<T> {
+    fn set_height(&mut self, horizontal_position: usize, height: T) {
+        self.bars[horizontal_position] = height;
+    }
+}
+
+// Only `i32` bars get these impls, as with `ConcreteHistogram::new`; for any
+// other height type the area's type could not be inferred from an integer
+// literal such as `&[2, 1, 5]`.
+impl Histogram for [i32] {
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        self[horizontal_position]
+    }
+
+    fn try_height_at(&self, horizontal_position: usize) -> Option<i32> {
+        self.get(horizontal_position).copied()
+    }
+
+    fn width(&self) -> usize {
+        self.len()
+    }
+
+    fn as_slice(&self) -> Option<&[i32]> {
+        Some(self)
+    }
+}
+
+impl Histogram for Vec<i32> {
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        self[horizontal_position]
+    }
+
+    fn try_height_at(&self, horizontal_position: usize) -> Option<i32> {
+        self.get(horizontal_position).copied()
+    }
+
+    fn width(&self) -> usize {
+        self.len()
+    }
+
+    fn iter_heights(&self) -> impl Iterator<Item = i32> + '_ {
+        self.iter().copied()
+    }
+
+    fn as_slice(&self) -> Option<&[i32]> {
+        Some(self)
+    }
+}
+
+impl<const N: usize> Histogram for [i32; N] {
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        self[horizontal_position]
+    }
+
+    fn try_height_at(&self, horizontal_position: usize) -> Option<i32> {
+        self.get(horizontal_position).copied()
+    }
+
+    fn width(&self) -> usize {
+        N
+    }
+
+    fn iter_heights(&self) -> impl Iterator<Item = i32> + '_ {
+        self.iter().copied()
+    }
+
+    fn as_slice(&self) -> Option<&[i32]> {
+        Some(self)
+    }
+}
+
+/// A histogram borrowing its bars, for solving bars held elsewhere without
+/// copying them.
+#[derive(Clone, Copy)]
+pub struct SliceHistogram<'a, T = i32> {
+    bars: &'a [T],
+}
+
+impl<'a> SliceHistogram<'a> {
+    pub fn new(bars: &'a [i32]) -> Self {
+        Self::from_bars(bars)
+    }
+}
+
+impl<'a, T> SliceHistogram<'a, T> {
+    pub fn from_bars(bars: &'a [T]) -> Self {
+        Self { bars }
+    }
+}
+
+impl<T: Copy> Histogram<T> for SliceHistogram<'_, T> {
+    fn height_at(&self, horizontal_position: usize) -> T {
+        self.bars[horizontal_position]
+    }
+
+    fn try_height_at(&self, horizontal_position: usize) -> Option<T> {
+        self.bars.get(horizontal_position).copied()
+    }
+
+    fn width(&self) -> usize {
+        self.bars.len()
+    }
+
+    fn iter_heights(&self) -> impl Iterator<Item = T> + '_ {
+        self.bars.iter().copied()
+    }
+
+    fn as_slice(&self) -> Option<&[T]> {
+        Some(self.bars)
+    }
+}
+
+/// A histogram stored inline in a fixed-size array, for use without a heap.
+pub struct ArrayHistogram<const N: usize> {
+    bars: [i32; N],
+}
+
+impl<const N: usize> ArrayHistogram<N> {
+    pub const fn new(bars: [i32; N]) -> Self {
+        Self { bars }
+    }
+}
+
+impl<const N: usize> Histogram for ArrayHistogram<N> {
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        self.bars[horizontal_position]
+    }
+
+    fn width(&self) -> usize {
+        N
+    }
+
+    fn as_slice(&self) -> Option<&[i32]> {
+        Some(&self.bars)
+    }
+}
+
+/// A histogram of `(height, width)` bars.
+pub struct WeightedConcreteHistogram {
+    bars: Vec<(i32, u64)>,
+}
+
+impl WeightedConcreteHistogram {
+    pub fn new(bars: Vec<(i32, u64)>) -> Self {
+        Self { bars }
+    }
+}
+
+impl Histogram for WeightedConcreteHistogram {
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        self.bars[horizontal_position].0
+    }
+
+    fn width(&self) -> usize {
+        self.bars.len()
+    }
+}
+
+impl WeightedHistogram for WeightedConcreteHistogram {
+    fn width_of(&self, horizontal_position: usize) -> u64 {
+        self.bars[horizontal_position].1
+    }
+}
+
+/// Serializes as a plain sequence of heights.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for ConcreteHistogram<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.bars.serialize(serializer)
+    }
+}
+
+/// Deserializes from a plain sequence of heights, rejecting the first
+/// negative one as `NegativePolicy::Reject` would.
+#[cfg(feature = "serde")]
+impl<'de, T: Height + serde::Deserialize<'de>> serde::Deserialize<'de> for ConcreteHistogram<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BarsVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: Height + serde::Deserialize<'de>> serde::de::Visitor<'de> for BarsVisitor<T> {
+            type Value = Vec<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a sequence of non-negative heights")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Vec<T>, A::Error> {
+                let mut bars = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(height) = seq.next_element::<T>()? {
+                    if height < T::ZERO {
+                        let bar_index = bars.len();
+                        return Err(serde::de::Error::custom(NegativeHeight { bar_index }));
+                    }
+                    bars.push(height);
+                }
+                Ok(bars)
+            }
+        }
+
+        deserializer
+            .deserialize_seq(BarsVisitor(std::marker::PhantomData))
+            .map(Self::from_bars)
+    }
+}
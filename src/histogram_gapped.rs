@@ -0,0 +1,47 @@
+use crate::histogram::Histogram;
+use std::ops::Range;
+
+/// A histogram in which some bars are missing. No rectangle may span a gap.
+pub struct GappedHistogram<T = i32> {
+    bars: Vec<Option<T>>,
+}
+
+impl GappedHistogram {
+    pub fn new(bars: Vec<Option<i32>>) -> Self {
+        Self::from_bars(bars)
+    }
+}
+
+impl<T> GappedHistogram<T> {
+    pub fn from_bars(bars: Vec<Option<T>>) -> Self {
+        Self { bars }
+    }
+
+    /// Returns the positions of the missing bars, in increasing order.
+    pub fn gaps(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.bars.len()).filter(|&x_pos| self.bars[x_pos].is_none())
+    }
+
+    /// Returns the maximal runs of bars without a gap, in increasing order.
+    pub fn segments(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        let mut start = 0;
+        (0..=self.bars.len()).filter_map(move |x_pos| {
+            if x_pos < self.bars.len() && self.bars[x_pos].is_some() {
+                return None;
+            }
+            let segment = start..x_pos;
+            start = x_pos + 1;
+            Some(segment).filter(|segment| !segment.is_empty())
+        })
+    }
+}
+
+impl<T: Copy> Histogram<Option<T>> for GappedHistogram<T> {
+    fn height_at(&self, horizontal_position: usize) -> Option<T> {
+        self.bars[horizontal_position]
+    }
+
+    fn width(&self) -> usize {
+        self.bars.len()
+    }
+}
@@ -0,0 +1,76 @@
+use crate::histogram::Histogram;
+
+/// A histogram stored as runs of equal consecutive bars, each a height and the
+/// number of bars in the run. It behaves as the histogram of all those bars,
+/// so `width` counts bars rather than runs.
+pub struct RunLengthHistogram {
+    runs: Vec<(i32, u64)>,
+    /// The position just past the last bar of each run.
+    ends: Vec<u64>,
+    width: usize,
+}
+
+impl RunLengthHistogram {
+    /// Empty runs are dropped and neighbouring runs of the same height are
+    /// merged, so `runs` may return fewer runs than were given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runs hold more bars than a `usize` can count.
+    pub fn new(runs: Vec<(i32, u64)>) -> Self {
+        let mut merged: Vec<(i32, u64)> = Vec::with_capacity(runs.len());
+        for (height, length) in runs {
+            match merged.last_mut() {
+                _ if length == 0 => {}
+                Some(last) if last.0 == height => last.1 += length,
+                _ => merged.push((height, length)),
+            }
+        }
+        let mut end = 0_u64;
+        let ends = merged
+            .iter()
+            .map(|&(_, length)| {
+                end = end
+                    .checked_add(length)
+                    .expect("run lengths overflow the width of a histogram");
+                end
+            })
+            .collect();
+        let width = usize::try_from(end).expect("run lengths overflow the width of a histogram");
+        Self {
+            runs: merged,
+            ends,
+            width,
+        }
+    }
+
+    /// Returns the runs as `(height, length)` pairs, in order.
+    pub fn runs(&self) -> &[(i32, u64)] {
+        &self.runs
+    }
+}
+
+impl Histogram for RunLengthHistogram {
+    /// Finds the run holding the bar by binary search.
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        assert!(
+            horizontal_position < self.width,
+            "bar {horizontal_position} is out of range for a histogram of width {}",
+            self.width
+        );
+        let run = self
+            .ends
+            .partition_point(|&end| end <= horizontal_position as u64);
+        self.runs[run].0
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn iter_heights(&self) -> impl Iterator<Item = i32> + '_ {
+        self.runs
+            .iter()
+            .flat_map(|&(height, length)| (0..length).map(move |_| height))
+    }
+}
@@ -0,0 +1,58 @@
+use crate::histogram::Histogram;
+
+/// A histogram in which only the listed bars have a height, and every other
+/// bar has height zero. It suits very wide histograms with few non-zero bars.
+pub struct SparseHistogram {
+    width: usize,
+    /// The listed bars as `(position, height)`, in increasing position.
+    bars: Vec<(usize, i32)>,
+}
+
+impl SparseHistogram {
+    /// # Panics
+    ///
+    /// Panics if `width` does not fit in a `usize`, or if a bar is listed more
+    /// than once or lies outside the histogram.
+    pub fn new(width: u64, nonzero: Vec<(u64, i32)>) -> Self {
+        let width = usize::try_from(width).expect("width must fit in a usize");
+        let mut bars: Vec<(usize, i32)> = nonzero
+            .into_iter()
+            .map(|(x_pos, height)| {
+                let x_pos = usize::try_from(x_pos).unwrap_or(usize::MAX);
+                assert!(
+                    x_pos < width,
+                    "bar {x_pos} is out of range for a histogram of width {width}"
+                );
+                (x_pos, height)
+            })
+            .collect();
+        bars.sort_unstable_by_key(|&(x_pos, _)| x_pos);
+        if let Some(pair) = bars.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            panic!("bar {} is listed more than once", pair[0].0);
+        }
+        Self { width, bars }
+    }
+
+    /// Returns the listed bars as `(position, height)` pairs, in increasing
+    /// position.
+    pub fn nonzero_bars(&self) -> &[(usize, i32)] {
+        &self.bars
+    }
+}
+
+impl Histogram for SparseHistogram {
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        assert!(
+            horizontal_position < self.width,
+            "bar {horizontal_position} is out of range for a histogram of width {}",
+            self.width
+        );
+        self.bars
+            .binary_search_by_key(&horizontal_position, |&(x_pos, _)| x_pos)
+            .map_or(0, |index| self.bars[index].1)
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+}
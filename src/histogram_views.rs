@@ -0,0 +1,208 @@
+use crate::histogram::Histogram;
+use std::cmp;
+use std::ops::Range;
+
+/// A view of a contiguous range of another histogram's bars, created by
+/// `Histogram::slice`.
+pub struct SubHistogram<'a, H: ?Sized> {
+    histogram: &'a H,
+    range: Range<usize>,
+}
+
+impl<'a, H: ?Sized> SubHistogram<'a, H> {
+    /// # Panics
+    ///
+    /// Panics if the range runs backwards or past the last bar.
+    pub fn new<T>(histogram: &'a H, range: Range<usize>) -> Self
+    where
+        H: Histogram<T>,
+    {
+        assert!(
+            range.start <= range.end && range.end <= histogram.width(),
+            "range {}..{} is out of bounds for a histogram of width {}",
+            range.start,
+            range.end,
+            histogram.width()
+        );
+        Self { histogram, range }
+    }
+}
+
+impl<T, H: Histogram<T> + ?Sized> Histogram<T> for SubHistogram<'_, H> {
+    fn width(&self) -> usize {
+        self.range.len()
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> T {
+        assert!(horizontal_position < self.width());
+        self.histogram
+            .height_at(self.range.start + horizontal_position)
+    }
+
+    fn as_slice(&self) -> Option<&[T]> {
+        Some(&self.histogram.as_slice()?[self.range.clone()])
+    }
+}
+
+/// A view of another histogram's bars in reverse order, created by
+/// `Histogram::reversed`.
+pub struct ReversedHistogram<'a, H: ?Sized> {
+    histogram: &'a H,
+}
+
+impl<'a, H: ?Sized> ReversedHistogram<'a, H> {
+    pub fn new(histogram: &'a H) -> Self {
+        Self { histogram }
+    }
+}
+
+impl<T, H: Histogram<T> + ?Sized> Histogram<T> for ReversedHistogram<'_, H> {
+    fn width(&self) -> usize {
+        self.histogram.width()
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> T {
+        self.histogram
+            .height_at(self.width() - 1 - horizontal_position)
+    }
+}
+
+/// A view of one histogram's bars followed by another's, created by
+/// `Histogram::chain`.
+pub struct ChainHistogram<'a, A: ?Sized, B: ?Sized> {
+    first: &'a A,
+    second: &'a B,
+}
+
+impl<'a, A: ?Sized, B: ?Sized> ChainHistogram<'a, A, B> {
+    pub fn new(first: &'a A, second: &'a B) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<T, A: Histogram<T> + ?Sized, B: Histogram<T> + ?Sized> Histogram<T>
+    for ChainHistogram<'_, A, B>
+{
+    fn width(&self) -> usize {
+        self.first.width() + self.second.width()
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> T {
+        let first_width = self.first.width();
+        if horizontal_position < first_width {
+            self.first.height_at(horizontal_position)
+        } else {
+            self.second.height_at(horizontal_position - first_width)
+        }
+    }
+}
+
+/// A view of another histogram with its heights transformed as they are read,
+/// created by `Histogram::map_heights`.
+pub struct MapHistogram<'a, H: ?Sized, F> {
+    histogram: &'a H,
+    f: F,
+}
+
+impl<'a, H: ?Sized, F> MapHistogram<'a, H, F> {
+    pub fn new(histogram: &'a H, f: F) -> Self {
+        Self { histogram, f }
+    }
+}
+
+impl<T, H: Histogram<T> + ?Sized, F: Fn(T) -> T> Histogram<T> for MapHistogram<'_, H, F> {
+    fn width(&self) -> usize {
+        self.histogram.width()
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> T {
+        (self.f)(self.histogram.height_at(horizontal_position))
+    }
+}
+
+/// A view combining two histograms of equal width bar by bar, created by
+/// `overlay_min` or `overlay_max`.
+pub struct OverlayHistogram<'a, A: ?Sized, B: ?Sized> {
+    first: &'a A,
+    second: &'a B,
+    kind: OverlayKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayKind {
+    Min,
+    Max,
+}
+
+impl<'a, A: ?Sized, B: ?Sized> OverlayHistogram<'a, A, B> {
+    /// # Panics
+    ///
+    /// Panics if the histograms have different widths.
+    pub fn new<T>(first: &'a A, second: &'a B, kind: OverlayKind) -> Self
+    where
+        A: Histogram<T>,
+        B: Histogram<T>,
+    {
+        assert_eq!(
+            first.width(),
+            second.width(),
+            "overlaid histograms must have the same width"
+        );
+        Self {
+            first,
+            second,
+            kind,
+        }
+    }
+}
+
+impl<T: Ord, A: Histogram<T> + ?Sized, B: Histogram<T> + ?Sized> Histogram<T>
+    for OverlayHistogram<'_, A, B>
+{
+    fn width(&self) -> usize {
+        self.first.width()
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> T {
+        let first = self.first.height_at(horizontal_position);
+        let second = self.second.height_at(horizontal_position);
+        match self.kind {
+            OverlayKind::Min => cmp::min(first, second),
+            OverlayKind::Max => cmp::max(first, second),
+        }
+    }
+}
+
+/// A view of another histogram with its heights clamped into a range, created
+/// by `Histogram::clamp`.
+pub struct ClampHistogram<'a, H: ?Sized, T = i32> {
+    histogram: &'a H,
+    min: T,
+    max: T,
+}
+
+impl<'a, H: ?Sized, T: Ord> ClampHistogram<'a, H, T> {
+    /// # Panics
+    ///
+    /// Panics if `min > max`.
+    pub fn new(histogram: &'a H, min: T, max: T) -> Self {
+        assert!(min <= max, "clamp range must not have min above max");
+        Self {
+            histogram,
+            min,
+            max,
+        }
+    }
+}
+
+impl<T: Ord + Copy, H: Histogram<T> + ?Sized> Histogram<T> for ClampHistogram<'_, H, T> {
+    fn width(&self) -> usize {
+        self.histogram.width()
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> T {
+        self.histogram
+            .height_at(horizontal_position)
+            .clamp(self.min, self.max)
+    }
+}
@@ -0,0 +1,30 @@
+pub mod cached_search;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod generate;
+pub mod histogram;
+pub mod histogram_builder;
+pub mod histogram_concrete;
+pub mod histogram_gapped;
+pub mod histogram_run_length;
+pub mod histogram_sparse;
+pub mod histogram_views;
+pub mod matrix;
+pub mod parse;
+pub mod range_search;
+pub mod rectangle;
+pub mod render;
+pub mod square_search;
+#[cfg(feature = "testing")]
+pub mod strategies;
+
+pub use histogram::{Height, Histogram, HistogramMut, WeightedHistogram};
+pub use histogram_builder::ConcreteHistogramBuilder;
+pub use histogram_concrete::{
+    ArrayHistogram, ConcreteHistogram, SliceHistogram, WeightedConcreteHistogram,
+};
+pub use histogram_gapped::GappedHistogram;
+pub use histogram_run_length::RunLengthHistogram;
+pub use histogram_sparse::SparseHistogram;
+pub use rectangle::{Rectangle, Rectangle2D};
+pub use square_search::{compute_area_of_largest_rectangle, compute_largest_rectangle};
@@ -0,0 +1,154 @@
+//! Rectangles of cells in a grid, found by treating each row as the base of a
+//! histogram whose bars are the runs of cells ending in that row.
+
+use crate::square_search::{compute_largest_rectangle, sum_of_subarray_minimums};
+use std::fmt;
+
+/// A grid of cells, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix<T> {
+    rows: usize,
+    columns: usize,
+    cells: Vec<T>,
+}
+
+/// A grid of cells that are either set or not.
+pub type BinaryMatrix = Matrix<bool>;
+
+impl<T> Matrix<T> {
+    /// Fails if the rows are not all the same length.
+    pub fn new(rows: Vec<Vec<T>>) -> Result<Self, RaggedRows> {
+        let (row_count, columns) = (rows.len(), rows.first().map_or(0, Vec::len));
+        let mut cells = Vec::with_capacity(rows.len() * columns);
+        for (row, cells_in_row) in rows.into_iter().enumerate() {
+            if cells_in_row.len() != columns {
+                return Err(RaggedRows {
+                    row,
+                    columns: cells_in_row.len(),
+                    expected_columns: columns,
+                });
+            }
+            cells.extend(cells_in_row);
+        }
+        Ok(Self {
+            rows: row_count,
+            columns,
+            cells,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns the cells of one row, from left to right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row is out of range.
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(
+            row < self.rows,
+            "row {row} is out of range for a matrix of {} rows",
+            self.rows
+        );
+        &self.cells[row * self.columns..(row + 1) * self.columns]
+    }
+}
+
+/// The error from building a matrix out of rows of different lengths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RaggedRows {
+    /// The first row whose length differs from the first row's.
+    pub row: usize,
+    pub columns: usize,
+    pub expected_columns: usize,
+}
+
+impl fmt::Display for RaggedRows {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "row {} has {} columns, but the first row has {}",
+            self.row, self.columns, self.expected_columns
+        )
+    }
+}
+
+impl std::error::Error for RaggedRows {}
+
+/// A rectangle of cells, from row `top` to row `bottom` and from column `left`
+/// to column `right`, all inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatrixRectangle {
+    pub top: usize,
+    pub left: usize,
+    pub bottom: usize,
+    pub right: usize,
+    pub area: u64,
+}
+
+/// Returns the largest rectangle of set cells, or `None` if no cell is set.
+pub fn maximal_rectangle(matrix: &BinaryMatrix) -> Option<MatrixRectangle> {
+    largest_rectangle_where(matrix, |cell| cell)
+}
+
+/// Returns the largest rectangle of cells that all satisfy `predicate`, or
+/// `None` if no cell does. When several rectangles share the largest area, the
+/// one whose bottom row is highest is returned, and among those the leftmost.
+pub fn largest_rectangle_where<T: Copy>(
+    matrix: &Matrix<T>,
+    predicate: impl Fn(T) -> bool,
+) -> Option<MatrixRectangle> {
+    let mut heights = vec![0_i32; matrix.columns()];
+    let mut best: Option<MatrixRectangle> = None;
+    for row in 0..matrix.rows() {
+        stack_row(&mut heights, matrix.row(row), &predicate);
+        let Some(rectangle) = compute_largest_rectangle(&heights[..]) else {
+            continue;
+        };
+        if best.is_none_or(|best| rectangle.area > best.area) {
+            best = Some(MatrixRectangle {
+                top: row + 1 - rectangle.height as usize,
+                left: rectangle.left,
+                bottom: row,
+                right: rectangle.right,
+                area: rectangle.area,
+            });
+        }
+    }
+    best
+}
+
+/// Returns how many rectangles of cells, of any size, contain only set cells.
+///
+/// The rectangles whose bottom row is a given row are counted by summing the
+/// lowest bar over every run of bars in that row's histogram, so this takes
+/// `O(rows × columns)` time.
+///
+/// # Panics
+///
+/// Panics if the count does not fit in a `u64`, which needs more than about
+/// `8.6 × 10⁹` cells.
+pub fn count_all_ones_submatrices(matrix: &BinaryMatrix) -> u64 {
+    let mut heights = vec![0_i32; matrix.columns()];
+    let mut count: u128 = 0;
+    for row in 0..matrix.rows() {
+        stack_row(&mut heights, matrix.row(row), |cell| cell);
+        count += sum_of_subarray_minimums(&heights[..]);
+    }
+    u64::try_from(count).expect("count of submatrices must fit in a u64")
+}
+
+/// Extends each column's run of cells satisfying `predicate` by the next row
+/// down, or ends it.
+fn stack_row<T: Copy>(heights: &mut [i32], row: &[T], predicate: impl Fn(T) -> bool) {
+    for (height, &cell) in heights.iter_mut().zip(row) {
+        *height = if predicate(cell) { *height + 1 } else { 0 };
+    }
+}
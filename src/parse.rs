@@ -0,0 +1,7 @@
+//! The parsers the command-line tool reads its input with: bar heights and
+//! grids as plain text, JSON and CSV. They are part of the library so they
+//! can be fuzzed and reused; none of them panics on malformed input.
+
+pub mod csv;
+pub mod json;
+pub mod plain;
@@ -0,0 +1,145 @@
+//! Reading records from comma-separated values, as described by RFC 4180.
+
+use std::fmt;
+use std::io::{self, BufRead};
+
+/// One record, with the 1-based line on which it starts.
+#[derive(Debug)]
+pub struct Record {
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+/// The error from reading a record.
+#[derive(Debug)]
+pub enum CsvError {
+    Io(io::Error),
+    /// A quoted field with no closing quote, starting on a 1-based line.
+    UnterminatedQuote {
+        line: usize,
+    },
+    /// Something other than a comma or the end of the line after a quoted
+    /// field's closing quote.
+    TextAfterQuote {
+        line: usize,
+    },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(error) => write!(f, "{error}"),
+            CsvError::UnterminatedQuote { line } => {
+                write!(f, "line {line}: quoted field has no closing quote")
+            }
+            CsvError::TextAfterQuote { line } => {
+                write!(f, "line {line}: text after a quoted field's closing quote")
+            }
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(error: io::Error) -> Self {
+        CsvError::Io(error)
+    }
+}
+
+/// Reads records one line at a time, so only the current record is held in
+/// memory. A quoted field may contain commas, line breaks and quotes, which
+/// are written twice. Blank lines are skipped and a line may end in `\r\n`.
+pub struct CsvReader<R> {
+    reader: R,
+    line: usize,
+    buffer: Vec<u8>,
+}
+
+impl<R: BufRead> CsvReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    fn read_line(&mut self) -> io::Result<bool> {
+        let read = self.reader.read_until(b'\n', &mut self.buffer)?;
+        if read > 0 {
+            self.line += 1;
+        }
+        Ok(read > 0)
+    }
+
+    fn read_record(&mut self) -> Result<Option<Record>, CsvError> {
+        loop {
+            self.buffer.clear();
+            if !self.read_line()? {
+                return Ok(None);
+            }
+            if !self.buffer.iter().all(u8::is_ascii_whitespace) {
+                break;
+            }
+        }
+        let start_line = self.line;
+        let mut fields = Vec::new();
+        let mut pos = 0;
+        loop {
+            let mut field = Vec::new();
+            if self.buffer.get(pos) == Some(&b'"') {
+                pos += 1;
+                loop {
+                    match self.buffer.get(pos) {
+                        Some(b'"') if self.buffer.get(pos + 1) == Some(&b'"') => {
+                            field.push(b'"');
+                            pos += 2;
+                        }
+                        Some(b'"') => {
+                            pos += 1;
+                            break;
+                        }
+                        Some(&byte) => {
+                            field.push(byte);
+                            pos += 1;
+                        }
+                        None => {
+                            if !self.read_line()? {
+                                return Err(CsvError::UnterminatedQuote { line: start_line });
+                            }
+                        }
+                    }
+                }
+            } else {
+                while let Some(&byte) = self.buffer.get(pos) {
+                    if byte == b',' || byte == b'\n' {
+                        break;
+                    }
+                    field.push(byte);
+                    pos += 1;
+                }
+                if self.buffer.get(pos) == Some(&b'\n') && field.last() == Some(&b'\r') {
+                    field.pop();
+                }
+            }
+            fields.push(String::from_utf8_lossy(&field).into_owned());
+            match self.buffer.get(pos) {
+                Some(b',') => pos += 1,
+                Some(b'\r') if self.buffer.get(pos + 1) == Some(&b'\n') => break,
+                Some(b'\n') | None => break,
+                Some(_) => return Err(CsvError::TextAfterQuote { line: self.line }),
+            }
+        }
+        Ok(Some(Record {
+            line: start_line,
+            fields,
+        }))
+    }
+}
+
+impl<R: BufRead> Iterator for CsvReader<R> {
+    type Item = Result<Record, CsvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
@@ -0,0 +1,366 @@
+//! Just enough JSON to read bar heights and write results.
+
+use std::fmt;
+
+/// A parsed JSON value. Numbers keep their text, so that heights can be
+/// parsed from it exactly.
+#[derive(Debug)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+/// Invalid JSON, at a 1-based line and byte column.
+#[derive(Debug)]
+pub struct SyntaxError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+/// Arrays and objects nested deeper than this are rejected rather than risk
+/// overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Parses a single JSON value, with nothing but whitespace around it.
+pub fn parse(text: &[u8]) -> Result<Value, SyntaxError> {
+    let mut parser = Parser {
+        text,
+        pos: 0,
+        depth: 0,
+    };
+    if let Err(error) = std::str::from_utf8(text) {
+        parser.pos = error.valid_up_to();
+        return Err(parser.error("invalid UTF-8"));
+    }
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.text.len() {
+        return Err(parser.error("unexpected text after the value"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn value(&mut self) -> Result<Value, SyntaxError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'n') => self.literal("null", Value::Null),
+            Some(b't') => self.literal("true", Value::Bool(true)),
+            Some(b'f') => self.literal("false", Value::Bool(false)),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'{') => self.nested(Self::object),
+            Some(_) => Err(self.error("expected a value")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<Value, SyntaxError>,
+    ) -> Result<Value, SyntaxError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("too deeply nested"));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn array(&mut self) -> Result<Value, SyntaxError> {
+        self.pos += 1;
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.eat(b']') {
+            return Ok(Value::Array(elements));
+        }
+        loop {
+            elements.push(self.value()?);
+            self.skip_whitespace();
+            if self.eat(b']') {
+                return Ok(Value::Array(elements));
+            }
+            if !self.eat(b',') {
+                return Err(self.error("expected `,` or `]`"));
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, SyntaxError> {
+        self.pos += 1;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.eat(b'}') {
+            return Ok(Value::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected a string key"));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            if !self.eat(b':') {
+                return Err(self.error("expected `:`"));
+            }
+            members.push((key, self.value()?));
+            self.skip_whitespace();
+            if self.eat(b'}') {
+                return Ok(Value::Object(members));
+            }
+            if !self.eat(b',') {
+                return Err(self.error("expected `,` or `}`"));
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, SyntaxError> {
+        self.pos += 1;
+        let mut bytes = Vec::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let escaped = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.unicode_escape()?,
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    self.pos += 1;
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                Some(0..=0x1f) => return Err(self.error("control character in string")),
+                Some(byte) => {
+                    bytes.push(byte);
+                    self.pos += 1;
+                }
+            }
+        }
+        self.pos += 1;
+        // The input was checked to be UTF-8 and escapes are pushed as whole
+        // characters, so the bytes are always valid UTF-8.
+        Ok(String::from_utf8(bytes).expect("string must be valid UTF-8"))
+    }
+
+    /// Reads the `XXXX` of a `\uXXXX` escape, and a second escape after it if
+    /// the first is a high surrogate. Leaves `pos` on the last hex digit.
+    fn unicode_escape(&mut self) -> Result<char, SyntaxError> {
+        let high = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            if self.text.get(self.pos + 1..self.pos + 3) != Some(b"\\u") {
+                return Err(self.error("unpaired surrogate"));
+            }
+            self.pos += 2;
+            let low = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("unpaired surrogate"));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("unpaired surrogate"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, SyntaxError> {
+        let digits = self
+            .text
+            .get(self.pos + 1..self.pos + 5)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .filter(|digits| digits.bytes().all(|byte| byte.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error("expected four hex digits"))?;
+        self.pos += 4;
+        Ok(u32::from_str_radix(digits, 16).expect("hex digits must parse"))
+    }
+
+    fn number(&mut self) -> Result<Value, SyntaxError> {
+        let start = self.pos;
+        self.eat(b'-');
+        if !self.eat(b'0') && self.digits() == 0 {
+            return Err(self.error("expected a digit"));
+        }
+        if self.eat(b'.') && self.digits() == 0 {
+            return Err(self.error("expected a digit"));
+        }
+        if self.eat(b'e') || self.eat(b'E') {
+            if !self.eat(b'+') {
+                self.eat(b'-');
+            }
+            if self.digits() == 0 {
+                return Err(self.error("expected a digit"));
+            }
+        }
+        let text = std::str::from_utf8(&self.text[start..self.pos]).expect("digits are ASCII");
+        Ok(Value::Number(text.to_string()))
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn literal(&mut self, word: &str, value: Value) -> Result<Value, SyntaxError> {
+        if !self.text[self.pos..].starts_with(word.as_bytes()) {
+            return Err(self.error("expected a value"));
+        }
+        self.pos += word.len();
+        Ok(value)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let found = self.peek() == Some(byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> SyntaxError {
+        let before = &self.text[..self.pos.min(self.text.len())];
+        let line_start = before
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(0, |newline| newline + 1);
+        SyntaxError {
+            line: before.iter().filter(|&&byte| byte == b'\n').count() + 1,
+            column: before.len() - line_start + 1,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Returns the bars of a bare array of heights, or of the `bars` member of an
+/// object, or describes what is wrong with the value's shape.
+pub fn bars(value: Value) -> Result<Vec<i32>, String> {
+    let elements = match value {
+        Value::Array(elements) => elements,
+        Value::Object(members) => match members.into_iter().find(|(key, _)| key == "bars") {
+            Some((_, Value::Array(elements))) => elements,
+            Some(_) => return Err("`bars` is not an array".to_string()),
+            None => return Err("object has no `bars` member".to_string()),
+        },
+        _ => return Err("expected an array of bar heights or an object with `bars`".to_string()),
+    };
+    elements
+        .into_iter()
+        .enumerate()
+        .map(|(index, element)| match element {
+            Value::Number(text) => text
+                .parse()
+                .map_err(|_| format!("bar {index}: `{text}` is not a bar height")),
+            Value::String(text) => Err(format!(
+                "bar {index} is the string {}, not a number",
+                quote(&text)
+            )),
+            Value::Bool(value) => Err(format!("bar {index} is `{value}`, not a number")),
+            _ => Err(format!("bar {index} is not a number")),
+        })
+        .collect()
+}
+
+/// Returns the rows of a grid given as a bare array of rows or as the `rows`
+/// member of an object, each row an array of `0`, `1` or booleans, or
+/// describes what is wrong with the value's shape.
+pub fn rows(value: Value) -> Result<Vec<Vec<bool>>, String> {
+    let rows = match value {
+        Value::Array(rows) => rows,
+        Value::Object(members) => match members.into_iter().find(|(key, _)| key == "rows") {
+            Some((_, Value::Array(rows))) => rows,
+            Some(_) => return Err("`rows` is not an array".to_string()),
+            None => return Err("object has no `rows` member".to_string()),
+        },
+        _ => return Err("expected an array of rows or an object with `rows`".to_string()),
+    };
+    let mut grid: Vec<Vec<bool>> = Vec::with_capacity(rows.len());
+    for (row, cells) in rows.into_iter().enumerate() {
+        let Value::Array(cells) = cells else {
+            return Err(format!("row {row} is not an array"));
+        };
+        let cells = cells
+            .into_iter()
+            .enumerate()
+            .map(|(column, cell)| match cell {
+                Value::Bool(cell) => Ok(cell),
+                Value::Number(text) if text == "0" || text == "1" => Ok(text == "1"),
+                _ => Err(format!(
+                    "row {row}, column {column} is not a cell, which must be 0, 1 or a boolean"
+                )),
+            })
+            .collect::<Result<Vec<bool>, String>>()?;
+        if let Some(first) = grid.first() {
+            if cells.len() != first.len() {
+                return Err(format!(
+                    "row {row} has {} cells, but the first row has {}",
+                    cells.len(),
+                    first.len()
+                ));
+            }
+        }
+        grid.push(cells);
+    }
+    Ok(grid)
+}
+
+/// Writes `text` as a JSON string literal, quotes included.
+pub fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c < ' ' => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,153 @@
+//! Reading bar heights and grids of cells from text.
+
+use std::fmt;
+use std::io::{self, BufRead};
+
+/// The error from reading bar heights.
+#[derive(Debug)]
+pub enum InputError {
+    Io(io::Error),
+    /// A token that is not a bar height, at a 1-based line and column.
+    Parse {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    /// A character that is not a cell of a grid.
+    Cell {
+        line: usize,
+        column: usize,
+        cell: char,
+    },
+    /// A row of a grid with a different number of cells from the first row.
+    Ragged {
+        line: usize,
+        cells: usize,
+        expected: usize,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Io(error) => write!(f, "{error}"),
+            InputError::Parse {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {line}, column {column}: `{token}` is not a bar height"
+            ),
+            InputError::Cell { line, column, cell } => write!(
+                f,
+                "line {line}, column {column}: `{cell}` is not a cell, which must be 0 or 1"
+            ),
+            InputError::Ragged {
+                line,
+                cells,
+                expected,
+            } => write!(
+                f,
+                "line {line}: row has {cells} cells, but the first row has {expected}"
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for InputError {
+    fn from(error: io::Error) -> Self {
+        InputError::Io(error)
+    }
+}
+
+/// Reads heights separated by any whitespace, including blank lines, until
+/// the end of the input. Each token is parsed as soon as it ends, so only the
+/// heights themselves are held in memory.
+pub fn read_heights<R: BufRead>(mut reader: R) -> Result<Vec<i32>, InputError> {
+    let mut heights = Vec::new();
+    let mut token = Vec::new();
+    let (mut line, mut column) = (1, 1);
+    let mut token_column = 1;
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            break;
+        }
+        for &byte in buffer {
+            if byte.is_ascii_whitespace() {
+                if !token.is_empty() {
+                    heights.push(parse_height(&token, line, token_column)?);
+                    token.clear();
+                }
+            } else {
+                if token.is_empty() {
+                    token_column = column;
+                }
+                token.push(byte);
+            }
+            if byte == b'\n' {
+                (line, column) = (line + 1, 1);
+            } else {
+                column += 1;
+            }
+        }
+        let consumed = buffer.len();
+        reader.consume(consumed);
+    }
+    if !token.is_empty() {
+        heights.push(parse_height(&token, line, token_column)?);
+    }
+    Ok(heights)
+}
+
+fn parse_height(token: &[u8], line: usize, column: usize) -> Result<i32, InputError> {
+    std::str::from_utf8(token)
+        .ok()
+        .and_then(|token| token.parse().ok())
+        .ok_or_else(|| InputError::Parse {
+            line,
+            column,
+            token: String::from_utf8_lossy(token).into_owned(),
+        })
+}
+
+/// Reads a grid with one row per line and each cell written as `0` or `1`,
+/// optionally separated by whitespace. Blank lines are skipped, and every row
+/// must have as many cells as the first.
+pub fn read_rows<R: BufRead>(reader: R) -> Result<Vec<Vec<bool>>, InputError> {
+    let mut rows: Vec<Vec<bool>> = Vec::new();
+    for (index, text) in reader.split(b'\n').enumerate() {
+        let (text, line) = (text?, index + 1);
+        let mut row = Vec::new();
+        for (offset, &byte) in text.iter().enumerate() {
+            match byte {
+                b'0' => row.push(false),
+                b'1' => row.push(true),
+                _ if byte.is_ascii_whitespace() => {}
+                _ => {
+                    let rest = String::from_utf8_lossy(&text[offset..]);
+                    return Err(InputError::Cell {
+                        line,
+                        column: offset + 1,
+                        cell: rest.chars().next().unwrap_or(char::REPLACEMENT_CHARACTER),
+                    });
+                }
+            }
+        }
+        if row.is_empty() {
+            continue;
+        }
+        if let Some(first) = rows.first() {
+            if row.len() != first.len() {
+                return Err(InputError::Ragged {
+                    line,
+                    cells: row.len(),
+                    expected: first.len(),
+                });
+            }
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
@@ -0,0 +1,63 @@
+use crate::histogram::{Height, Histogram};
+use crate::rectangle::Rectangle;
+use crate::square_search::lowest_bar::LowestBar;
+use crate::square_search::rank;
+use std::cmp;
+
+/// Answers largest-rectangle queries restricted to a range of bars.
+///
+/// Building takes `O(n log n)` time for a sparse table locating the lowest bar
+/// of any range in constant time. Each query then splits its range around the
+/// lowest bar, so it takes time proportional to the length of the range
+/// rather than to the width of the whole histogram.
+pub struct RangeLargestRectangle<T = i32> {
+    lowest_bar: LowestBar<Vec<T>>,
+}
+
+impl<T: Height> RangeLargestRectangle<T> {
+    pub fn new<H: Histogram<T> + ?Sized>(histogram: &H) -> Self {
+        let heights: Vec<T> = (0..histogram.width())
+            .map(|x_pos| histogram.height_at(x_pos))
+            .collect();
+        Self {
+            lowest_bar: LowestBar::new(heights),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.lowest_bar.heights().len()
+    }
+
+    /// Returns the largest rectangle using only the bars in `left..right`,
+    /// with ties going to the leftmost rectangle. Returns `None` if the range
+    /// is empty, extends past the histogram, or holds no rectangle of positive
+    /// area.
+    pub fn query(&self, left: usize, right: usize) -> Option<Rectangle<T>> {
+        if left >= right || right > self.width() {
+            return None;
+        }
+        let mut largest_rectangle = None;
+        let mut pending_ranges = vec![(left, right)];
+        while let Some((start, end)) = pending_ranges.pop() {
+            let lowest = self.lowest_bar.position(start, end);
+            let height = self.lowest_bar.heights()[lowest];
+            if height > T::ZERO {
+                let candidate = Rectangle {
+                    left: start,
+                    right: end - 1,
+                    height,
+                    area: height.area(end - start),
+                };
+                largest_rectangle =
+                    cmp::max_by_key(largest_rectangle, Some(candidate), |r| r.as_ref().map(rank));
+            }
+            if start < lowest {
+                pending_ranges.push((start, lowest));
+            }
+            if lowest + 1 < end {
+                pending_ranges.push((lowest + 1, end));
+            }
+        }
+        largest_rectangle
+    }
+}
@@ -0,0 +1,34 @@
+use crate::histogram::Height;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rectangle<T: Height = i32> {
+    pub left: usize,
+    pub right: usize,
+    pub height: T,
+    pub area: T::Area,
+}
+
+impl<T: Height> Rectangle<T> {
+    pub fn width(&self) -> usize {
+        self.right - self.left + 1
+    }
+}
+
+/// A rectangle placed at a height: it spans the bars from `left` to `right`,
+/// both inclusive, and rises from `bottom` to `top`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rectangle2D {
+    pub left: usize,
+    pub right: usize,
+    pub bottom: i32,
+    pub top: i32,
+    pub area: u64,
+}
+
+impl Rectangle2D {
+    pub fn width(&self) -> usize {
+        self.right - self.left + 1
+    }
+}
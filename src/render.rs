@@ -0,0 +1,363 @@
+use crate::histogram::Histogram;
+use crate::rectangle::Rectangle;
+use std::fmt::Write as _;
+use std::{cmp, fmt};
+
+/// Controls the layout of `to_ascii_with_options`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AsciiOptions {
+    /// The most bars drawn side by side, or `None` for no limit.
+    pub max_columns: Option<usize>,
+    /// What to do with the bars beyond `max_columns`.
+    pub overflow: Overflow,
+    /// Bars whose index is a multiple of this are labelled below the axis.
+    /// Zero leaves every bar unlabelled.
+    pub label_interval: usize,
+}
+
+impl Default for AsciiOptions {
+    fn default() -> Self {
+        Self {
+            max_columns: Some(80),
+            overflow: Overflow::Wrap,
+            label_interval: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Draws the remaining bars in further blocks below, separated by a blank
+    /// line.
+    Wrap,
+    /// Leaves the remaining bars out, marking the cut with `...` after the
+    /// axis.
+    Truncate,
+}
+
+/// Draws the histogram with the default options. See `to_ascii_with_options`.
+pub fn to_ascii<H: Histogram + ?Sized>(histogram: &H) -> String {
+    to_ascii_with_options(histogram, &AsciiOptions::default())
+}
+
+/// Draws each bar as a column of `#`, one row per unit of height, above an
+/// axis line and the labels of every `label_interval`th bar. Bars of height
+/// zero or less leave their column blank, and trailing spaces are trimmed.
+/// An empty histogram draws as an empty string.
+///
+/// # Panics
+///
+/// Panics if `max_columns` is `Some(0)`.
+pub fn to_ascii_with_options<H: Histogram + ?Sized>(
+    histogram: &H,
+    options: &AsciiOptions,
+) -> String {
+    render(histogram, options, |_, _| '#')
+}
+
+/// Draws the histogram as `to_ascii` does, with the cells covered by
+/// `rectangle` drawn as `*` instead of `#`.
+///
+/// Returns an error instead if `rectangle` does not lie under the histogram or
+/// is not a rectangle of positive area.
+pub fn to_ascii_with_rectangle<H: Histogram + ?Sized>(
+    histogram: &H,
+    rectangle: &Rectangle,
+) -> Result<String, RectangleMisfit> {
+    to_ascii_with_rectangle_and_options(histogram, rectangle, &AsciiOptions::default())
+}
+
+/// Draws the histogram as `to_ascii_with_options` does, with the cells
+/// covered by `rectangle` drawn as `*` instead of `#`. Cells left out by
+/// `Overflow::Truncate` are not drawn even if the rectangle covers them.
+///
+/// Returns an error instead if `rectangle` does not lie under the histogram,
+/// as drawn with bars of negative height at zero, or is not a rectangle of
+/// positive area.
+///
+/// # Panics
+///
+/// Panics if `max_columns` is `Some(0)`.
+pub fn to_ascii_with_rectangle_and_options<H: Histogram + ?Sized>(
+    histogram: &H,
+    rectangle: &Rectangle,
+    options: &AsciiOptions,
+) -> Result<String, RectangleMisfit> {
+    let width = histogram.width();
+    if rectangle.left > rectangle.right || rectangle.right >= width {
+        return Err(RectangleMisfit::OutOfBounds {
+            left: rectangle.left,
+            right: rectangle.right,
+            width,
+        });
+    }
+    if rectangle.height <= 0 {
+        return Err(RectangleMisfit::NotPositive {
+            height: rectangle.height,
+        });
+    }
+    let expected = rectangle.height as u64 * (rectangle.right - rectangle.left + 1) as u64;
+    if rectangle.area != expected {
+        return Err(RectangleMisfit::WrongArea {
+            area: rectangle.area,
+            expected,
+        });
+    }
+    if let Some(bar_index) = (rectangle.left..=rectangle.right)
+        .find(|&x_pos| cmp::max(histogram.height_at(x_pos), 0) < rectangle.height)
+    {
+        return Err(RectangleMisfit::AboveBar { bar_index });
+    }
+    let covers =
+        |x_pos, row| (rectangle.left..=rectangle.right).contains(&x_pos) && row <= rectangle.height;
+    Ok(render(histogram, options, |x_pos, row| {
+        if covers(x_pos, row) {
+            '*'
+        } else {
+            '#'
+        }
+    }))
+}
+
+/// The ways a rectangle can fail to lie under a histogram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RectangleMisfit {
+    /// The rectangle's columns are reversed or run past the last bar.
+    OutOfBounds {
+        left: usize,
+        right: usize,
+        width: usize,
+    },
+    /// The rectangle rises above the bar at `bar_index`.
+    AboveBar { bar_index: usize },
+    /// The rectangle has no positive height, so it would cover no cell.
+    NotPositive { height: i32 },
+    /// The rectangle's area is not its height times its width.
+    WrongArea { area: u64, expected: u64 },
+}
+
+impl fmt::Display for RectangleMisfit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RectangleMisfit::OutOfBounds { left, right, width } => write!(
+                f,
+                "columns {left}..={right} do not fit a histogram of width {width}"
+            ),
+            RectangleMisfit::AboveBar { bar_index } => {
+                write!(f, "the rectangle rises above bar {bar_index}")
+            }
+            RectangleMisfit::NotPositive { height } => {
+                write!(f, "the rectangle has height {height}, which covers nothing")
+            }
+            RectangleMisfit::WrongArea { area, expected } => write!(
+                f,
+                "the rectangle has area {area}, but its height and width make {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RectangleMisfit {}
+
+/// Controls the drawing made by `to_svg`. Lengths are in pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    pub bar_width: u32,
+    /// The height drawn for each unit of bar height.
+    pub unit_height: u32,
+    /// The space left clear around the bars on every side.
+    pub margin: u32,
+    pub bar_fill: String,
+    pub bar_stroke: String,
+    pub rectangle_fill: String,
+    /// How opaque the rectangle's fill is, from 0 to 1.
+    pub rectangle_opacity: f64,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            bar_width: 20,
+            unit_height: 20,
+            margin: 10,
+            bar_fill: "steelblue".to_string(),
+            bar_stroke: "white".to_string(),
+            rectangle_fill: "orange".to_string(),
+            rectangle_opacity: 0.5,
+        }
+    }
+}
+
+/// Draws the histogram as a standalone SVG document, with each bar of
+/// positive height as a `<rect>` and `rectangle`, if given, as a translucent
+/// `<rect>` over them. The rectangle is drawn where it says, so giving one
+/// that does not lie under the histogram shows it sticking out. An empty
+/// histogram draws as an SVG holding only its margins.
+pub fn to_svg<H: Histogram + ?Sized>(
+    histogram: &H,
+    rectangle: Option<&Rectangle>,
+    options: &SvgOptions,
+) -> String {
+    let bar_width = u64::from(options.bar_width);
+    let unit_height = u64::from(options.unit_height);
+    let margin = u64::from(options.margin);
+    let heights: Vec<u64> = (0..histogram.width())
+        .map(|x_pos| cmp::max(histogram.height_at(x_pos), 0) as u64)
+        .collect();
+    let drawn_width = cmp::max(
+        heights.len() as u64,
+        rectangle.map_or(0, |rectangle| rectangle.right as u64 + 1),
+    );
+    let top = heights
+        .iter()
+        .copied()
+        .chain(rectangle.map(|rectangle| cmp::max(rectangle.height, 0) as u64))
+        .max()
+        .unwrap_or(0);
+    let canvas_width = 2 * margin + drawn_width * bar_width;
+    let canvas_height = 2 * margin + top * unit_height;
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{canvas_width}" height="{canvas_height}" viewBox="0 0 {canvas_width} {canvas_height}">"#
+    );
+    for (x_pos, &height) in heights.iter().enumerate() {
+        if height == 0 {
+            continue;
+        }
+        let _ = writeln!(
+            out,
+            r#"  <rect x="{}" y="{}" width="{bar_width}" height="{}" fill="{}" stroke="{}"/>"#,
+            margin + x_pos as u64 * bar_width,
+            margin + (top - height) * unit_height,
+            height * unit_height,
+            escape(&options.bar_fill),
+            escape(&options.bar_stroke),
+        );
+    }
+    if let Some(rectangle) = rectangle {
+        let height = cmp::max(rectangle.height, 0) as u64;
+        let _ = writeln!(
+            out,
+            r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="{}" fill-opacity="{}"/>"#,
+            margin + rectangle.left as u64 * bar_width,
+            margin + (top - height) * unit_height,
+            (rectangle.right + 1).saturating_sub(rectangle.left) as u64 * bar_width,
+            height * unit_height,
+            escape(&options.rectangle_fill),
+            options.rectangle_opacity.clamp(0.0, 1.0),
+        );
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Escapes the characters that cannot appear as themselves in a quoted XML
+/// attribute.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Draws the histogram as `to_ascii_with_options` does, taking the character
+/// for the cell at each bar and row, counted from one at the bottom, from
+/// `fill`.
+fn render<H: Histogram + ?Sized>(
+    histogram: &H,
+    options: &AsciiOptions,
+    fill: impl Fn(usize, i32) -> char,
+) -> String {
+    let width = histogram.width();
+    let columns = options.max_columns.unwrap_or(width);
+    assert!(columns > 0, "max_columns must be non-zero");
+    let heights: Vec<i32> = (0..width)
+        .map(|x_pos| cmp::max(histogram.height_at(x_pos), 0))
+        .collect();
+    let truncated = options.overflow == Overflow::Truncate && width > columns;
+    let drawn_width = if truncated { columns } else { width };
+
+    let mut out = String::new();
+    for start in (0..drawn_width).step_by(columns) {
+        let end = cmp::min(start + columns, drawn_width);
+        if start > 0 {
+            out.push('\n');
+        }
+        let top = heights[start..end].iter().copied().max().unwrap_or(0);
+        for row in (1..=top).rev() {
+            let line: String = (start..end)
+                .map(|x_pos| {
+                    if heights[x_pos] >= row {
+                        fill(x_pos, row)
+                    } else {
+                        ' '
+                    }
+                })
+                .collect();
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out.extend(std::iter::repeat_n('-', end - start));
+        if truncated {
+            out.push_str("...");
+        }
+        out.push('\n');
+        let labels = label_line(start..end, options.label_interval);
+        if !labels.is_empty() {
+            out.push_str(&labels);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Writes the index of each labelled bar starting under its column, leaving
+/// out any label that would run into the one before it.
+fn label_line(bars: std::ops::Range<usize>, interval: usize) -> String {
+    let mut line = String::new();
+    if interval == 0 {
+        return line;
+    }
+    let start = bars.start;
+    for x_pos in bars.filter(|x_pos| x_pos % interval == 0) {
+        let column = x_pos - start;
+        if line.is_empty() || line.len() < column {
+            line.extend(std::iter::repeat_n(' ', column - line.len()));
+            line.push_str(&x_pos.to_string());
+        }
+    }
+    line
+}
+
+/// Returns the histogram's outline as the points where its height changes.
+///
+/// Each point `(x, height)` means the bars from `x` up to the next point's
+/// `x` all have that height, so runs of equal bars give a single point. The
+/// first point is at `x = 0` and the last is `(width, 0)`, marking where the
+/// histogram ends. An empty histogram has no points. Heights are given as they
+/// are, including negative ones. `ConcreteHistogram::from_outline` is the
+/// inverse.
+pub fn outline<H: Histogram + ?Sized>(histogram: &H) -> Vec<(u64, i32)> {
+    let mut points: Vec<(u64, i32)> = Vec::new();
+    for x_pos in 0..histogram.width() {
+        let height = histogram.height_at(x_pos);
+        if points
+            .last()
+            .is_none_or(|&(_, previous)| previous != height)
+        {
+            points.push((x_pos as u64, height));
+        }
+    }
+    if !points.is_empty() {
+        points.push((histogram.width() as u64, 0));
+    }
+    points
+}
@@ -0,0 +1,1162 @@
+use crate::histogram::{Height, Histogram};
+use crate::histogram_concrete::ConcreteHistogram;
+use crate::rectangle::Rectangle;
+use std::any::Any;
+use std::cmp::{self, Reverse};
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::mem;
+use std::ops::{ControlFlow, Range};
+
+mod area_distribution;
+mod between;
+mod boundaries;
+mod candidates;
+mod containing;
+mod contiguous;
+mod decompose;
+mod divide_and_conquer;
+mod height_width;
+pub(crate) mod lowest_bar;
+pub mod naive;
+mod parallel;
+mod reorderable;
+mod run_length;
+mod scratch;
+mod sliding_window;
+mod sparse;
+mod stack;
+mod stats;
+mod streaming;
+mod trace;
+mod trapped_water;
+mod violations;
+mod weighted;
+
+pub use area_distribution::{candidate_area_histogram, AreaDistribution};
+pub use between::{largest_rectangle_between, WidthMismatch};
+pub use boundaries::{boundaries, per_bar_maximal_areas, sum_of_subarray_minimums, Boundaries};
+pub use candidates::rectangles_with_area_at_least;
+pub use containing::largest_rectangle_containing;
+pub(crate) use containing::largest_rectangle_through;
+pub use decompose::decompose_into_rectangles;
+pub use divide_and_conquer::compute_area_divide_and_conquer;
+pub use height_width::{
+    best_height_per_width, max_height_at_width, max_height_at_width_with_position,
+    max_width_at_height, HeightWidthIndex,
+};
+pub use parallel::{compute_area_parallel, compute_area_parallel_with, compute_areas_parallel};
+pub use reorderable::{compute_area_if_reorderable, compute_best_reordering};
+pub use run_length::compute_area_of_largest_rectangle_run_length;
+pub use scratch::{compute_area_of_largest_rectangle_with_scratch, ScratchTooSmall};
+pub use sliding_window::sliding_window_largest_rectangle;
+pub use sparse::compute_largest_rectangle_sparse;
+use stack::{Stack, StackEntry};
+pub use stats::{compute_area_with_stats, SearchStats};
+pub use streaming::StreamingSearcher;
+pub use trace::{compute_with_trace, SearchEvent, SearchTrace};
+pub use trapped_water::trapped_water;
+pub use violations::compute_largest_rectangle_with_violations;
+pub use weighted::compute_area_of_largest_rectangle_weighted;
+
+/// Returns 0 when no rectangle of positive area exists.
+pub fn compute_area_of_largest_rectangle<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+) -> T::Area {
+    compute_largest_rectangle(histogram).map_or_else(T::Area::default, |rectangle| rectangle.area)
+}
+
+/// Returns the largest rectangle under the histogram. When several rectangles
+/// share the largest area, the leftmost one is returned. Returns `None` when no
+/// rectangle of positive area exists, i.e. for an empty histogram or one whose
+/// bars all have height zero.
+///
+/// Bars of negative height are treated as having height zero, here and in
+/// every other search in this module; see `NegativePolicy` to reject them
+/// instead.
+///
+/// With the `paranoid` feature, histograms no wider than the limit set by
+/// `set_crosscheck_limit` are also searched by `naive::compute_largest_rectangle`,
+/// and any disagreement panics.
+pub fn compute_largest_rectangle<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+) -> Option<Rectangle<T>> {
+    let (largest_rectangle, searched_width) = match histogram.as_slice() {
+        Some(bars) => (contiguous::largest_rectangle_in_slice(bars), bars.len()),
+        None => {
+            let mut searcher = LargestRectangleSearcher::new(histogram);
+            let mut largest_rectangle: Option<Rectangle<T>> = None;
+            searcher.for_each_candidate(|candidate| {
+                if largest_rectangle.is_none_or(|largest| rank(&candidate) > rank(&largest)) {
+                    largest_rectangle = Some(candidate);
+                }
+            });
+            (largest_rectangle, searcher.width())
+        }
+    };
+    #[cfg(feature = "paranoid")]
+    crosscheck(histogram, searched_width, largest_rectangle);
+    #[cfg(not(feature = "paranoid"))]
+    let _ = searched_width;
+    largest_rectangle
+}
+
+#[cfg(feature = "paranoid")]
+static CROSSCHECK_LIMIT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(256);
+
+/// Sets the widest histogram that `compute_largest_rectangle` cross-checks
+/// against the naive search. The limit is shared by every thread, and starts
+/// at 256 bars.
+#[cfg(feature = "paranoid")]
+pub fn set_crosscheck_limit(max_width: usize) {
+    CROSSCHECK_LIMIT.store(max_width, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Checks `found` against the naive search of the `width` bars the search
+/// counted, so a histogram whose width changes is checked against the bars
+/// that were searched.
+#[cfg(feature = "paranoid")]
+fn crosscheck<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+    width: usize,
+    found: Option<Rectangle<T>>,
+) {
+    if width > CROSSCHECK_LIMIT.load(std::sync::atomic::Ordering::Relaxed) {
+        return;
+    }
+    let searched = FirstBars { histogram, width };
+    let expected = naive::compute_largest_rectangle(&searched);
+    if found != expected {
+        let bars: Vec<T> = (0..width).map(|x_pos| histogram.height_at(x_pos)).collect();
+        panic!(
+            "cross-check failed for bars {bars:?}: the search found {found:?} but the naive \
+             search found {expected:?}"
+        );
+    }
+}
+
+/// The first `width` bars of a histogram, whatever width it reports.
+#[cfg(feature = "paranoid")]
+struct FirstBars<'a, H: ?Sized> {
+    histogram: &'a H,
+    width: usize,
+}
+
+#[cfg(feature = "paranoid")]
+impl<T, H: Histogram<T> + ?Sized> Histogram<T> for FirstBars<'_, H> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> T {
+        assert!(horizontal_position < self.width);
+        self.histogram.height_at(horizontal_position)
+    }
+}
+
+/// The ways `compute_with` can search a histogram. Both give the same area.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    /// The stack of bars of increasing height used by
+    /// `compute_area_of_largest_rectangle`.
+    #[default]
+    MonotonicStack,
+    /// The search of `compute_area_divide_and_conquer`.
+    DivideAndConquer,
+}
+
+/// Returns the area of the largest rectangle as found by `algorithm`.
+pub fn compute_with<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+    algorithm: Algorithm,
+) -> T::Area {
+    match algorithm {
+        Algorithm::MonotonicStack => compute_area_of_largest_rectangle(histogram),
+        Algorithm::DivideAndConquer => compute_area_divide_and_conquer(histogram),
+    }
+}
+
+/// Decides which rectangle is reported when several share the largest area.
+/// Any ties remaining after applying the policy go to the leftmost rectangle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// The rectangle with the smallest left index, then the narrowest one.
+    /// This is the default, and will remain so.
+    #[default]
+    Leftmost,
+    /// The rectangle with the greatest right index, then the narrowest one.
+    Rightmost,
+    /// The rectangle spanning the most bars.
+    Widest,
+    /// The rectangle with the greatest height.
+    Tallest,
+}
+
+pub fn compute_largest_rectangle_with<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+    tie_break: TieBreak,
+) -> Option<Rectangle<T>> {
+    match tie_break {
+        TieBreak::Leftmost => compute_best_by(histogram, |rectangle| rectangle.area),
+        TieBreak::Rightmost => compute_best_by(histogram, |rectangle| {
+            (rectangle.area, rectangle.right, rectangle.left)
+        }),
+        TieBreak::Widest => {
+            compute_best_by(histogram, |rectangle| (rectangle.area, rectangle.width()))
+        }
+        TieBreak::Tallest => {
+            compute_best_by(histogram, |rectangle| (rectangle.area, rectangle.height))
+        }
+    }
+}
+
+/// Decides how bars of negative height are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NegativePolicy {
+    /// Negative heights are treated as zero. This is what every search does
+    /// when no policy is given.
+    #[default]
+    Clamp,
+    /// The first bar of negative height is reported as an error.
+    Reject,
+}
+
+pub fn compute_largest_rectangle_with_policy<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+    policy: NegativePolicy,
+) -> Result<Option<Rectangle<T>>, NegativeHeight> {
+    if policy == NegativePolicy::Reject {
+        if let Some(bar_index) =
+            (0..histogram.width()).find(|&x_pos| histogram.height_at(x_pos) < T::ZERO)
+        {
+            return Err(NegativeHeight { bar_index });
+        }
+    }
+    Ok(compute_largest_rectangle(histogram))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegativeHeight {
+    pub bar_index: usize,
+}
+
+impl fmt::Display for NegativeHeight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bar {} has a negative height", self.bar_index)
+    }
+}
+
+impl std::error::Error for NegativeHeight {}
+
+/// Calls `f` with every candidate rectangle found by the search. Each maximal
+/// rectangle is visited exactly once, in the order the search discovers them.
+pub fn for_each_candidate<T: Height, H: Histogram<T> + ?Sized, F: FnMut(Rectangle<T>)>(
+    histogram: &H,
+    f: F,
+) {
+    LargestRectangleSearcher::new(histogram).for_each_candidate(f);
+}
+
+/// Returns the candidate rectangle with the greatest key. Ties are broken in
+/// favour of the leftmost, then narrowest, rectangle.
+pub fn compute_best_by<
+    T: Height,
+    H: Histogram<T> + ?Sized,
+    K: Ord,
+    F: FnMut(&Rectangle<T>) -> K,
+>(
+    histogram: &H,
+    key_fn: F,
+) -> Option<Rectangle<T>> {
+    compute_best_derived_by(histogram, Some, key_fn)
+}
+
+/// Like `compute_best_by`, but ranks the rectangles that `derive` produces from
+/// each candidate rather than the candidates themselves.
+fn compute_best_derived_by<T, H, D, K, F>(
+    histogram: &H,
+    mut derive: D,
+    mut key_fn: F,
+) -> Option<Rectangle<T>>
+where
+    T: Height,
+    H: Histogram<T> + ?Sized,
+    D: FnMut(Rectangle<T>) -> Option<Rectangle<T>>,
+    K: Ord,
+    F: FnMut(&Rectangle<T>) -> K,
+{
+    let mut best: Option<(K, Rectangle<T>)> = None;
+    for_each_candidate(histogram, |candidate| {
+        let Some(rectangle) = derive(candidate) else {
+            return;
+        };
+        let key = key_fn(&rectangle);
+        let is_better = best.as_ref().is_none_or(|(best_key, best_rectangle)| {
+            (&key, Reverse(rectangle.left), Reverse(rectangle.right))
+                > (
+                    best_key,
+                    Reverse(best_rectangle.left),
+                    Reverse(best_rectangle.right),
+                )
+        });
+        if is_better {
+            best = Some((key, rectangle));
+        }
+    });
+    best.map(|(_, rectangle)| rectangle)
+}
+
+/// Bounds on the rectangles considered by
+/// `compute_largest_rectangle_constrained`. `None` leaves a bound unset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchOptions<T = i32> {
+    pub min_width: Option<usize>,
+    pub max_width: Option<usize>,
+    pub min_height: Option<T>,
+    pub max_height: Option<T>,
+}
+
+/// Returns the largest rectangle whose width and height lie within the bounds
+/// of `options`, or `None` if no rectangle of positive area satisfies them.
+///
+/// A candidate that is too wide or too tall is cut down to the bounds rather
+/// than discarded, keeping its left edge.
+pub fn compute_largest_rectangle_constrained<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+    options: &SearchOptions<T>,
+) -> Option<Rectangle<T>> {
+    compute_best_derived_by(
+        histogram,
+        |candidate| clamp_to_options(candidate, options),
+        |rectangle| rectangle.area,
+    )
+}
+
+/// Returns the largest square under the histogram, as a rectangle whose
+/// height equals its width. Ties go to the leftmost square.
+pub fn compute_largest_square<H: Histogram + ?Sized>(histogram: &H) -> Option<Rectangle> {
+    compute_best_derived_by(
+        histogram,
+        |candidate| {
+            let side = cmp::min(candidate.width(), candidate.height as usize);
+            Some(Rectangle {
+                left: candidate.left,
+                right: candidate.left + side - 1,
+                height: side as i32,
+                area: side as u64 * side as u64,
+            })
+        },
+        |square| square.area,
+    )
+}
+
+/// Returns the rectangle with the greatest perimeter, `2 * (width + height)`.
+/// Ties go to the leftmost rectangle.
+pub fn compute_largest_rectangle_by_perimeter<H: Histogram + ?Sized>(
+    histogram: &H,
+) -> Option<Rectangle> {
+    compute_best_by(histogram, |candidate| {
+        2 * (candidate.width() as u64 + candidate.height as u64)
+    })
+}
+
+/// Returns the largest rectangle whose width-to-height ratio lies within
+/// `min_ratio..=max_ratio`, or `None` if no rectangle satisfies it. No ratio
+/// is negative, so a negative `min_ratio` bounds nothing and acts as `0.0`.
+///
+/// Each candidate is shrunk to its best sub-rectangle with an acceptable
+/// ratio, which takes time proportional to the smaller of its dimensions in
+/// the worst case.
+pub fn compute_largest_rectangle_with_aspect<H: Histogram + ?Sized>(
+    histogram: &H,
+    min_ratio: f64,
+    max_ratio: f64,
+) -> Option<Rectangle> {
+    let min_ratio = min_ratio.max(0.0);
+    let has_acceptable_ratio = min_ratio <= max_ratio && max_ratio > 0.0;
+    if !has_acceptable_ratio {
+        return None;
+    }
+    compute_best_derived_by(
+        histogram,
+        |candidate| {
+            let (width, height) = best_dimensions_with_aspect(
+                candidate.width(),
+                candidate.height as usize,
+                min_ratio,
+                max_ratio,
+            )?;
+            Some(Rectangle {
+                left: candidate.left,
+                right: candidate.left + width - 1,
+                height: height as i32,
+                area: width as u64 * height as u64,
+            })
+        },
+        |rectangle| rectangle.area,
+    )
+}
+
+/// The largest `(width, height)` fitting in `max_width` by `max_height` whose
+/// ratio lies within `min_ratio..=max_ratio`. For a fixed height the widest
+/// acceptable width is best, and the resulting area only grows with the
+/// height, so the tallest feasible height wins (and symmetrically for widths).
+/// Whichever dimension has fewer values to try is scanned.
+fn best_dimensions_with_aspect(
+    max_width: usize,
+    max_height: usize,
+    min_ratio: f64,
+    max_ratio: f64,
+) -> Option<(usize, usize)> {
+    let tallest = cmp::min(max_height, (max_width as f64 / min_ratio) as usize);
+    let widest = cmp::min(max_width, (max_height as f64 * max_ratio) as usize);
+    if widest <= tallest {
+        (1..=widest).rev().find_map(|width| {
+            let height = cmp::min(max_height, (width as f64 / min_ratio) as usize);
+            (height >= 1 && width as f64 <= max_ratio * height as f64).then_some((width, height))
+        })
+    } else {
+        (1..=tallest).rev().find_map(|height| {
+            let width = cmp::min(max_width, (height as f64 * max_ratio) as usize);
+            (width >= 1 && width as f64 >= min_ratio * height as f64).then_some((width, height))
+        })
+    }
+}
+
+fn clamp_to_options<T: Height>(
+    candidate: Rectangle<T>,
+    options: &SearchOptions<T>,
+) -> Option<Rectangle<T>> {
+    let width = options.max_width.map_or(candidate.width(), |max_width| {
+        cmp::min(candidate.width(), max_width)
+    });
+    let height = options.max_height.map_or(candidate.height, |max_height| {
+        cmp::min(candidate.height, max_height)
+    });
+    let fits_minimums = options.min_width.is_none_or(|min_width| width >= min_width)
+        && options
+            .min_height
+            .is_none_or(|min_height| height >= min_height);
+    if width == 0 || height <= T::ZERO || !fits_minimums {
+        return None;
+    }
+    Some(Rectangle {
+        left: candidate.left,
+        right: candidate.left + width - 1,
+        height,
+        area: height.area(width),
+    })
+}
+
+/// Returns the largest area together with the number of distinct rectangles
+/// attaining it, or `(0, 0)` when no rectangle of positive area exists.
+pub fn count_largest_rectangles<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+) -> (T::Area, usize) {
+    let mut area_of_largest_rectangle = T::Area::default();
+    let mut number_of_largest_rectangles = 0;
+    // Bars of equal height share a single stack entry, so each maximal
+    // rectangle is popped exactly once and nothing is counted twice.
+    for_each_candidate(histogram, |candidate| {
+        match candidate.area.cmp(&area_of_largest_rectangle) {
+            cmp::Ordering::Greater => {
+                area_of_largest_rectangle = candidate.area;
+                number_of_largest_rectangles = 1;
+            }
+            cmp::Ordering::Equal => number_of_largest_rectangles += 1,
+            cmp::Ordering::Less => {}
+        }
+    });
+    (area_of_largest_rectangle, number_of_largest_rectangles)
+}
+
+/// Returns the `k` largest maximal rectangles, ordered by decreasing area and
+/// then by increasing left index.
+pub fn top_k_rectangles<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+    k: usize,
+) -> Vec<Rectangle<T>> {
+    if k == 0 {
+        return Vec::new();
+    }
+    let mut smallest_first = BinaryHeap::new();
+    for_each_candidate(histogram, |candidate| {
+        smallest_first.push(Reverse(RankedRectangle(candidate)));
+        if smallest_first.len() > k {
+            smallest_first.pop();
+        }
+    });
+    smallest_first
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(RankedRectangle(rectangle))| rectangle)
+        .collect()
+}
+
+/// Yields every maximal rectangle, i.e. every rectangle of positive area that
+/// cannot be extended left, right or up, exactly once. Rectangles are ordered
+/// by left index and then by right index.
+pub fn maximal_rectangles<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+) -> impl Iterator<Item = Rectangle<T>> {
+    let mut rectangles = Vec::new();
+    for_each_candidate(histogram, |candidate| rectangles.push(candidate));
+    rectangles.sort_unstable_by_key(|rectangle| (rectangle.left, rectangle.right));
+    rectangles.into_iter()
+}
+
+/// Returns some rectangle with an area of at least `threshold`, stopping the
+/// search as soon as one is found. The rectangle returned is the first one
+/// found by a left-to-right sweep, not necessarily the largest.
+pub fn find_rectangle_with_area_at_least<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+    threshold: T::Area,
+) -> Option<Rectangle<T>> {
+    LargestRectangleSearcher::new(histogram).find_rectangle_with_area_at_least(threshold)
+}
+
+/// Returns the pair of rectangles, with no bar in common, whose areas have the
+/// largest sum, ordered left to right. Ties go to the pair split at the
+/// leftmost bar. Returns `None` unless two disjoint rectangles of positive area
+/// exist, so in particular for histograms narrower than two bars.
+pub fn best_two_disjoint_rectangles<H: Histogram + ?Sized>(
+    histogram: &H,
+) -> Option<(Rectangle, Rectangle)> {
+    let width = histogram.width();
+    let mut searcher = StreamingSearcher::new();
+    let largest_in_prefixes: Vec<Option<Rectangle>> = (0..width)
+        .map(|x_pos| {
+            searcher.push_bar(histogram.height_at(x_pos));
+            searcher.largest_rectangle()
+        })
+        .collect();
+    let mut searcher = StreamingSearcher::new();
+    let mut largest_in_suffixes: Vec<Option<Rectangle>> = (0..width)
+        .rev()
+        .map(|x_pos| {
+            searcher.push_bar(histogram.height_at(x_pos));
+            searcher.largest_rectangle().map(|mirrored| Rectangle {
+                left: width - 1 - mirrored.right,
+                right: width - 1 - mirrored.left,
+                ..mirrored
+            })
+        })
+        .collect();
+    largest_in_suffixes.reverse();
+    let mut best_pair: Option<(Rectangle, Rectangle)> = None;
+    for split in 1..width {
+        if let (Some(left), Some(right)) =
+            (largest_in_prefixes[split - 1], largest_in_suffixes[split])
+        {
+            let is_better = best_pair.is_none_or(|(best_left, best_right)| {
+                left.area + right.area > best_left.area + best_right.area
+            });
+            if is_better {
+                best_pair = Some((left, right));
+            }
+        }
+    }
+    best_pair
+}
+
+/// Returns the largest rectangle that shares no bar with any of the `banned`
+/// ranges, with ties going to the leftmost rectangle. Banned ranges may
+/// overlap, be empty, or extend past the histogram.
+pub fn compute_largest_rectangle_excluding<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+    banned: &[Range<usize>],
+) -> Option<Rectangle<T>> {
+    compute_largest_rectangle(&ExcludingHistogram::new(histogram, banned))
+}
+
+/// A view of a histogram in which banned bars have height zero, so that no
+/// rectangle can cross them.
+struct ExcludingHistogram<'a, H: ?Sized> {
+    histogram: &'a H,
+    banned: Vec<Range<usize>>,
+}
+
+impl<'a, H: ?Sized> ExcludingHistogram<'a, H> {
+    fn new(histogram: &'a H, banned: &[Range<usize>]) -> Self {
+        let mut sorted: Vec<Range<usize>> = banned
+            .iter()
+            .filter(|range| !range.is_empty())
+            .cloned()
+            .collect();
+        sorted.sort_unstable_by_key(|range| range.start);
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        Self {
+            histogram,
+            banned: merged,
+        }
+    }
+
+    fn is_banned(&self, x_pos: usize) -> bool {
+        let following = self.banned.partition_point(|range| range.start <= x_pos);
+        following > 0 && self.banned[following - 1].end > x_pos
+    }
+}
+
+impl<T: Height, H: Histogram<T> + ?Sized> Histogram<T> for ExcludingHistogram<'_, H> {
+    fn width(&self) -> usize {
+        self.histogram.width()
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> T {
+        if self.is_banned(horizontal_position) {
+            T::ZERO
+        } else {
+            self.histogram.height_at(horizontal_position)
+        }
+    }
+}
+
+/// Returns the largest rectangle under a histogram whose last bar is followed
+/// by its first, so that rectangles may wrap around. A wrapping rectangle has
+/// `left > right` and covers the bars from `left` to the end and then from the
+/// start to `right`; its `width()` is not meaningful, but its area is. Ties go
+/// to the rectangle with the leftmost `left`.
+pub fn compute_largest_rectangle_circular<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+) -> Option<Rectangle<T>> {
+    let width = histogram.width();
+    // Every span of at most `width` bars around the circle appears unwrapped in
+    // two laps of the histogram, less the final bar.
+    let options = SearchOptions {
+        min_width: None,
+        max_width: Some(width),
+        min_height: None,
+        max_height: None,
+    };
+    let unwrapped = compute_largest_rectangle_constrained(&TwoLaps(histogram), &options)?;
+    Some(Rectangle {
+        left: unwrapped.left % width,
+        right: unwrapped.right % width,
+        ..unwrapped
+    })
+}
+
+struct TwoLaps<'a, H: ?Sized>(&'a H);
+
+impl<T: Height, H: Histogram<T> + ?Sized> Histogram<T> for TwoLaps<'_, H> {
+    fn width(&self) -> usize {
+        (2 * self.0.width()).saturating_sub(1)
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> T {
+        self.0.height_at(horizontal_position % self.0.width())
+    }
+}
+
+/// Returns the largest rectangle under a histogram with missing bars, which no
+/// rectangle may span. The search restarts after every gap, and ties go to the
+/// leftmost rectangle. Returns `None` if no gap-free run of bars holds a
+/// rectangle of positive area.
+pub fn compute_largest_rectangle_gapped<T: Height, H: Histogram<Option<T>> + ?Sized>(
+    histogram: &H,
+) -> Option<Rectangle<T>> {
+    let mut largest_rectangle: Option<Rectangle<T>> = None;
+    let mut start = 0;
+    while start < histogram.width() {
+        let end = (start..histogram.width())
+            .find(|&x_pos| histogram.height_at(x_pos).is_none())
+            .unwrap_or(histogram.width());
+        let segment = GapFreeSegment {
+            histogram,
+            start,
+            width: end - start,
+        };
+        if let Some(candidate) = compute_largest_rectangle(&segment) {
+            let candidate = Rectangle {
+                left: candidate.left + start,
+                right: candidate.right + start,
+                ..candidate
+            };
+            if largest_rectangle.is_none_or(|largest| candidate.area > largest.area) {
+                largest_rectangle = Some(candidate);
+            }
+        }
+        start = end + 1;
+    }
+    largest_rectangle
+}
+
+struct GapFreeSegment<'a, H: ?Sized> {
+    histogram: &'a H,
+    start: usize,
+    width: usize,
+}
+
+impl<T, H: Histogram<Option<T>> + ?Sized> Histogram<T> for GapFreeSegment<'_, H> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> T {
+        self.histogram
+            .height_at(self.start + horizontal_position)
+            .expect("gap-free segments have no missing bars")
+    }
+}
+
+/// Solves many histograms in turn, keeping the search's stack allocation
+/// between calls instead of allocating it afresh for every histogram. The
+/// stack keeps its allocation for as long as consecutive histograms share a
+/// height type.
+#[derive(Debug, Default)]
+pub struct BatchSolver {
+    recorded_bars_of_increasing_height: Option<Box<dyn Any>>,
+}
+
+impl BatchSolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the same area as `compute_area_of_largest_rectangle`.
+    pub fn solve<T: Height + 'static, H: Histogram<T> + ?Sized>(
+        &mut self,
+        histogram: &H,
+    ) -> T::Area {
+        let mut buffer: Box<Vec<StackEntry<T>>> = self
+            .recorded_bars_of_increasing_height
+            .take()
+            .and_then(|buffer| buffer.downcast().ok())
+            .unwrap_or_default();
+        let mut searcher =
+            LargestRectangleSearcher::with_buffer(histogram, mem::take(&mut *buffer));
+        let area_of_largest_rectangle = searcher.compute_area_of_largest_rectangle();
+        *buffer = searcher.into_buffer();
+        self.recorded_bars_of_increasing_height = Some(buffer);
+        area_of_largest_rectangle
+    }
+
+    pub fn solve_all(&mut self, histograms: &[ConcreteHistogram]) -> Vec<u64> {
+        histograms
+            .iter()
+            .map(|histogram| self.solve(histogram))
+            .collect()
+    }
+}
+
+/// Like `compute_area_of_largest_rectangle`, but fails as soon as any
+/// candidate rectangle has an area that does not fit in an `i32`.
+pub fn compute_area_checked<H: Histogram + ?Sized>(histogram: &H) -> Result<i32, AreaOverflow> {
+    let mut area_of_largest_rectangle = 0;
+    let flow = LargestRectangleSearcher::new(histogram).visit_candidates(|candidate| {
+        match checked_area(&candidate) {
+            Some(area) => {
+                area_of_largest_rectangle = cmp::max(area_of_largest_rectangle, area);
+                ControlFlow::Continue(())
+            }
+            None => ControlFlow::Break(AreaOverflow {
+                bar_index: candidate.left,
+                width: candidate.width(),
+                height: candidate.height,
+            }),
+        }
+    });
+    match flow {
+        ControlFlow::Continue(()) => Ok(area_of_largest_rectangle),
+        ControlFlow::Break(overflow) => Err(overflow),
+    }
+}
+
+/// The rectangle whose area overflowed, identified by its leftmost bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AreaOverflow {
+    pub bar_index: usize,
+    pub width: usize,
+    pub height: i32,
+}
+
+impl fmt::Display for AreaOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "area of rectangle starting at bar {} with width {} and height {} exceeds i32::MAX",
+            self.bar_index, self.width, self.height
+        )
+    }
+}
+
+impl std::error::Error for AreaOverflow {}
+
+fn checked_area(rectangle: &Rectangle) -> Option<i32> {
+    i32::try_from(rectangle.width())
+        .ok()?
+        .checked_mul(rectangle.height)
+}
+
+/// Like `compute_area_of_largest_rectangle`, but fails rather than panics for
+/// a histogram that does not have the bars its `width` says it has. Bars are
+/// read with `try_height_at`, so a histogram that overrides it to look its
+/// bars up directly is caught even when its `width` overstates them.
+pub fn try_compute_area<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+) -> Result<T::Area, SearchError> {
+    let mut searcher = LargestRectangleSearcher::new(histogram);
+    let mut area_of_largest_rectangle = T::Area::default();
+    for x_pos in 0..=searcher.width() {
+        let height = searcher.try_height_at(x_pos)?;
+        let flow = searcher.visit_candidates_impl(x_pos, height, &mut |candidate| {
+            area_of_largest_rectangle = cmp::max(area_of_largest_rectangle, candidate.area);
+            ControlFlow::<()>::Continue(())
+        });
+        debug_assert!(flow.is_continue());
+    }
+    Ok(area_of_largest_rectangle)
+}
+
+/// Why `try_compute_area` could not search a histogram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchError {
+    /// The histogram's `width` of `width` bars includes the bar at
+    /// `horizontal_position`, but it has no such bar.
+    MissingBar {
+        horizontal_position: usize,
+        width: usize,
+    },
+    /// The histogram's `width` changed from `before` to `after` during the
+    /// search.
+    WidthChanged { before: usize, after: usize },
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::MissingBar {
+                horizontal_position,
+                width,
+            } => write!(
+                f,
+                "histogram of width {width} has no bar at position {horizontal_position}"
+            ),
+            SearchError::WidthChanged { before, after } => write!(
+                f,
+                "histogram's width changed from {before} to {after} during the search"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SearchError {}
+
+/// Computes the largest area under a histogram of floating-point heights.
+///
+/// Heights are ordered with `f64::total_cmp`, with `-0.0` treated as `0.0`.
+/// NaN heights are rejected: the first NaN bar is reported as an error.
+pub fn compute_area_of_largest_rectangle_f64<H: Histogram<f64> + ?Sized>(
+    histogram: &H,
+) -> Result<f64, NanHeight> {
+    if let Some(bar_index) = (0..histogram.width()).find(|&x| histogram.height_at(x).is_nan()) {
+        return Err(NanHeight { bar_index });
+    }
+    let area = compute_area_of_largest_rectangle(&TotallyOrderedHistogram(histogram));
+    Ok(area.0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NanHeight {
+    pub bar_index: usize,
+}
+
+impl fmt::Display for NanHeight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bar {} has a NaN height", self.bar_index)
+    }
+}
+
+impl std::error::Error for NanHeight {}
+
+struct TotallyOrderedHistogram<'a, H: ?Sized>(&'a H);
+
+impl<H: Histogram<f64> + ?Sized> Histogram<TotallyOrderedF64> for TotallyOrderedHistogram<'_, H> {
+    fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> TotallyOrderedF64 {
+        let height = self.0.height_at(horizontal_position);
+        TotallyOrderedF64(if height == 0.0 { 0.0 } else { height })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct TotallyOrderedF64(f64);
+
+impl PartialEq for TotallyOrderedF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for TotallyOrderedF64 {}
+
+impl PartialOrd for TotallyOrderedF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotallyOrderedF64 {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Height for TotallyOrderedF64 {
+    type Area = TotallyOrderedF64;
+
+    const ZERO: Self = TotallyOrderedF64(0.0);
+
+    fn area(self, width: usize) -> Self::Area {
+        TotallyOrderedF64(width as f64 * self.0)
+    }
+}
+
+/// The monotonic-stack search behind the free functions of this module.
+///
+/// A searcher can be run any number of times and pointed at other histograms
+/// with `set_histogram`, reusing its stack allocation throughout.
+///
+/// Each bar's height is read from the histogram once per search, and kept on
+/// the stack alongside its position for as long as the bar is recorded.
+///
+/// The width is sampled once, by `reset` at the start of each search, and the
+/// search covers that many bars whatever `width` returns afterwards. A
+/// histogram whose width changes mid-search therefore gets the answer for its
+/// bars as they were counted when the search began.
+pub struct LargestRectangleSearcher<'a, T: Height, H: Histogram<T> + ?Sized> {
+    histogram: &'a H,
+    width: usize,
+    recorded_bars_of_increasing_height: Stack<T>,
+}
+
+impl<'a, T: Height, H: Histogram<T> + ?Sized> LargestRectangleSearcher<'a, T, H> {
+    pub fn new(histogram: &'a H) -> Self {
+        Self::with_stack(histogram, Stack::new(T::ZERO))
+    }
+
+    /// Reuses `buffer`'s allocation for the stack of recorded bars.
+    fn with_buffer(histogram: &'a H, buffer: Vec<StackEntry<T>>) -> Self {
+        Self::with_stack(histogram, Stack::Heap(buffer))
+    }
+
+    fn with_stack(histogram: &'a H, stack: Stack<T>) -> Self {
+        let mut searcher = Self {
+            histogram,
+            width: 0,
+            recorded_bars_of_increasing_height: stack,
+        };
+        searcher.reset();
+        searcher
+    }
+
+    fn into_buffer(self) -> Vec<StackEntry<T>> {
+        self.recorded_bars_of_increasing_height.into_vec()
+    }
+
+    pub fn set_histogram(&mut self, histogram: &'a H) {
+        self.histogram = histogram;
+        self.reset();
+    }
+
+    /// Empties the stack and samples the histogram's width. Every search
+    /// starts by doing this, so a searcher never carries state from one
+    /// search into the next.
+    ///
+    /// The stack never holds more entries than there are bars, so room for
+    /// that many is made here and the search itself never reallocates.
+    pub fn reset(&mut self) {
+        self.width = self.histogram.width();
+        self.recorded_bars_of_increasing_height
+            .clear_with_capacity(self.width);
+    }
+
+    pub fn compute_area_of_largest_rectangle(&mut self) -> T::Area {
+        let mut area_of_largest_rectangle = T::Area::default();
+        self.for_each_candidate(|candidate| {
+            area_of_largest_rectangle = cmp::max(area_of_largest_rectangle, candidate.area);
+        });
+        area_of_largest_rectangle
+    }
+
+    /// Calls `f` with every candidate rectangle, like the free function
+    /// `for_each_candidate`.
+    pub fn for_each_candidate<F: FnMut(Rectangle<T>)>(&mut self, mut f: F) {
+        let flow = self.visit_candidates(|candidate| {
+            f(candidate);
+            ControlFlow::<()>::Continue(())
+        });
+        debug_assert!(flow.is_continue());
+    }
+
+    fn visit_candidates<B, F: FnMut(Rectangle<T>) -> ControlFlow<B>>(
+        &mut self,
+        mut visit: F,
+    ) -> ControlFlow<B> {
+        self.reset();
+        for x_pos in 0..=self.width() {
+            let height = self.height_at(x_pos);
+            self.visit_candidates_impl(x_pos, height, &mut visit)?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn find_rectangle_with_area_at_least(mut self, threshold: T::Area) -> Option<Rectangle<T>> {
+        let mut meets_threshold = |candidate: Rectangle<T>| {
+            if candidate.area >= threshold {
+                ControlFlow::Break(candidate)
+            } else {
+                ControlFlow::Continue(())
+            }
+        };
+        for x_pos in 0..=self.width() {
+            let height = self.height_at(x_pos);
+            if let ControlFlow::Break(found) =
+                self.visit_candidates_impl(x_pos, height, &mut meets_threshold)
+            {
+                return Some(found);
+            }
+            // The last recorded bar's rectangle already extends up to x_pos,
+            // even though it has not been popped yet.
+            if x_pos < self.width() && height > T::ZERO {
+                let open = self.compute_rectangle_at_last_recorded_bar(x_pos + 1);
+                if let ControlFlow::Break(found) = meets_threshold(open) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+
+    /// Like `height_at`, but reports a bar of the sampled width that cannot
+    /// be read, rather than panicking. The histogram's width is checked
+    /// against the sample at every bar, so one that changes is caught before
+    /// any bar past its new end is read.
+    fn try_height_at(&self, x_pos: usize) -> Result<T, SearchError> {
+        let width = self.width;
+        let after = self.histogram.width();
+        if after != width {
+            return Err(SearchError::WidthChanged {
+                before: width,
+                after,
+            });
+        }
+        if x_pos == width {
+            return Ok(T::ZERO);
+        }
+        let height = self
+            .histogram
+            .try_height_at(x_pos)
+            .ok_or(SearchError::MissingBar {
+                horizontal_position: x_pos,
+                width,
+            })?;
+        Ok(cmp::max(height, T::ZERO))
+    }
+
+    fn height_at(&self, x_pos: usize) -> T {
+        debug_assert!(x_pos <= self.width);
+        if x_pos < self.width {
+            // Clamping keeps the zero-height sentinel after the last bar no
+            // higher than any bar, so it empties the stack.
+            cmp::max(self.histogram.height_at(x_pos), T::ZERO)
+        } else {
+            T::ZERO
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Visits the rectangle of every recorded bar higher than the bar of
+    /// height `height` at `x_pos`, then records that bar.
+    fn visit_candidates_impl<B, F: FnMut(Rectangle<T>) -> ControlFlow<B>>(
+        &mut self,
+        x_pos: usize,
+        height: T,
+        visit: &mut F,
+    ) -> ControlFlow<B> {
+        while let Some(&last) = self.recorded_bars_of_increasing_height.last() {
+            if last.height <= height {
+                break;
+            }
+            visit(self.compute_rectangle_at_last_recorded_bar(x_pos))?;
+            self.recorded_bars_of_increasing_height.pop();
+        }
+        let entry = StackEntry {
+            index: x_pos,
+            height,
+        };
+        match self.recorded_bars_of_increasing_height.last_mut() {
+            Some(last) if last.height == height => *last = entry,
+            _ => self.recorded_bars_of_increasing_height.push(entry),
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Returns the rectangle of the last recorded bar, ending just before the
+    /// bar at `x_pos`. The stack must not be empty.
+    fn compute_rectangle_at_last_recorded_bar(&self, x_pos: usize) -> Rectangle<T> {
+        let stack = &self.recorded_bars_of_increasing_height;
+        let last = stack[stack.len() - 1];
+        // The entry below the last one is the nearest strictly lower bar to its
+        // left, so the rectangle starts just after it, or at the first bar if
+        // there is no such entry. This holds even when an equal-height bar has
+        // taken over the last entry.
+        let left = match stack.len() {
+            1 => 0,
+            len => stack[len - 2].index + 1,
+        };
+        let right = x_pos - 1;
+        Rectangle {
+            left,
+            right,
+            height: last.height,
+            area: last.height.area(right - left + 1),
+        }
+    }
+}
+
+/// Orders rectangles by area, preferring the leftmost (then narrowest) one
+/// among rectangles of equal area.
+pub(crate) fn rank<T: Height>(
+    rectangle: &Rectangle<T>,
+) -> (T::Area, Reverse<usize>, Reverse<usize>) {
+    (
+        rectangle.area,
+        Reverse(rectangle.left),
+        Reverse(rectangle.right),
+    )
+}
+
+struct RankedRectangle<T: Height>(Rectangle<T>);
+
+impl<T: Height> PartialEq for RankedRectangle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        rank(&self.0) == rank(&other.0)
+    }
+}
+
+impl<T: Height> Eq for RankedRectangle<T> {}
+
+impl<T: Height> PartialOrd for RankedRectangle<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Height> Ord for RankedRectangle<T> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        rank(&self.0).cmp(&rank(&other.0))
+    }
+}
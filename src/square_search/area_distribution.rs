@@ -0,0 +1,70 @@
+use crate::histogram::Histogram;
+use crate::square_search::for_each_candidate;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// How the areas of a histogram's maximal rectangles are spread, as counts of
+/// areas in equal ranges. Displaying it gives one line per range.
+///
+/// `candidate_area_histogram` returns this rather than a bare `Vec` because
+/// `Display` cannot be implemented for `Vec`, which is a foreign type. The
+/// `Vec` itself is the public `buckets` field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AreaDistribution {
+    /// Each range of areas with how many maximal rectangles fall in it, in
+    /// increasing order of area.
+    pub buckets: Vec<(RangeInclusive<u64>, usize)>,
+}
+
+/// Counts the areas of every maximal rectangle in at most `bucket_count`
+/// ranges of equal size, spanning the smallest area to the largest. The last
+/// range may be cut short at the largest area, and fewer ranges are used when
+/// there are fewer distinct areas than ranges. A histogram with no rectangle
+/// of positive area has no ranges.
+///
+/// # Panics
+///
+/// Panics if `bucket_count` is zero.
+pub fn candidate_area_histogram<H: Histogram + ?Sized>(
+    histogram: &H,
+    bucket_count: usize,
+) -> AreaDistribution {
+    assert!(bucket_count > 0, "bucket count must be non-zero");
+    let mut areas = Vec::new();
+    for_each_candidate(histogram, |candidate| areas.push(candidate.area));
+    let (Some(&smallest), Some(&largest)) = (areas.iter().min(), areas.iter().max()) else {
+        return AreaDistribution {
+            buckets: Vec::new(),
+        };
+    };
+    let span = u128::from(largest - smallest) + 1;
+    let bucket_size = span.div_ceil(bucket_count as u128);
+    let mut buckets: Vec<(RangeInclusive<u64>, usize)> = (0..span.div_ceil(bucket_size))
+        .map(|bucket| {
+            let start = u128::from(smallest) + bucket * bucket_size;
+            let end = (start + bucket_size - 1).min(u128::from(largest));
+            (start as u64..=end as u64, 0)
+        })
+        .collect();
+    for area in areas {
+        let bucket = u128::from(area - smallest) / bucket_size;
+        buckets[bucket as usize].1 += 1;
+    }
+    AreaDistribution { buckets }
+}
+
+/// Writes each range and its count on its own line, with the counts lined up.
+impl fmt::Display for AreaDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranges: Vec<String> = self
+            .buckets
+            .iter()
+            .map(|(range, _)| format!("{}..={}", range.start(), range.end()))
+            .collect();
+        let range_width = ranges.iter().map(String::len).max().unwrap_or(0);
+        for (range, (_, count)) in ranges.iter().zip(&self.buckets) {
+            writeln!(f, "{range:>range_width$}: {count}")?;
+        }
+        Ok(())
+    }
+}
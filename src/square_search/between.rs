@@ -0,0 +1,92 @@
+use crate::histogram::Histogram;
+use crate::histogram_concrete::SliceHistogram;
+use crate::rectangle::{Rectangle, Rectangle2D};
+use crate::square_search::compute_largest_rectangle;
+use std::cmp::Reverse;
+use std::fmt;
+
+/// Returns the largest rectangle that fits between `floor` and `ceiling`: over
+/// every column it spans, its bottom is at or above the floor and its top at
+/// or below the ceiling. Columns whose ceiling is not above their floor leave
+/// no room. Returns `None` when no rectangle of positive area fits.
+///
+/// The best rectangle rests on the highest floor under it, so each distinct
+/// floor height is tried as the bottom in turn, searching the room above it as
+/// an ordinary histogram. This takes `O(n × k)` time for `k` distinct floor
+/// heights. When several rectangles share the largest area, the leftmost (then
+/// narrowest, then lowest) one is returned.
+pub fn largest_rectangle_between<F: Histogram + ?Sized, C: Histogram + ?Sized>(
+    floor: &F,
+    ceiling: &C,
+) -> Result<Option<Rectangle2D>, WidthMismatch> {
+    let width = floor.width();
+    if ceiling.width() != width {
+        return Err(WidthMismatch {
+            floor_width: width,
+            ceiling_width: ceiling.width(),
+        });
+    }
+    let floors: Vec<i32> = (0..width).map(|x_pos| floor.height_at(x_pos)).collect();
+    let ceilings: Vec<i32> = (0..width).map(|x_pos| ceiling.height_at(x_pos)).collect();
+    let mut bottoms = floors.clone();
+    bottoms.sort_unstable();
+    bottoms.dedup();
+    let mut room = vec![0_u32; width];
+    let mut best: Option<Rectangle2D> = None;
+    for bottom in bottoms {
+        for ((room, &floor), &ceiling) in room.iter_mut().zip(&floors).zip(&ceilings) {
+            *room = if floor <= bottom {
+                (i64::from(ceiling) - i64::from(bottom)).max(0) as u32
+            } else {
+                0
+            };
+        }
+        let Some(Rectangle {
+            left,
+            right,
+            height,
+            area,
+        }) = compute_largest_rectangle(&SliceHistogram::from_bars(&room))
+        else {
+            continue;
+        };
+        let candidate = Rectangle2D {
+            left,
+            right,
+            bottom,
+            top: (i64::from(bottom) + i64::from(height)) as i32,
+            area,
+        };
+        if best.is_none_or(|best| rank(&candidate) > rank(&best)) {
+            best = Some(candidate);
+        }
+    }
+    Ok(best)
+}
+
+fn rank(rectangle: &Rectangle2D) -> (u64, Reverse<usize>, Reverse<usize>) {
+    (
+        rectangle.area,
+        Reverse(rectangle.left),
+        Reverse(rectangle.right),
+    )
+}
+
+/// The error from pairing a floor and a ceiling of different widths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WidthMismatch {
+    pub floor_width: usize,
+    pub ceiling_width: usize,
+}
+
+impl fmt::Display for WidthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the floor has {} bars but the ceiling has {}",
+            self.floor_width, self.ceiling_width
+        )
+    }
+}
+
+impl std::error::Error for WidthMismatch {}
@@ -0,0 +1,114 @@
+use crate::histogram::{Height, Histogram};
+use crate::square_search::{Stack, StackEntry};
+
+/// The nearest lower bars on each side of every bar, as found by the
+/// monotonic stack behind every search in this module.
+///
+/// The two sides differ on ties: a bar's extent stops before a strictly lower
+/// bar on its left but before a lower *or equal* bar on its right. Within a
+/// run of equal bars only the last one reaches across the whole run, so each
+/// maximal rectangle is attributed to exactly one bar.
+///
+/// Heights are compared as given; negative heights are not treated as zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Boundaries {
+    /// For each bar, the position of the nearest bar to its left that is
+    /// strictly lower, or `None` if there is no such bar.
+    pub prev_smaller: Vec<Option<usize>>,
+    /// For each bar, the position of the nearest bar to its right that is
+    /// lower or of equal height, or `None` if there is no such bar.
+    pub next_smaller_or_equal: Vec<Option<usize>>,
+}
+
+/// Returns the `Boundaries` of every bar, reading each bar once.
+///
+/// The largest rectangle containing bar `i` at its full height spans the bars
+/// strictly between `prev_smaller[i]` and `next_smaller_or_equal[i]`, taking
+/// the histogram's ends if either is `None`.
+pub fn boundaries<T: Height, H: Histogram<T> + ?Sized>(histogram: &H) -> Boundaries {
+    let width = histogram.width();
+    let mut boundaries = Boundaries {
+        prev_smaller: vec![None; width],
+        next_smaller_or_equal: vec![None; width],
+    };
+    let mut recorded_bars_of_increasing_height = Stack::new(T::ZERO);
+    recorded_bars_of_increasing_height.clear_with_capacity(width);
+    for x_pos in 0..width {
+        let height = histogram.height_at(x_pos);
+        while let Some(&last) = recorded_bars_of_increasing_height.last() {
+            if last.height < height {
+                break;
+            }
+            recorded_bars_of_increasing_height.pop();
+            boundaries.next_smaller_or_equal[last.index] = Some(x_pos);
+        }
+        boundaries.prev_smaller[x_pos] = recorded_bars_of_increasing_height
+            .last()
+            .map(|below| below.index);
+        recorded_bars_of_increasing_height.push(StackEntry {
+            index: x_pos,
+            height,
+        });
+    }
+    boundaries
+}
+
+/// Returns the sum, over every non-empty run of consecutive bars, of the
+/// height of its lowest bar. Negative heights count as zero.
+///
+/// Each bar is the lowest of the runs that start after its `prev_smaller` and
+/// end before its `next_smaller_or_equal`. Runs with several equal lowest
+/// bars are counted once, for the last of them.
+pub fn sum_of_subarray_minimums<H: Histogram + ?Sized>(histogram: &H) -> u128 {
+    let width = histogram.width();
+    let boundaries = boundaries(histogram);
+    (0..width)
+        .map(|x_pos| {
+            let starts = boundaries.prev_smaller[x_pos].map_or(x_pos + 1, |below| x_pos - below);
+            let ends = boundaries.next_smaller_or_equal[x_pos].unwrap_or(width) - x_pos;
+            histogram.height_at(x_pos).max(0) as u128 * starts as u128 * ends as u128
+        })
+        .sum()
+}
+
+/// Returns, for every bar, the area of the largest rectangle that bar limits:
+/// as tall as the bar, and reaching sideways until a strictly lower bar on
+/// either side. Negative heights are treated as zero.
+///
+/// Unlike `Boundaries`, both sides stop only at strictly lower bars, so every
+/// bar of a run of equal bars is credited with the same, whole rectangle.
+/// The largest of these areas is the area of the largest rectangle.
+pub fn per_bar_maximal_areas<H: Histogram + ?Sized>(histogram: &H) -> Vec<u64> {
+    limited_spans(histogram)
+        .into_iter()
+        .map(|(height, span)| height as u64 * span as u64)
+        .collect()
+}
+
+/// Returns every bar's height, with negative heights as zero, and the number
+/// of bars its rectangle spans when it reaches sideways until a strictly lower
+/// bar on either side.
+pub(super) fn limited_spans<H: Histogram + ?Sized>(histogram: &H) -> Vec<(i32, usize)> {
+    let width = histogram.width();
+    let mut spans = vec![(0, 0); width];
+    // Each waiting bar as `(position, start, height)`, where `start` is the
+    // first bar its rectangle covers. Equal bars are all kept.
+    let mut waiting: Vec<(usize, usize, i32)> = Vec::new();
+    let heights = (0..width).map(|x_pos| histogram.height_at(x_pos).max(0));
+    for (x_pos, height) in heights.chain([-1]).enumerate() {
+        while let Some(&(position, start, waiting_height)) = waiting.last() {
+            if waiting_height <= height {
+                break;
+            }
+            waiting.pop();
+            spans[position] = (waiting_height, x_pos - start);
+        }
+        let start = match waiting.last() {
+            Some(&(_, start, below)) if below == height => start,
+            Some(&(below, _, _)) => below + 1,
+            None => 0,
+        };
+        waiting.push((x_pos, start, height));
+    }
+    spans
+}
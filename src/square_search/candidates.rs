@@ -0,0 +1,69 @@
+use crate::histogram::{Height, Histogram};
+use crate::rectangle::Rectangle;
+use crate::square_search::{LargestRectangleSearcher, StackEntry};
+
+/// Lazily yields every maximal rectangle with an area of at least `threshold`,
+/// each exactly once, in the order the search finds them: by right index, and
+/// from the tallest down among rectangles ending at the same bar. Dropping the
+/// iterator early leaves the remaining bars unread.
+pub fn rectangles_with_area_at_least<H: Histogram + ?Sized>(
+    histogram: &H,
+    threshold: u64,
+) -> impl Iterator<Item = Rectangle> + '_ {
+    Candidates::new(histogram).filter(move |candidate| candidate.area >= threshold)
+}
+
+/// The search as a resumable state machine, yielding each candidate as it is
+/// popped off the stack.
+struct Candidates<'a, T: Height, H: Histogram<T> + ?Sized> {
+    searcher: LargestRectangleSearcher<'a, T, H>,
+    /// The next bar to read.
+    x_pos: usize,
+    /// The height of the bar before `x_pos`, while the stack holds recorded
+    /// bars higher than it that are still to be popped.
+    popping_for: Option<T>,
+}
+
+impl<'a, T: Height, H: Histogram<T> + ?Sized> Candidates<'a, T, H> {
+    fn new(histogram: &'a H) -> Self {
+        let mut searcher = LargestRectangleSearcher::new(histogram);
+        searcher.reset();
+        Self {
+            searcher,
+            x_pos: 0,
+            popping_for: None,
+        }
+    }
+}
+
+impl<T: Height, H: Histogram<T> + ?Sized> Iterator for Candidates<'_, T, H> {
+    type Item = Rectangle<T>;
+
+    fn next(&mut self) -> Option<Rectangle<T>> {
+        loop {
+            if let Some(height) = self.popping_for {
+                let x_pos = self.x_pos - 1;
+                let stack = &mut self.searcher.recorded_bars_of_increasing_height;
+                if stack.last().is_some_and(|last| last.height > height) {
+                    let candidate = self.searcher.compute_rectangle_at_last_recorded_bar(x_pos);
+                    self.searcher.recorded_bars_of_increasing_height.pop();
+                    return Some(candidate);
+                }
+                let entry = StackEntry {
+                    index: x_pos,
+                    height,
+                };
+                match stack.last_mut() {
+                    Some(last) if last.height == height => *last = entry,
+                    _ => stack.push(entry),
+                }
+                self.popping_for = None;
+            }
+            if self.x_pos > self.searcher.width() {
+                return None;
+            }
+            self.popping_for = Some(self.searcher.height_at(self.x_pos));
+            self.x_pos += 1;
+        }
+    }
+}
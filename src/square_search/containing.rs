@@ -0,0 +1,84 @@
+use crate::histogram::{Height, Histogram};
+use crate::histogram_concrete::OutOfRange;
+use crate::rectangle::Rectangle;
+use crate::square_search::rank;
+
+/// Returns the largest rectangle that covers the bar at `x_pos`, or `None` if
+/// that bar has no positive height. When several share the largest area, the
+/// leftmost one is returned.
+///
+/// This is not always the rectangle the bar itself limits, as a shorter
+/// rectangle reaching further to either side may be larger. Every
+/// height up to the bar's own is tried by walking outwards from the bar once,
+/// so this takes `O(n)` time.
+pub fn largest_rectangle_containing<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+    x_pos: usize,
+) -> Result<Option<Rectangle<T>>, OutOfRange> {
+    let width = histogram.width();
+    if x_pos >= width {
+        return Err(OutOfRange {
+            horizontal_position: x_pos,
+            width,
+        });
+    }
+    Ok(largest_rectangle_through(histogram, x_pos))
+}
+
+/// Returns the largest rectangle covering the bar at `x_pos`, with ties going
+/// to the leftmost rectangle.
+pub(crate) fn largest_rectangle_through<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+    x_pos: usize,
+) -> Option<Rectangle<T>> {
+    let left = staircase(histogram, (0..=x_pos).rev());
+    let right = staircase(histogram, x_pos + 1..histogram.width());
+    let mut height = left.first()?.0;
+    let (mut left_step, mut right_steps) = (0, 0);
+    let mut largest_rectangle: Option<Rectangle<T>> = None;
+    loop {
+        while left.get(left_step + 1).is_some_and(|step| step.0 >= height) {
+            left_step += 1;
+        }
+        while right.get(right_steps).is_some_and(|step| step.0 >= height) {
+            right_steps += 1;
+        }
+        let bars_after = right_steps.checked_sub(1).map_or(0, |step| right[step].1);
+        let candidate = Rectangle {
+            left: x_pos + 1 - left[left_step].1,
+            right: x_pos + bars_after,
+            height,
+            area: height.area(left[left_step].1 + bars_after),
+        };
+        if largest_rectangle.is_none_or(|largest| rank(&candidate) > rank(&largest)) {
+            largest_rectangle = Some(candidate);
+        }
+        let lower_left = left.get(left_step + 1).map(|step| step.0);
+        let lower_right = right.get(right_steps).map(|step| step.0);
+        match lower_left.max(lower_right) {
+            Some(lower) => height = lower,
+            None => return largest_rectangle,
+        }
+    }
+}
+
+/// Walks over the given bars and returns each height the walk's running
+/// minimum takes, with the number of bars walked while it held. Stops at the
+/// first bar with no positive height.
+pub(super) fn staircase<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+    positions: impl Iterator<Item = usize>,
+) -> Vec<(T, usize)> {
+    let mut steps: Vec<(T, usize)> = Vec::new();
+    for (walked, x_pos) in positions.enumerate() {
+        let height = histogram.height_at(x_pos);
+        if height <= T::ZERO {
+            break;
+        }
+        match steps.last_mut() {
+            Some(last) if last.0 <= height => last.1 = walked + 1,
+            _ => steps.push((height, walked + 1)),
+        }
+    }
+    steps
+}
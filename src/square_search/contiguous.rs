@@ -0,0 +1,44 @@
+use crate::histogram::Height;
+use crate::rectangle::Rectangle;
+use crate::square_search::{rank, Stack, StackEntry};
+use std::cmp;
+
+/// Returns the same rectangle as `compute_largest_rectangle`, reading the
+/// bars straight from a slice. Iterating over the slice rather than calling
+/// `height_at` per position lets the compiler drop the bounds checks.
+pub(super) fn largest_rectangle_in_slice<T: Height>(bars: &[T]) -> Option<Rectangle<T>> {
+    let mut recorded_bars_of_increasing_height = Stack::new(T::ZERO);
+    recorded_bars_of_increasing_height.clear_with_capacity(bars.len());
+    let mut best: Option<Rectangle<T>> = None;
+    let heights = bars.iter().map(|&height| cmp::max(height, T::ZERO));
+    // The zero-height bar after the last one empties the stack.
+    for (x_pos, height) in heights.chain([T::ZERO]).enumerate() {
+        while let Some(&last) = recorded_bars_of_increasing_height.last() {
+            if last.height <= height {
+                break;
+            }
+            recorded_bars_of_increasing_height.pop();
+            let left = recorded_bars_of_increasing_height
+                .last()
+                .map_or(0, |below| below.index + 1);
+            let candidate = Rectangle {
+                left,
+                right: x_pos - 1,
+                height: last.height,
+                area: last.height.area(x_pos - left),
+            };
+            if best.is_none_or(|best| rank(&candidate) > rank(&best)) {
+                best = Some(candidate);
+            }
+        }
+        let entry = StackEntry {
+            index: x_pos,
+            height,
+        };
+        match recorded_bars_of_increasing_height.last_mut() {
+            Some(last) if last.height == height => *last = entry,
+            _ => recorded_bars_of_increasing_height.push(entry),
+        }
+    }
+    best
+}
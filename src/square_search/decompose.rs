@@ -0,0 +1,41 @@
+use crate::histogram::Histogram;
+use crate::rectangle::Rectangle2D;
+
+/// Returns the fewest rectangles that together cover the area under the
+/// histogram exactly, without overlapping. Negative heights are treated as
+/// zero, so those bars are left uncovered.
+///
+/// Each rectangle is one level of the monotonic stack: it starts where the
+/// stack first reaches its height, ends where a lower bar cuts it off, and
+/// rests on the next level down. Bars of equal height with nothing lower
+/// between them share a single rectangle, which is what makes the count
+/// minimal. Rectangles are listed in the order they are cut off, so by their
+/// right edges.
+pub fn decompose_into_rectangles<H: Histogram + ?Sized>(histogram: &H) -> Vec<Rectangle2D> {
+    let mut rectangles = Vec::new();
+    // Each level as `(start, height)`, in increasing height.
+    let mut levels: Vec<(usize, i32)> = Vec::new();
+    let heights = (0..histogram.width()).map(|x_pos| histogram.height_at(x_pos).max(0));
+    for (x_pos, height) in heights.chain([0]).enumerate() {
+        let mut start = x_pos;
+        while let Some(&(level_start, level_height)) = levels.last() {
+            if level_height <= height {
+                break;
+            }
+            levels.pop();
+            let below = levels.last().map_or(0, |&(_, below)| below).max(height);
+            rectangles.push(Rectangle2D {
+                left: level_start,
+                right: x_pos - 1,
+                bottom: below,
+                top: level_height,
+                area: (level_height - below) as u64 * (x_pos - level_start) as u64,
+            });
+            start = level_start;
+        }
+        if height > levels.last().map_or(0, |&(_, top)| top) {
+            levels.push((start, height));
+        }
+    }
+    rectangles
+}
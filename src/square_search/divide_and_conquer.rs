@@ -0,0 +1,35 @@
+use crate::histogram::{Height, Histogram};
+use crate::square_search::lowest_bar::LowestBar;
+use std::cmp;
+
+/// Returns the same area as `compute_area_of_largest_rectangle`, found
+/// independently by divide and conquer: the largest rectangle spanning a range
+/// of bars is as tall as its lowest bar, and every other candidate lies wholly
+/// on one side of that bar.
+///
+/// The lowest bar of each range comes from a sparse table in `O(1)`, so the
+/// search takes `O(n log n)` time and memory for building the table and `O(n)`
+/// after that. Ranges wait on an explicit stack rather than in recursive
+/// calls, so sorted bars, which split off one bar at a time, cannot overflow
+/// the call stack.
+pub fn compute_area_divide_and_conquer<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+) -> T::Area {
+    let heights: Vec<T> = (0..histogram.width())
+        .map(|x_pos| cmp::max(histogram.height_at(x_pos), T::ZERO))
+        .collect();
+    let lowest = LowestBar::new(heights.as_slice());
+    let mut area_of_largest_rectangle = T::Area::default();
+    let mut ranges = vec![(0, heights.len())];
+    while let Some((start, end)) = ranges.pop() {
+        if start == end {
+            continue;
+        }
+        let split = lowest.position(start, end);
+        area_of_largest_rectangle =
+            cmp::max(area_of_largest_rectangle, heights[split].area(end - start));
+        ranges.push((start, split));
+        ranges.push((split + 1, end));
+    }
+    area_of_largest_rectangle
+}
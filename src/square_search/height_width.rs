@@ -0,0 +1,149 @@
+use crate::histogram::Histogram;
+use crate::square_search::boundaries::limited_spans;
+use std::collections::VecDeque;
+
+/// Answers, for any height, how wide the widest run of bars at least that tall
+/// is. Building the index takes `O(n log n)` time and each query `O(log n)`.
+///
+/// Negative heights are treated as zero, so every height of zero or less is
+/// met by the whole histogram.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeightWidthIndex {
+    width: usize,
+    /// The distinct positive heights of the bars, in increasing order.
+    heights: Vec<i32>,
+    /// `widest[i]` is the widest run of bars at least `heights[i]` tall.
+    widest: Vec<usize>,
+}
+
+impl HeightWidthIndex {
+    /// Each bar is the lowest of a run that ends at strictly lower bars, and
+    /// the widest run at a height is the widest such run of a bar at least
+    /// that tall. So the runs are sorted by height and their widths maximised
+    /// from the tallest down.
+    pub fn new<H: Histogram + ?Sized>(histogram: &H) -> Self {
+        let mut spans = limited_spans(histogram);
+        spans.retain(|&(height, _)| height > 0);
+        spans.sort_unstable();
+        let (mut heights, mut widest): (Vec<i32>, Vec<usize>) = (Vec::new(), Vec::new());
+        for &(height, span) in spans.iter().rev() {
+            match heights.last() {
+                Some(&last) if last == height => {}
+                _ => {
+                    let wider = widest.last().map_or(0, |&wider| wider);
+                    heights.push(height);
+                    widest.push(span.max(wider));
+                }
+            }
+        }
+        heights.reverse();
+        widest.reverse();
+        Self {
+            width: histogram.width(),
+            heights,
+            widest,
+        }
+    }
+
+    /// Returns the number of bars in the widest run of bars that are all at
+    /// least `height` tall, or zero if no bar is.
+    pub fn query(&self, height: i32) -> usize {
+        if height <= 0 {
+            return self.width;
+        }
+        let taller = self.heights.partition_point(|&bar| bar < height);
+        self.widest.get(taller).copied().unwrap_or(0)
+    }
+}
+
+/// Returns the number of bars in the widest run of bars that are all at least
+/// `height` tall. To answer many heights, build a `HeightWidthIndex` instead.
+pub fn max_width_at_height<H: Histogram + ?Sized>(histogram: &H, height: i32) -> usize {
+    if height <= 0 {
+        return histogram.width();
+    }
+    let mut widest = 0;
+    let mut run = 0;
+    for x_pos in 0..histogram.width() {
+        run = if histogram.height_at(x_pos) >= height {
+            run + 1
+        } else {
+            0
+        };
+        widest = widest.max(run);
+    }
+    widest
+}
+
+/// Returns the greatest height that every bar of some run of `width`
+/// consecutive bars reaches, or zero if the histogram has fewer than `width`
+/// bars. See `max_height_at_width_with_position` for where the run starts.
+///
+/// # Panics
+///
+/// Panics if `width` is zero.
+pub fn max_height_at_width<H: Histogram + ?Sized>(histogram: &H, width: usize) -> i32 {
+    max_height_at_width_with_position(histogram, width).map_or(0, |(_, height)| height)
+}
+
+/// Returns the first bar of the leftmost run of `width` consecutive bars whose
+/// lowest bar is tallest, with that lowest bar's height. Returns `None` if the
+/// histogram has fewer than `width` bars. Negative heights are treated as
+/// zero.
+///
+/// The lowest bar of each run comes from a queue of the bars that could still
+/// be the lowest of a later run, so this takes `O(n)` time.
+///
+/// # Panics
+///
+/// Panics if `width` is zero.
+pub fn max_height_at_width_with_position<H: Histogram + ?Sized>(
+    histogram: &H,
+    width: usize,
+) -> Option<(usize, i32)> {
+    assert!(width > 0, "window width must be non-zero");
+    if width > histogram.width() {
+        return None;
+    }
+    let mut best: Option<(usize, i32)> = None;
+    // Bars as `(position, height)`, in increasing position and strictly
+    // increasing height, so the front is the lowest bar of the current run.
+    let mut lowest = VecDeque::new();
+    for x_pos in 0..histogram.width() {
+        let height = histogram.height_at(x_pos).max(0);
+        while lowest.back().is_some_and(|&(_, back)| back >= height) {
+            lowest.pop_back();
+        }
+        lowest.push_back((x_pos, height));
+        let Some(start) = (x_pos + 1).checked_sub(width) else {
+            continue;
+        };
+        if lowest.front().is_some_and(|&(front, _)| front < start) {
+            lowest.pop_front();
+        }
+        let (_, run_height) = lowest[0];
+        if best.is_none_or(|(_, best)| run_height > best) {
+            best = Some((start, run_height));
+        }
+    }
+    best
+}
+
+/// Returns, at index `w - 1` for each width `w` up to the histogram's, the
+/// greatest height that every bar of some run of `w` bars reaches. Negative
+/// heights are treated as zero.
+///
+/// Each bar is the lowest of the run reaching sideways to the nearest strictly
+/// lower bars, so its height is achievable at that run's width and at every
+/// narrower width. Recording each bar at its own run's width and then carrying
+/// the maximum from wider to narrower widths takes `O(n)` time.
+pub fn best_height_per_width<H: Histogram + ?Sized>(histogram: &H) -> Vec<i32> {
+    let mut best = vec![0; histogram.width()];
+    for (height, span) in limited_spans(histogram) {
+        best[span - 1] = best[span - 1].max(height);
+    }
+    for w in (1..best.len()).rev() {
+        best[w - 1] = best[w - 1].max(best[w]);
+    }
+    best
+}
@@ -0,0 +1,76 @@
+use crate::histogram::Height;
+use std::ops::Deref;
+
+/// A sparse table answering which bar of a range is the lowest, over bars it
+/// either borrows or owns.
+pub(crate) struct LowestBar<H> {
+    heights: H,
+    /// `levels[k][i]` is the position of the lowest of the `2^k` bars from `i`.
+    levels: Vec<Vec<usize>>,
+}
+
+impl<T: Height, H: Deref<Target = [T]>> LowestBar<H> {
+    pub(crate) fn new(heights: H) -> Self {
+        let mut levels = vec![(0..heights.len()).collect::<Vec<_>>()];
+        let mut span = 1;
+        while 2 * span <= heights.len() {
+            let previous = levels.last().unwrap();
+            let level = (0..=heights.len() - 2 * span)
+                .map(|start| lower(&heights, previous[start], previous[start + span]))
+                .collect();
+            levels.push(level);
+            span *= 2;
+        }
+        Self { heights, levels }
+    }
+
+    pub(crate) fn heights(&self) -> &[T] {
+        &self.heights
+    }
+
+    /// Returns the position of the lowest bar in `start..end`, which must not
+    /// be empty.
+    pub(crate) fn position(&self, start: usize, end: usize) -> usize {
+        let level = (end - start).ilog2() as usize;
+        let span = 1 << level;
+        lower(
+            &self.heights,
+            self.levels[level][start],
+            self.levels[level][end - span],
+        )
+    }
+
+    /// Returns the position of the nearest bar before `end` that is lower than
+    /// `height`, if any.
+    pub(super) fn nearest_lower_before(&self, mut end: usize, height: T) -> Option<usize> {
+        for level in (0..self.levels.len()).rev() {
+            let span = 1 << level;
+            if end >= span && self.heights[self.levels[level][end - span]] >= height {
+                end -= span;
+            }
+        }
+        end.checked_sub(1)
+    }
+
+    /// Returns the position of the nearest bar from `start` onwards that is
+    /// lower than `height`, if any.
+    pub(super) fn nearest_lower_from(&self, mut start: usize, height: T) -> Option<usize> {
+        for level in (0..self.levels.len()).rev() {
+            let span = 1 << level;
+            if start + span <= self.heights.len()
+                && self.heights[self.levels[level][start]] >= height
+            {
+                start += span;
+            }
+        }
+        (start < self.heights.len()).then_some(start)
+    }
+}
+
+fn lower<T: Height>(heights: &[T], a: usize, b: usize) -> usize {
+    if heights[b] < heights[a] {
+        b
+    } else {
+        a
+    }
+}
@@ -0,0 +1,41 @@
+//! A brute-force search for the largest rectangle, for use as a reference
+//! when testing faster searches. It takes `O(n²)` time, so it only suits small
+//! histograms.
+
+use crate::histogram::{Height, Histogram};
+use crate::rectangle::Rectangle;
+use crate::square_search::rank;
+use std::cmp;
+
+/// Returns the same area as `square_search::compute_area_of_largest_rectangle`.
+pub fn compute_area_of_largest_rectangle<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+) -> T::Area {
+    compute_largest_rectangle(histogram).map_or_else(T::Area::default, |rectangle| rectangle.area)
+}
+
+/// Returns the same rectangle as `square_search::compute_largest_rectangle`,
+/// found by trying every range of bars at the height of its lowest bar.
+pub fn compute_largest_rectangle<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+) -> Option<Rectangle<T>> {
+    let mut best: Option<Rectangle<T>> = None;
+    for left in 0..histogram.width() {
+        let mut height = cmp::max(histogram.height_at(left), T::ZERO);
+        for right in left..histogram.width() {
+            height = cmp::min(height, cmp::max(histogram.height_at(right), T::ZERO));
+            let candidate = Rectangle {
+                left,
+                right,
+                height,
+                area: height.area(right - left + 1),
+            };
+            if candidate.area > T::Area::default()
+                && best.is_none_or(|best| rank(&candidate) > rank(&best))
+            {
+                best = Some(candidate);
+            }
+        }
+    }
+    best
+}
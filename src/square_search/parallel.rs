@@ -0,0 +1,214 @@
+use crate::histogram::{Height, Histogram};
+use crate::histogram_concrete::ConcreteHistogram;
+use crate::square_search::BatchSolver;
+use std::cmp;
+use std::num::NonZeroUsize;
+use std::thread;
+
+/// The fewest bars worth giving a thread of their own.
+const MIN_BARS_PER_CHUNK: usize = 1 << 16;
+
+/// Returns the same area as `compute_area_of_largest_rectangle`, splitting
+/// the work across the available threads. Histograms too narrow to be worth
+/// splitting are searched on the calling thread.
+pub fn compute_area_parallel<T, H>(histogram: &H) -> T::Area
+where
+    T: Height + Send + Sync,
+    T::Area: Send + Sync,
+    H: Histogram<T> + Sync + ?Sized,
+{
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunks = cmp::min(threads, histogram.width() / MIN_BARS_PER_CHUNK);
+    compute_area_parallel_with(histogram, cmp::max(chunks, 1))
+}
+
+/// Returns the same area as `compute_area_of_largest_rectangle`, splitting
+/// the bars into `chunks` runs of nearly equal width and searching each on a
+/// thread of its own.
+///
+/// Each thread runs the stack search over its run. That settles every bar
+/// whose nearest lower bars on both sides lie within the run. The others are
+/// the run's prefix minima, whose nearest lower bar on the left lies in an
+/// earlier run, and the bars left on the stack at the end, whose nearest lower
+/// bar on the right lies in a later run. The nearest lower bar in another run
+/// is found from the minima of the runs in between and a binary search of the
+/// prefix minima or the final stack of the run holding it, which the threads
+/// then do in parallel too.
+///
+/// # Panics
+///
+/// Panics if `chunks` is zero.
+pub fn compute_area_parallel_with<T, H>(histogram: &H, chunks: usize) -> T::Area
+where
+    T: Height + Send + Sync,
+    T::Area: Send + Sync,
+    H: Histogram<T> + Sync + ?Sized,
+{
+    assert!(chunks > 0, "the number of chunks must be non-zero");
+    let width = histogram.width();
+    let chunks = cmp::max(cmp::min(chunks, width), 1);
+    let bounds: Vec<(usize, usize)> = (0..chunks)
+        .map(|chunk| (chunk * width / chunks, (chunk + 1) * width / chunks))
+        .collect();
+    if chunks == 1 {
+        // One run needs no thread of its own, which also keeps the search
+        // working where no thread can be spawned, as on
+        // wasm32-unknown-unknown.
+        let summaries = [ChunkSummary::new(histogram, 0, width)];
+        return summaries[0].resolve(&summaries, 0, width);
+    }
+
+    let summaries: Vec<ChunkSummary<T>> = thread::scope(|scope| {
+        let handles: Vec<_> = bounds
+            .iter()
+            .map(|&(start, end)| scope.spawn(move || ChunkSummary::new(histogram, start, end)))
+            .collect();
+        handles.into_iter().map(join).collect()
+    });
+
+    let summaries = &summaries;
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..chunks)
+            .map(|chunk| scope.spawn(move || summaries[chunk].resolve(summaries, chunk, width)))
+            .collect();
+        handles.into_iter().map(join).max().unwrap_or_default()
+    })
+}
+
+/// Returns the area of the largest rectangle of each histogram, in order,
+/// solving runs of consecutive histograms on the available threads. Each
+/// thread reuses one `BatchSolver` for all of its histograms.
+pub fn compute_areas_parallel(histograms: &[ConcreteHistogram]) -> Vec<u64> {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let mut areas = vec![0; histograms.len()];
+    let per_thread = histograms.len().div_ceil(threads).max(1);
+    if per_thread >= histograms.len() {
+        let mut solver = BatchSolver::new();
+        for (histogram, area) in histograms.iter().zip(&mut areas) {
+            *area = solver.solve(histogram);
+        }
+        return areas;
+    }
+    thread::scope(|scope| {
+        for (histograms, areas) in histograms
+            .chunks(per_thread)
+            .zip(areas.chunks_mut(per_thread))
+        {
+            scope.spawn(move || {
+                let mut solver = BatchSolver::new();
+                for (histogram, area) in histograms.iter().zip(areas) {
+                    *area = solver.solve(histogram);
+                }
+            });
+        }
+    });
+    areas
+}
+
+fn join<R>(handle: thread::ScopedJoinHandle<'_, R>) -> R {
+    handle
+        .join()
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
+
+/// What a run of bars needs to share with the other runs, and the best area
+/// it found on its own.
+struct ChunkSummary<T: Height> {
+    lowest: Option<T>,
+    /// The bars lower than every earlier bar of the run, as `(position,
+    /// height)`, leftmost first.
+    prefix_minima: Vec<(usize, T)>,
+    /// The stack at the end of the run, as `(position, height)` with heights
+    /// increasing, and the position where each entry's rectangle starts, or
+    /// `None` when that lies in an earlier run.
+    final_stack: Vec<(usize, T, Option<usize>)>,
+    /// Prefix minima popped within the run, as `(height, last bar of their
+    /// rectangle)`; their rectangles start in an earlier run.
+    open_on_left: Vec<(T, usize)>,
+    best_within: T::Area,
+}
+
+impl<T: Height> ChunkSummary<T> {
+    fn new<H: Histogram<T> + ?Sized>(histogram: &H, start: usize, end: usize) -> Self {
+        let mut summary = Self {
+            lowest: None,
+            prefix_minima: Vec::new(),
+            final_stack: Vec::new(),
+            open_on_left: Vec::new(),
+            best_within: T::Area::default(),
+        };
+        // Entries are `(position, height)`, as in the serial search.
+        let mut stack: Vec<(usize, T)> = Vec::new();
+        for x_pos in start..end {
+            let height = cmp::max(histogram.height_at(x_pos), T::ZERO);
+            if summary.lowest.is_none_or(|lowest| height < lowest) {
+                summary.lowest = Some(height);
+                summary.prefix_minima.push((x_pos, height));
+            }
+            while let Some(&(_, last_height)) = stack.last() {
+                if last_height <= height {
+                    break;
+                }
+                stack.pop();
+                match stack.last() {
+                    Some(&(below, _)) => {
+                        let area = last_height.area(x_pos - below - 1);
+                        summary.best_within = cmp::max(summary.best_within, area);
+                    }
+                    None => summary.open_on_left.push((last_height, x_pos - 1)),
+                }
+            }
+            match stack.last_mut() {
+                Some(last) if last.1 == height => last.0 = x_pos,
+                _ => stack.push((x_pos, height)),
+            }
+        }
+        summary.final_stack = stack
+            .iter()
+            .enumerate()
+            .map(|(depth, &(x_pos, height))| {
+                let left = depth.checked_sub(1).map(|below| stack[below].0 + 1);
+                (x_pos, height, left)
+            })
+            .collect();
+        summary
+    }
+
+    /// Returns the best area of a rectangle of any bar of the run, finding
+    /// where the rectangles of the bars the run could not settle itself end.
+    fn resolve(&self, summaries: &[ChunkSummary<T>], chunk: usize, width: usize) -> T::Area {
+        let mut best = self.best_within;
+        let left_edge = |height: T| {
+            summaries[..chunk]
+                .iter()
+                .rev()
+                .find(|summary| summary.lowest.is_some_and(|lowest| lowest < height))
+                .map_or(0, |summary| {
+                    let stack = &summary.final_stack;
+                    let lower = stack.partition_point(|&(_, below, _)| below < height);
+                    stack[lower - 1].0 + 1
+                })
+        };
+        let right_edge = |height: T| {
+            summaries[chunk + 1..]
+                .iter()
+                .find(|summary| summary.lowest.is_some_and(|lowest| lowest < height))
+                .map_or(width, |summary| {
+                    let minima = &summary.prefix_minima;
+                    minima[minima.partition_point(|&(_, above)| above >= height)].0
+                })
+        };
+        for &(height, right) in &self.open_on_left {
+            if height > T::ZERO {
+                best = cmp::max(best, height.area(right + 1 - left_edge(height)));
+            }
+        }
+        for &(_, height, left) in &self.final_stack {
+            if height > T::ZERO {
+                let left = left.unwrap_or_else(|| left_edge(height));
+                best = cmp::max(best, height.area(right_edge(height) - left));
+            }
+        }
+        best
+    }
+}
@@ -0,0 +1,31 @@
+use crate::histogram::Histogram;
+use std::cmp::Reverse;
+
+/// Returns the area of the largest rectangle the bars could hold if they were
+/// free to be put in any order. Negative heights are treated as zero.
+pub fn compute_area_if_reorderable<H: Histogram + ?Sized>(histogram: &H) -> u64 {
+    compute_best_reordering(histogram).0
+}
+
+/// Returns the area of the largest rectangle the bars could hold in any order,
+/// with how many of the tallest bars that rectangle spans. Fewer bars are
+/// preferred when several counts give the same area, and an area of zero
+/// spans no bars.
+///
+/// Side by side from the tallest down, the `k` tallest bars hold a rectangle
+/// as tall as the `k`th of them, and no order does better. Only a sorted copy
+/// of the heights is kept, so this takes `O(n log n)` time.
+pub fn compute_best_reordering<H: Histogram + ?Sized>(histogram: &H) -> (u64, usize) {
+    let mut heights: Vec<i32> = (0..histogram.width())
+        .map(|x_pos| histogram.height_at(x_pos).max(0))
+        .collect();
+    heights.sort_unstable_by_key(|&height| Reverse(height));
+    let mut best = (0, 0);
+    for (count, &height) in (1..).zip(&heights) {
+        let area = height as u64 * count as u64;
+        if area > best.0 {
+            best = (area, count);
+        }
+    }
+    best
+}
@@ -0,0 +1,30 @@
+use crate::histogram::{Histogram, WeightedHistogram};
+use crate::histogram_run_length::RunLengthHistogram;
+use crate::square_search::compute_area_of_largest_rectangle_weighted;
+
+/// Returns the largest area under the histogram in time linear in the number
+/// of runs rather than bars, by searching the runs as bars as wide as their
+/// lengths. The area is a `u128`, as with
+/// `compute_area_of_largest_rectangle_weighted`.
+pub fn compute_area_of_largest_rectangle_run_length(histogram: &RunLengthHistogram) -> u128 {
+    compute_area_of_largest_rectangle_weighted(&Runs(histogram.runs()))
+}
+
+/// The runs of a `RunLengthHistogram`, each as one weighted bar.
+struct Runs<'a>(&'a [(i32, u64)]);
+
+impl Histogram for Runs<'_> {
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        self.0[horizontal_position].0
+    }
+
+    fn width(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl WeightedHistogram for Runs<'_> {
+    fn width_of(&self, horizontal_position: usize) -> u64 {
+        self.0[horizontal_position].1
+    }
+}
@@ -0,0 +1,66 @@
+use crate::histogram::{Height, Histogram};
+use std::cmp;
+use std::fmt;
+
+/// Computes the same area as `compute_area_of_largest_rectangle`, keeping the
+/// stack of recorded bars in `scratch` rather than in a heap allocation. The
+/// stack never holds more than one entry per bar plus a sentinel, so `scratch`
+/// must have room for `width + 1` entries.
+pub fn compute_area_of_largest_rectangle_with_scratch<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+    scratch: &mut [i64],
+) -> Result<T::Area, ScratchTooSmall> {
+    let width = histogram.width();
+    if scratch.len() <= width {
+        return Err(ScratchTooSmall {
+            required: width + 1,
+            provided: scratch.len(),
+        });
+    }
+    let height_at = |x_pos: i64| {
+        if x_pos >= 0 && (x_pos as usize) < width {
+            cmp::max(histogram.height_at(x_pos as usize), T::ZERO)
+        } else {
+            T::ZERO
+        }
+    };
+    let recorded_bars_of_increasing_height = scratch;
+    recorded_bars_of_increasing_height[0] = -1;
+    let mut recorded = 1;
+    let mut area_of_largest_rectangle = T::Area::default();
+    for x_pos in 0..=width as i64 {
+        let height = height_at(x_pos);
+        while height_at(recorded_bars_of_increasing_height[recorded - 1]) > height {
+            let last = recorded_bars_of_increasing_height[recorded - 1];
+            recorded -= 1;
+            let left = recorded_bars_of_increasing_height[recorded - 1] + 1;
+            let area = height_at(last).area((x_pos - left) as usize);
+            area_of_largest_rectangle = cmp::max(area_of_largest_rectangle, area);
+        }
+        if height_at(recorded_bars_of_increasing_height[recorded - 1]) == height {
+            recorded_bars_of_increasing_height[recorded - 1] = x_pos;
+        } else {
+            recorded_bars_of_increasing_height[recorded] = x_pos;
+            recorded += 1;
+        }
+    }
+    Ok(area_of_largest_rectangle)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScratchTooSmall {
+    pub required: usize,
+    pub provided: usize,
+}
+
+impl fmt::Display for ScratchTooSmall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "scratch buffer holds {} entries but the search needs {}",
+            self.provided, self.required
+        )
+    }
+}
+
+impl std::error::Error for ScratchTooSmall {}
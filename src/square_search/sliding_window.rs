@@ -0,0 +1,283 @@
+use crate::histogram::Histogram;
+use crate::square_search::containing::staircase;
+use crate::square_search::{compute_area_of_largest_rectangle, StreamingSearcher};
+
+/// Returns the area of the largest rectangle within each run of `window`
+/// consecutive bars, in order of the run's first bar. A window at least as wide
+/// as the histogram gives a single answer for the whole histogram.
+///
+/// The bars are split into blocks of `window` bars, so that every window is a
+/// suffix of one block followed by a prefix of the next. The best rectangles
+/// within those suffixes and prefixes come from streaming each block outwards
+/// from the boundary, and the best rectangles crossing the boundary are found
+/// for all windows at once, taking `O(n log window)` time overall.
+///
+/// # Panics
+///
+/// Panics if `window` is zero.
+pub fn sliding_window_largest_rectangle<H: Histogram + ?Sized>(
+    histogram: &H,
+    window: usize,
+) -> Vec<u64> {
+    assert!(window > 0, "window size must be non-zero");
+    let width = histogram.width();
+    if width == 0 {
+        return Vec::new();
+    }
+    if window >= width {
+        return vec![compute_area_of_largest_rectangle(histogram)];
+    }
+    let mut answers = Vec::with_capacity(width - window + 1);
+    let mut crossing = CrossingSearch::new(window);
+    let last_start = width - window;
+    for boundary in (0..width).step_by(window) {
+        let prefix_answers = stream_answers((boundary..width).take(window), histogram);
+        if boundary > 0 {
+            let suffix_answers = stream_answers((0..boundary).rev().take(window - 1), histogram);
+            crossing.search(histogram, boundary);
+            let first_start = boundary.saturating_sub(window - 1);
+            for start in first_start..boundary.min(last_start + 1) {
+                let bars_before_boundary = boundary - start;
+                answers.push(
+                    suffix_answers[bars_before_boundary - 1]
+                        .max(prefix_answers[window - bars_before_boundary - 1])
+                        .max(crossing.best_area(bars_before_boundary)),
+                );
+            }
+        }
+        if boundary <= last_start {
+            answers.push(prefix_answers[window - 1]);
+        }
+    }
+    answers
+}
+
+/// Returns the largest area within the first `k` of the given bars, for each
+/// `k` from one upwards.
+fn stream_answers<H: Histogram + ?Sized>(
+    positions: impl Iterator<Item = usize>,
+    histogram: &H,
+) -> Vec<u64> {
+    let mut searcher = StreamingSearcher::new();
+    positions
+        .map(|pos| searcher.push_bar(histogram.height_at(pos)))
+        .collect()
+}
+
+/// Finds, for every way of placing a window across a block boundary, the
+/// largest rectangle that includes the bars on both sides of the boundary.
+///
+/// A rectangle of height `h` reaching `a` bars left and `b` bars right of the
+/// boundary covers `min(a, i) + min(b, window - i)` bars of the window with `i`
+/// bars before the boundary. Its area rises, stays flat and then falls as `i`
+/// grows, so the best over all heights is the upper envelope of three families
+/// of line segments.
+struct CrossingSearch {
+    window: usize,
+    rising: LineEnvelope,
+    flat: RangeMax,
+    falling: LineEnvelope,
+}
+
+impl CrossingSearch {
+    fn new(window: usize) -> Self {
+        Self {
+            window,
+            rising: LineEnvelope::new(window),
+            flat: RangeMax::new(window),
+            falling: LineEnvelope::new(window),
+        }
+    }
+
+    fn search<H: Histogram + ?Sized>(&mut self, histogram: &H, boundary: usize) {
+        let window = self.window;
+        let left = staircase(histogram, (0..boundary).rev().take(window - 1));
+        let right = staircase(histogram, (boundary..histogram.width()).take(window - 1));
+        let mut crossings = Vec::with_capacity(left.len() + right.len());
+        if let (Some(&(left_height, _)), Some(&(right_height, _))) = (left.first(), right.first()) {
+            let (mut left_step, mut right_step) = (0, 0);
+            let mut height = left_height.min(right_height);
+            loop {
+                while left.get(left_step + 1).is_some_and(|step| step.0 >= height) {
+                    left_step += 1;
+                }
+                while right
+                    .get(right_step + 1)
+                    .is_some_and(|step| step.0 >= height)
+                {
+                    right_step += 1;
+                }
+                crossings.push((height, left[left_step].1, right[right_step].1));
+                let lower_left = left.get(left_step + 1).map(|step| step.0);
+                let lower_right = right.get(right_step + 1).map(|step| step.0);
+                match lower_left.max(lower_right) {
+                    Some(lower) => height = lower,
+                    None => break,
+                }
+            }
+        }
+
+        self.flat.clear();
+        for &(height, before, after) in &crossings {
+            let height = i128::from(height);
+            let (rise_end, fall_start) = (before.min(window - after), before.max(window - after));
+            let flat_area = height * (before + after).min(window) as i128;
+            self.flat.raise(rise_end, fall_start, flat_area);
+        }
+
+        self.rising.clear();
+        crossings.sort_unstable_by_key(|&(_, before, after)| before.min(window - after));
+        let mut pending = crossings.len();
+        for bars_before in (1..window).rev() {
+            while pending > 0 {
+                let (height, before, after) = crossings[pending - 1];
+                if before.min(window - after) < bars_before {
+                    break;
+                }
+                let height = i128::from(height);
+                self.rising.add(height, height * after as i128);
+                pending -= 1;
+            }
+            let best = self.rising.max_at(bars_before);
+            self.flat.raise(bars_before, bars_before, best);
+        }
+
+        self.falling.clear();
+        crossings.sort_unstable_by_key(|&(_, before, after)| before.max(window - after));
+        let mut added = 0;
+        for bars_before in 1..window {
+            while added < crossings.len() {
+                let (height, before, after) = crossings[added];
+                if before.max(window - after) > bars_before {
+                    break;
+                }
+                let height = i128::from(height);
+                self.falling
+                    .add(-height, height * (before + window) as i128);
+                added += 1;
+            }
+            let best = self.falling.max_at(bars_before);
+            self.flat.raise(bars_before, bars_before, best);
+        }
+    }
+
+    fn best_area(&self, bars_before_boundary: usize) -> u64 {
+        self.flat.max_at(bars_before_boundary) as u64
+    }
+}
+
+/// The maximum of a set of lines at integer points in `0..size`, as a Li Chao
+/// tree. Points with no line report zero.
+struct LineEnvelope {
+    size: usize,
+    lines: Vec<Option<(i128, i128)>>,
+}
+
+impl LineEnvelope {
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            lines: vec![None; 4 * size],
+        }
+    }
+
+    fn clear(&mut self) {
+        self.lines.fill(None);
+    }
+
+    fn add(&mut self, slope: i128, intercept: i128) {
+        let (mut node, mut low, mut high) = (1, 0, self.size - 1);
+        let mut line = (slope, intercept);
+        loop {
+            let Some(current) = self.lines[node] else {
+                self.lines[node] = Some(line);
+                return;
+            };
+            let mid = low + (high - low) / 2;
+            let mut current = current;
+            if evaluate(line, mid) > evaluate(current, mid) {
+                std::mem::swap(&mut line, &mut current);
+                self.lines[node] = Some(current);
+            }
+            if low == high {
+                return;
+            }
+            if evaluate(line, low) > evaluate(current, low) {
+                (node, high) = (2 * node, mid);
+            } else if evaluate(line, high) > evaluate(current, high) {
+                (node, low) = (2 * node + 1, mid + 1);
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn max_at(&self, x: usize) -> i128 {
+        let (mut node, mut low, mut high) = (1, 0, self.size - 1);
+        let mut best = 0;
+        loop {
+            if let Some(line) = self.lines[node] {
+                best = best.max(evaluate(line, x));
+            }
+            if low == high {
+                return best;
+            }
+            let mid = low + (high - low) / 2;
+            if x <= mid {
+                (node, high) = (2 * node, mid);
+            } else {
+                (node, low) = (2 * node + 1, mid + 1);
+            }
+        }
+    }
+}
+
+fn evaluate((slope, intercept): (i128, i128), x: usize) -> i128 {
+    slope * x as i128 + intercept
+}
+
+/// Point maxima over values raised on ranges of `0..size`.
+struct RangeMax {
+    size: usize,
+    maxima: Vec<i128>,
+}
+
+impl RangeMax {
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            maxima: vec![0; 2 * size],
+        }
+    }
+
+    fn clear(&mut self) {
+        self.maxima.fill(0);
+    }
+
+    /// Raises every point in `low..=high` to at least `value`.
+    fn raise(&mut self, low: usize, high: usize, value: i128) {
+        let (mut low, mut high) = (low + self.size, high + self.size + 1);
+        while low < high {
+            if low % 2 == 1 {
+                self.maxima[low] = self.maxima[low].max(value);
+                low += 1;
+            }
+            if high % 2 == 1 {
+                high -= 1;
+                self.maxima[high] = self.maxima[high].max(value);
+            }
+            low /= 2;
+            high /= 2;
+        }
+    }
+
+    fn max_at(&self, x: usize) -> i128 {
+        let mut node = x + self.size;
+        let mut best = 0;
+        while node > 0 {
+            best = best.max(self.maxima[node]);
+            node /= 2;
+        }
+        best
+    }
+}
@@ -0,0 +1,36 @@
+use crate::histogram_sparse::SparseHistogram;
+use crate::rectangle::Rectangle;
+use crate::square_search::compute_largest_rectangle;
+
+/// Returns the largest rectangle under the histogram, as
+/// `compute_largest_rectangle` would, without visiting the unlisted bars.
+///
+/// A rectangle cannot cross a bar of height zero, so each run of listed bars
+/// at consecutive positions is searched on its own, taking time linear in the
+/// number of listed bars.
+pub fn compute_largest_rectangle_sparse(histogram: &SparseHistogram) -> Option<Rectangle> {
+    let bars = histogram.nonzero_bars();
+    let mut best: Option<Rectangle> = None;
+    let mut heights = Vec::new();
+    let mut run_start = 0;
+    while run_start < bars.len() {
+        let mut run_end = run_start + 1;
+        while run_end < bars.len() && bars[run_end].0 == bars[run_end - 1].0 + 1 {
+            run_end += 1;
+        }
+        heights.clear();
+        heights.extend(bars[run_start..run_end].iter().map(|&(_, height)| height));
+        if let Some(rectangle) = compute_largest_rectangle(&heights[..]) {
+            if best.is_none_or(|best| rectangle.area > best.area) {
+                let offset = bars[run_start].0;
+                best = Some(Rectangle {
+                    left: rectangle.left + offset,
+                    right: rectangle.right + offset,
+                    ..rectangle
+                });
+            }
+        }
+        run_start = run_end;
+    }
+    best
+}
@@ -0,0 +1,102 @@
+use std::ops::{Deref, DerefMut};
+
+/// A bar recorded on the search's stack. Once a later bar of the same height
+/// takes over the entry, `index` is that later bar's position.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct StackEntry<T> {
+    pub(super) index: usize,
+    pub(super) height: T,
+}
+
+/// The most entries a stack holds without a heap allocation.
+const INLINE_CAPACITY: usize = 32;
+
+/// The search's stack of recorded bars. Histograms of up to
+/// `INLINE_CAPACITY` bars never need more entries than fit inline, so
+/// searching them does not touch the heap.
+pub(super) enum Stack<T> {
+    Inline {
+        entries: [StackEntry<T>; INLINE_CAPACITY],
+        len: usize,
+    },
+    Heap(Vec<StackEntry<T>>),
+}
+
+impl<T: Copy> Stack<T> {
+    pub(super) fn new(filler: T) -> Self {
+        Stack::Inline {
+            entries: [StackEntry {
+                index: 0,
+                height: filler,
+            }; INLINE_CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// Empties the stack and makes room for `capacity` entries, so that no
+    /// push reallocates during a search of that many bars.
+    pub(super) fn clear_with_capacity(&mut self, capacity: usize) {
+        match self {
+            Stack::Inline { len, .. } if capacity <= INLINE_CAPACITY => *len = 0,
+            Stack::Inline { .. } => *self = Stack::Heap(Vec::with_capacity(capacity)),
+            Stack::Heap(entries) => {
+                entries.clear();
+                entries.reserve(capacity);
+            }
+        }
+    }
+
+    pub(super) fn push(&mut self, entry: StackEntry<T>) {
+        match self {
+            Stack::Inline { entries, len } if *len < INLINE_CAPACITY => {
+                entries[*len] = entry;
+                *len += 1;
+            }
+            Stack::Inline { entries, .. } => {
+                let mut spilled = Vec::with_capacity(2 * INLINE_CAPACITY);
+                spilled.extend_from_slice(entries);
+                spilled.push(entry);
+                *self = Stack::Heap(spilled);
+            }
+            Stack::Heap(entries) => entries.push(entry),
+        }
+    }
+
+    pub(super) fn pop(&mut self) -> Option<StackEntry<T>> {
+        match self {
+            Stack::Inline { entries, len } => {
+                *len = len.checked_sub(1)?;
+                Some(entries[*len])
+            }
+            Stack::Heap(entries) => entries.pop(),
+        }
+    }
+
+    /// Returns the heap allocation, if the stack has one.
+    pub(super) fn into_vec(self) -> Vec<StackEntry<T>> {
+        match self {
+            Stack::Inline { .. } => Vec::new(),
+            Stack::Heap(entries) => entries,
+        }
+    }
+}
+
+impl<T> Deref for Stack<T> {
+    type Target = [StackEntry<T>];
+
+    fn deref(&self) -> &[StackEntry<T>] {
+        match self {
+            Stack::Inline { entries, len } => &entries[..*len],
+            Stack::Heap(entries) => entries,
+        }
+    }
+}
+
+impl<T> DerefMut for Stack<T> {
+    fn deref_mut(&mut self) -> &mut [StackEntry<T>] {
+        match self {
+            Stack::Inline { entries, len } => &mut entries[..*len],
+            Stack::Heap(entries) => entries,
+        }
+    }
+}
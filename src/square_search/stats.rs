@@ -0,0 +1,48 @@
+use super::LargestRectangleSearcher;
+use crate::histogram::{Height, Histogram};
+use std::cmp;
+use std::ops::ControlFlow;
+
+/// What a monotonic-stack search did, for monitoring a solver embedded in a
+/// larger program.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// The number of bars searched.
+    pub width: usize,
+    /// The number of candidate rectangles popped off the stack, each of which
+    /// `for_each_candidate` would have been called with.
+    pub candidates: usize,
+    /// The most bars recorded on the stack at once.
+    pub max_stack_depth: usize,
+}
+
+/// Returns the same area as `compute_area_of_largest_rectangle`, along with
+/// what the search did to find it. The search is the same single pass, so
+/// this costs little more than the area alone.
+pub fn compute_area_with_stats<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+) -> (T::Area, SearchStats) {
+    let mut searcher = LargestRectangleSearcher::new(histogram);
+    let width = searcher.width();
+    let mut stats = SearchStats {
+        width,
+        ..SearchStats::default()
+    };
+    let mut area_of_largest_rectangle = T::Area::default();
+    for x_pos in 0..=width {
+        let height = searcher.height_at(x_pos);
+        let flow = searcher.visit_candidates_impl(x_pos, height, &mut |candidate| {
+            stats.candidates += 1;
+            area_of_largest_rectangle = cmp::max(area_of_largest_rectangle, candidate.area);
+            ControlFlow::<()>::Continue(())
+        });
+        debug_assert!(flow.is_continue());
+        // The zero-height sentinel after the last bar is not a bar, so the
+        // entry it leaves does not count.
+        if x_pos < width {
+            let depth = searcher.recorded_bars_of_increasing_height.len();
+            stats.max_stack_depth = cmp::max(stats.max_stack_depth, depth);
+        }
+    }
+    (area_of_largest_rectangle, stats)
+}
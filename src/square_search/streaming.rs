@@ -0,0 +1,175 @@
+use crate::rectangle::Rectangle;
+use crate::square_search::rank;
+
+/// Finds the largest rectangle under a histogram whose bars arrive one at a
+/// time, reporting the best area seen so far after every bar.
+///
+/// The open rectangles on the stack all end at the newest bar, and the area of
+/// each grows linearly as the stream gets longer, so their best is tracked on
+/// an upper hull of lines. Pushing a bar takes `O(log n)` amortized time.
+#[derive(Debug, Default)]
+pub struct StreamingSearcher {
+    recorded_bars_of_increasing_height: Vec<RecordedBar>,
+    hull: Vec<Line>,
+    hull_len: usize,
+    bars_seen: usize,
+    largest_rectangle: Option<Rectangle>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RecordedBar {
+    line: Line,
+    hull_change: HullChange,
+}
+
+/// The area of the open rectangle of a recorded bar, as a function of the
+/// position of the newest bar.
+#[derive(Debug, Clone, Copy)]
+struct Line {
+    height: i32,
+    left: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct HullChange {
+    position: usize,
+    replaced: Option<Line>,
+    previous_len: usize,
+}
+
+impl StreamingSearcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a bar and returns the largest area over all bars seen so far,
+    /// including rectangles that end at this bar. Bars of height zero or less
+    /// end every open rectangle.
+    pub fn push_bar(&mut self, height: i32) -> u64 {
+        let x_pos = self.bars_seen;
+        let mut left = x_pos;
+        while let Some(last) = self.recorded_bars_of_increasing_height.last() {
+            if last.line.height <= height {
+                break;
+            }
+            left = last.line.left;
+            self.undo_hull_change(last.hull_change);
+            self.recorded_bars_of_increasing_height.pop();
+        }
+        let extends_last_recorded_bar = self
+            .recorded_bars_of_increasing_height
+            .last()
+            .is_some_and(|last| last.line.height == height);
+        if height > 0 && !extends_last_recorded_bar {
+            let line = Line { height, left };
+            let hull_change = self.add_to_hull(line);
+            self.recorded_bars_of_increasing_height
+                .push(RecordedBar { line, hull_change });
+        }
+        self.bars_seen += 1;
+        if let Some(open) = self.best_open_rectangle(x_pos) {
+            let is_larger = self
+                .largest_rectangle
+                .is_none_or(|largest| rank(&open) > rank(&largest));
+            if is_larger {
+                self.largest_rectangle = Some(open);
+            }
+        }
+        self.largest_rectangle.map_or(0, |largest| largest.area)
+    }
+
+    /// Returns the largest rectangle over the whole stream, with ties going to
+    /// the leftmost rectangle as in `compute_largest_rectangle`. Rectangles
+    /// still open at the end of the stream are already accounted for, so the
+    /// implicit zero-height bar after the last one changes nothing.
+    pub fn finish(self) -> Option<Rectangle> {
+        self.largest_rectangle
+    }
+
+    /// Returns the largest rectangle over the bars pushed so far.
+    pub fn largest_rectangle(&self) -> Option<Rectangle> {
+        self.largest_rectangle
+    }
+
+    fn best_open_rectangle(&self, x_pos: usize) -> Option<Rectangle> {
+        let hull = &self.hull[..self.hull_len];
+        let first_best = partition_point(hull.len().saturating_sub(1), |k| {
+            value_at(hull[k], x_pos) < value_at(hull[k + 1], x_pos)
+        });
+        let line = *hull.get(first_best)?;
+        Some(Rectangle {
+            left: line.left,
+            right: x_pos,
+            height: line.height,
+            area: value_at(line, x_pos) as u64,
+        })
+    }
+
+    /// Adds a line steeper than every line on the hull. Lines that it makes
+    /// irrelevant are hidden rather than removed, so that popping the bar can
+    /// restore the hull exactly.
+    fn add_to_hull(&mut self, line: Line) -> HullChange {
+        let hull = &self.hull[..self.hull_len];
+        let position = if hull.len() < 2 {
+            hull.len()
+        } else {
+            1 + partition_point(hull.len() - 1, |k| {
+                !is_irrelevant(hull[k], hull[k + 1], line)
+            })
+        };
+        let replaced = self.hull.get(position).copied();
+        if position == self.hull.len() {
+            self.hull.push(line);
+        } else {
+            self.hull[position] = line;
+        }
+        let change = HullChange {
+            position,
+            replaced,
+            previous_len: self.hull_len,
+        };
+        self.hull_len = position + 1;
+        change
+    }
+
+    fn undo_hull_change(&mut self, change: HullChange) {
+        if let Some(line) = change.replaced {
+            self.hull[change.position] = line;
+        }
+        self.hull_len = change.previous_len;
+    }
+}
+
+/// Returns the first index in `0..len` for which `pred` is false, assuming
+/// `pred` holds for a prefix of the indices.
+fn partition_point(len: usize, pred: impl Fn(usize) -> bool) -> usize {
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if pred(mid) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+fn value_at(line: Line, x_pos: usize) -> i128 {
+    i128::from(line.height) * (x_pos as i128 - line.left as i128 + 1)
+}
+
+fn slope(line: Line) -> i128 {
+    i128::from(line.height)
+}
+
+fn intercept(line: Line) -> i128 {
+    value_at(line, 0)
+}
+
+/// Whether `middle` never lies strictly above both of its neighbours, given
+/// that the slopes of `lower`, `middle` and `upper` increase in that order.
+fn is_irrelevant(lower: Line, middle: Line, upper: Line) -> bool {
+    (intercept(lower) - intercept(upper)) * (slope(middle) - slope(lower))
+        <= (intercept(lower) - intercept(middle)) * (slope(upper) - slope(lower))
+}
@@ -0,0 +1,125 @@
+use super::{rank, LargestRectangleSearcher, StackEntry};
+use crate::histogram::{Height, Histogram};
+use crate::rectangle::Rectangle;
+
+/// One step of the monotonic-stack search, as `compute_with_trace` records
+/// it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind"))]
+pub enum SearchEvent {
+    /// The search reached the bar at `pos`, with negative heights read as
+    /// zero. After the last bar it visits a bar of height zero at the
+    /// histogram's width, which pops every bar left on the stack.
+    Visit { pos: usize, height: i32 },
+    /// The visited bar was recorded on top of the stack.
+    Push { pos: usize },
+    /// The visited bar took over the top entry, which had the same height.
+    ReplaceTop { pos: usize },
+    /// The entry last taken over by the bar at `pos` was popped by a lower
+    /// visited bar. `candidate` is the rectangle of its height, reaching from
+    /// just after the entry below it to just before the visited bar.
+    Pop { pos: usize, candidate: Rectangle },
+    /// The candidate just popped is the largest so far, ties going to the
+    /// leftmost.
+    NewBest { rectangle: Rectangle },
+}
+
+/// Every step of a search, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchTrace {
+    pub events: Vec<SearchEvent>,
+}
+
+impl SearchTrace {
+    /// Replays the visits and stack operations and returns the largest
+    /// rectangle they find. Each popped rectangle is worked out again from
+    /// the replayed stack, so neither the recorded candidates nor `NewBest`
+    /// are read. A trace from `compute_with_trace` replays to the rectangle
+    /// it was returned with.
+    pub fn replay(&self) -> Option<Rectangle> {
+        let mut stack: Vec<(usize, i32)> = Vec::new();
+        let mut visited = None;
+        let mut best: Option<Rectangle> = None;
+        for event in &self.events {
+            match *event {
+                SearchEvent::Visit { pos, height } => visited = Some((pos, height)),
+                SearchEvent::Push { .. } => stack.extend(visited),
+                SearchEvent::ReplaceTop { .. } => {
+                    if let (Some(top), Some(bar)) = (stack.last_mut(), visited) {
+                        *top = bar;
+                    }
+                }
+                SearchEvent::Pop { .. } => {
+                    let (Some((_, height)), Some((x_pos, _))) = (stack.pop(), visited) else {
+                        continue;
+                    };
+                    let left = stack.last().map_or(0, |&(index, _)| index + 1);
+                    let Some(right) = x_pos.checked_sub(1).filter(|&right| right >= left) else {
+                        continue;
+                    };
+                    let candidate = Rectangle {
+                        left,
+                        right,
+                        height,
+                        area: height.area(right - left + 1),
+                    };
+                    if best.is_none_or(|best| rank(&candidate) > rank(&best)) {
+                        best = Some(candidate);
+                    }
+                }
+                SearchEvent::NewBest { .. } => {}
+            }
+        }
+        best
+    }
+}
+
+/// Returns the same rectangle as `compute_largest_rectangle`, along with
+/// every step the stack search took to find it, for showing the search at
+/// work.
+pub fn compute_with_trace<H: Histogram + ?Sized>(
+    histogram: &H,
+) -> (Option<Rectangle>, SearchTrace) {
+    let mut searcher = LargestRectangleSearcher::new(histogram);
+    let mut events = Vec::new();
+    let mut best: Option<Rectangle> = None;
+    for x_pos in 0..=searcher.width() {
+        let height = searcher.height_at(x_pos);
+        events.push(SearchEvent::Visit { pos: x_pos, height });
+        // The same steps as `visit_candidates_impl`, recorded as they happen.
+        while let Some(&last) = searcher.recorded_bars_of_increasing_height.last() {
+            if last.height <= height {
+                break;
+            }
+            let candidate = searcher.compute_rectangle_at_last_recorded_bar(x_pos);
+            searcher.recorded_bars_of_increasing_height.pop();
+            events.push(SearchEvent::Pop {
+                pos: last.index,
+                candidate,
+            });
+            if best.is_none_or(|best| rank(&candidate) > rank(&best)) {
+                best = Some(candidate);
+                events.push(SearchEvent::NewBest {
+                    rectangle: candidate,
+                });
+            }
+        }
+        let entry = StackEntry {
+            index: x_pos,
+            height,
+        };
+        match searcher.recorded_bars_of_increasing_height.last_mut() {
+            Some(last) if last.height == height => {
+                *last = entry;
+                events.push(SearchEvent::ReplaceTop { pos: x_pos });
+            }
+            _ => {
+                searcher.recorded_bars_of_increasing_height.push(entry);
+                events.push(SearchEvent::Push { pos: x_pos });
+            }
+        }
+    }
+    (best, SearchTrace { events })
+}
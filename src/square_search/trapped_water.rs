@@ -0,0 +1,29 @@
+use crate::histogram::Histogram;
+
+/// Returns how much water the histogram holds when rain fills every dip
+/// between its bars, in units of one bar's width by one unit of height.
+/// Negative heights are treated as zero, as in every search in this module.
+///
+/// Two positions walk inwards from the ends, always moving the one behind the
+/// lower wall, so the water over each bar is known when it is reached. This
+/// takes `O(n)` time and no allocation.
+pub fn trapped_water<H: Histogram + ?Sized>(histogram: &H) -> u64 {
+    let height_at = |x_pos| i64::from(histogram.height_at(x_pos).max(0));
+    let (mut left, mut right) = (0, histogram.width());
+    let (mut left_wall, mut right_wall) = (0, 0);
+    let mut water = 0;
+    while left < right {
+        if left_wall <= right_wall {
+            let height = height_at(left);
+            left_wall = left_wall.max(height);
+            water += (left_wall - height) as u64;
+            left += 1;
+        } else {
+            right -= 1;
+            let height = height_at(right);
+            right_wall = right_wall.max(height);
+            water += (right_wall - height) as u64;
+        }
+    }
+    water
+}
@@ -0,0 +1,66 @@
+use crate::histogram::{Height, Histogram};
+use crate::rectangle::Rectangle;
+use crate::square_search::lowest_bar::LowestBar;
+use crate::square_search::rank;
+use std::cmp;
+
+/// Returns the largest rectangle that at most `violations` of the bars it
+/// spans fall short of, as when a few bars are glitches that should not cut a
+/// rectangle short. At least one spanned bar must reach the rectangle's
+/// height. With no violations allowed this is `compute_largest_rectangle`.
+///
+/// The best rectangle is as tall as some bar it spans, and reaches sideways
+/// past at most `violations` shorter bars in total. So for each bar, the
+/// nearest `violations + 1` shorter bars on each side are found from a sparse
+/// table, and every way of sharing the violations between the two sides is
+/// tried. This takes `O(n log n)` time to build the table and
+/// `O(n × violations × log n)` time for the search.
+///
+/// Negative heights are treated as zero. When several rectangles share the
+/// largest area, the leftmost one is returned.
+pub fn compute_largest_rectangle_with_violations<T: Height, H: Histogram<T> + ?Sized>(
+    histogram: &H,
+    violations: usize,
+) -> Option<Rectangle<T>> {
+    let heights: Vec<T> = (0..histogram.width())
+        .map(|x_pos| cmp::max(histogram.height_at(x_pos), T::ZERO))
+        .collect();
+    // Beyond one violation per bar, more make no difference.
+    let violations = violations.min(heights.len());
+    let lowest = LowestBar::new(heights.as_slice());
+    let mut starts = Vec::with_capacity(violations + 1);
+    let mut ends = Vec::with_capacity(violations + 1);
+    let mut best: Option<Rectangle<T>> = None;
+    for (x_pos, &height) in heights.iter().enumerate() {
+        if height == T::ZERO {
+            continue;
+        }
+        // `starts[j]` is where a rectangle at this height starts when it
+        // reaches left past `j` shorter bars, and `ends[j]` likewise on the
+        // right.
+        starts.clear();
+        ends.clear();
+        let (mut start, mut end) = (Some(x_pos), Some(x_pos + 1));
+        for _ in 0..=violations {
+            let shorter = start.and_then(|start| lowest.nearest_lower_before(start, height));
+            starts.push(shorter.map_or(0, |shorter| shorter + 1));
+            start = shorter;
+            let shorter = end.and_then(|end| lowest.nearest_lower_from(end, height));
+            ends.push(shorter.unwrap_or(heights.len()));
+            end = shorter.map(|shorter| shorter + 1);
+        }
+        for (left_violations, &left) in starts.iter().enumerate() {
+            let right = ends[violations - left_violations] - 1;
+            let candidate = Rectangle {
+                left,
+                right,
+                height,
+                area: height.area(right + 1 - left),
+            };
+            if best.is_none_or(|best| rank(&candidate) > rank(&best)) {
+                best = Some(candidate);
+            }
+        }
+    }
+    best
+}
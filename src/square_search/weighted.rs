@@ -0,0 +1,42 @@
+use crate::histogram::WeightedHistogram;
+
+/// Returns the largest area under a histogram whose bars have their own widths,
+/// where a rectangle's width is the sum of the widths of the bars it covers.
+/// The area is a `u128` since the widths alone may add up to more than fits in
+/// a `u64`. Bars of height zero or less end every rectangle. Bars of width
+/// zero cover nothing, so they are skipped and neither widen nor end one.
+///
+/// Each recorded bar keeps the offset at which its rectangle starts, so widths
+/// are differences of accumulated offsets rather than of indices.
+pub fn compute_area_of_largest_rectangle_weighted<H: WeightedHistogram + ?Sized>(
+    histogram: &H,
+) -> u128 {
+    let mut recorded_bars_of_increasing_height: Vec<(i32, u128)> = Vec::new();
+    let mut area_of_largest_rectangle = 0;
+    let mut offset = 0;
+    for x_pos in 0..=histogram.width() {
+        if x_pos < histogram.width() && histogram.width_of(x_pos) == 0 {
+            continue;
+        }
+        let height = if x_pos < histogram.width() {
+            histogram.height_at(x_pos).max(0)
+        } else {
+            0
+        };
+        let mut start = offset;
+        while let Some(&(last_height, last_start)) = recorded_bars_of_increasing_height.last() {
+            if last_height < height {
+                break;
+            }
+            let area = last_height as u128 * (offset - last_start);
+            area_of_largest_rectangle = area_of_largest_rectangle.max(area);
+            start = last_start;
+            recorded_bars_of_increasing_height.pop();
+        }
+        if x_pos < histogram.width() {
+            recorded_bars_of_increasing_height.push((height, start));
+            offset += u128::from(histogram.width_of(x_pos));
+        }
+    }
+    area_of_largest_rectangle
+}
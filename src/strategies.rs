@@ -0,0 +1,150 @@
+//! Histograms for property tests, which shrink a failing case to a small one
+//! before reporting it. The crate's own properties are checked with these,
+//! and they are exported so code built on the crate can check its own.
+//!
+//! ```
+//! use leetcode_largest_rectangle::strategies::histogram_strategy;
+//! use leetcode_largest_rectangle::{compute_area_of_largest_rectangle, Histogram};
+//!
+//! // The largest rectangle is never smaller than the tallest bar.
+//! histogram_strategy(20, 50).assert_holds(200, |histogram| {
+//!     let tallest = histogram.max_height().unwrap_or(0).max(0);
+//!     compute_area_of_largest_rectangle(histogram) >= tallest as u64
+//! });
+//! ```
+
+use crate::generate::{self, SplitMix64};
+use crate::histogram_concrete::ConcreteHistogram;
+use std::fmt;
+
+/// Returns a strategy for histograms of at most `max_width` bars with heights
+/// in `0..=max_height`.
+///
+/// # Panics
+///
+/// Panics if `max_height` is negative.
+pub fn histogram_strategy(max_width: usize, max_height: i32) -> HistogramStrategy {
+    assert!(max_height >= 0, "max_height {max_height} is negative");
+    HistogramStrategy {
+        max_width,
+        max_height,
+    }
+}
+
+/// Generates histograms from seeds and shrinks them. Half the seeds give
+/// uniform noise and half give the plateaus, ramps and runs of zeros of
+/// `generate::from_bytes`, cut down to the strategy's bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistogramStrategy {
+    max_width: usize,
+    max_height: i32,
+}
+
+impl HistogramStrategy {
+    /// The histogram for `seed`, which is always the same.
+    pub fn generate(&self, seed: u64) -> ConcreteHistogram {
+        let mut rng = SplitMix64(seed);
+        let width = rng.below(self.max_width as u64 + 1) as usize;
+        let span = self.max_height as u64 + 1;
+        if seed.is_multiple_of(2) {
+            return (0..width).map(|_| rng.below(span) as i32).collect();
+        }
+        let bytes: Vec<u8> = (0..4 * self.max_width + 8)
+            .map(|_| rng.next_u64() as u8)
+            .collect();
+        let structured = generate::from_bytes(&bytes);
+        (0..width)
+            .map(|x_pos| match structured.heights().get(x_pos) {
+                Some(&height) => height.min(self.max_height),
+                None => rng.below(span) as i32,
+            })
+            .collect()
+    }
+
+    /// Histograms simpler than `histogram`, simplest first: with runs of bars
+    /// removed, from half of them down to one, then with every bar halved or
+    /// lowered by one, and then with single bars lowered to zero, halved or
+    /// lowered by one. Bars are never lowered below zero.
+    pub fn shrink(&self, histogram: &ConcreteHistogram) -> Vec<ConcreteHistogram> {
+        let bars = histogram.heights();
+        let mut simpler = Vec::new();
+        let mut run = bars.len().div_ceil(2);
+        while run > 0 {
+            for start in (0..bars.len()).step_by(run) {
+                let end = (start + run).min(bars.len());
+                simpler.push(bars[..start].iter().chain(&bars[end..]).collect());
+            }
+            run /= 2;
+        }
+        for lower in [|height: i32| height / 2, |height: i32| height - 1] {
+            if bars.iter().any(|&height| height > 0) {
+                simpler.push(bars.iter().map(|&height| lower(height).max(0)).collect());
+            }
+        }
+        for (x_pos, &height) in bars.iter().enumerate() {
+            for lower in [0, height / 2, height - 1] {
+                if (0..height).contains(&lower) {
+                    let mut lowered = bars.to_vec();
+                    lowered[x_pos] = lower;
+                    simpler.push(ConcreteHistogram::new(lowered));
+                }
+            }
+        }
+        simpler
+    }
+
+    /// Checks `property` on the histograms of seeds `0..cases`. The first
+    /// that fails is shrunk, by taking the first simpler histogram that
+    /// fails too until none does, and returned.
+    pub fn check<F>(&self, cases: u64, property: F) -> Result<(), Counterexample>
+    where
+        F: Fn(&ConcreteHistogram) -> bool,
+    {
+        let Some(seed) = (0..cases).find(|&seed| !property(&self.generate(seed))) else {
+            return Ok(());
+        };
+        let mut failing = self.generate(seed);
+        while let Some(simpler) = self
+            .shrink(&failing)
+            .into_iter()
+            .find(|simpler| !property(simpler))
+        {
+            failing = simpler;
+        }
+        Err(Counterexample {
+            seed,
+            bars: failing.heights().to_vec(),
+        })
+    }
+
+    /// Like `check`, but panics with the shrunk histogram if `property`
+    /// fails, for use in tests.
+    pub fn assert_holds<F>(&self, cases: u64, property: F)
+    where
+        F: Fn(&ConcreteHistogram) -> bool,
+    {
+        if let Err(counterexample) = self.check(cases, property) {
+            panic!("{counterexample}");
+        }
+    }
+}
+
+/// A histogram a property failed for, shrunk from the one generated from
+/// `seed`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterexample {
+    pub seed: u64,
+    pub bars: Vec<i32>,
+}
+
+impl fmt::Display for Counterexample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "property failed for bars {:?}, shrunk from the histogram of seed {}",
+            self.bars, self.seed
+        )
+    }
+}
+
+impl std::error::Error for Counterexample {}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::square_search::{self, AreaDistribution};
+use leetcode_largest_rectangle::ConcreteHistogram;
+
+#[test]
+fn test_leetcode_example() {
+    // The maximal rectangles have areas 2, 10, 6, 8, 3 and 6.
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let distribution = square_search::candidate_area_histogram(&histogram, 3);
+    assert_eq!(
+        distribution,
+        AreaDistribution {
+            buckets: vec![(2..=4, 2), (5..=7, 2), (8..=10, 2)]
+        }
+    );
+    assert_eq!(
+        distribution.to_string(),
+        " 2..=4: 2\n 5..=7: 2\n8..=10: 2\n"
+    );
+}
+
+#[test]
+fn test_single_distinct_area_gives_one_bucket() {
+    let histogram = ConcreteHistogram::new(vec![4, 0, 4, 0, 4]);
+    assert_eq!(
+        square_search::candidate_area_histogram(&histogram, 5).buckets,
+        vec![(4..=4, 3)]
+    );
+}
+
+#[test]
+fn test_last_bucket_is_cut_short_at_largest_area() {
+    // The maximal rectangles have areas 2, 3 and 4.
+    let histogram = ConcreteHistogram::new(vec![1, 2, 1, 0, 4]);
+    assert_eq!(
+        square_search::candidate_area_histogram(&histogram, 2).buckets,
+        vec![(2..=3, 2), (4..=4, 1)]
+    );
+}
+
+#[test]
+fn test_histogram_without_rectangles_has_no_buckets() {
+    for bars in [vec![], vec![0, 0]] {
+        let histogram = ConcreteHistogram::new(bars);
+        let distribution = square_search::candidate_area_histogram(&histogram, 4);
+        assert!(distribution.buckets.is_empty());
+        assert_eq!(distribution.to_string(), "");
+    }
+}
+
+#[test]
+#[should_panic(expected = "bucket count must be non-zero")]
+fn test_zero_buckets_panics() {
+    let histogram = ConcreteHistogram::new(vec![1]);
+    square_search::candidate_area_histogram(&histogram, 0);
+}
+
+#[test]
+fn test_buckets_count_every_maximal_rectangle() {
+    let mut rng = Rng::new(81);
+    for _ in 0..200 {
+        let histogram = ConcreteHistogram::new(rng.bars(50, 100));
+        let areas: Vec<u64> = square_search::maximal_rectangles(&histogram)
+            .map(|rectangle| rectangle.area)
+            .collect();
+        for bucket_count in [1, 2, 7, 100] {
+            let distribution = square_search::candidate_area_histogram(&histogram, bucket_count);
+            assert!(distribution.buckets.len() <= bucket_count);
+            for (range, count) in &distribution.buckets {
+                assert_eq!(
+                    areas.iter().filter(|&area| range.contains(area)).count(),
+                    *count
+                );
+            }
+            let counted: usize = distribution.buckets.iter().map(|(_, count)| count).sum();
+            assert_eq!(counted, areas.len());
+            assert_eq!(
+                distribution,
+                square_search::candidate_area_histogram(&histogram, bucket_count)
+            );
+        }
+    }
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::square_search::{self, WidthMismatch};
+use leetcode_largest_rectangle::{ConcreteHistogram, Rectangle2D};
+
+/// The largest area over every span of columns, from the room left between
+/// the span's highest floor and lowest ceiling.
+fn brute_force_area_between(floors: &[i32], ceilings: &[i32]) -> u64 {
+    let mut best = 0;
+    for left in 0..floors.len() {
+        let (mut highest_floor, mut lowest_ceiling) = (i64::MIN, i64::MAX);
+        for right in left..floors.len() {
+            highest_floor = highest_floor.max(i64::from(floors[right]));
+            lowest_ceiling = lowest_ceiling.min(i64::from(ceilings[right]));
+            let room = (lowest_ceiling - highest_floor).max(0) as u64;
+            best = best.max(room * (right - left + 1) as u64);
+        }
+    }
+    best
+}
+
+fn between(floors: &[i32], ceilings: &[i32]) -> Option<Rectangle2D> {
+    square_search::largest_rectangle_between(
+        &ConcreteHistogram::new(floors.to_vec()),
+        &ConcreteHistogram::new(ceilings.to_vec()),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_flat_floor_is_an_ordinary_histogram() {
+    assert_eq!(
+        between(&[0; 6], &[2, 1, 5, 6, 2, 3]),
+        Some(Rectangle2D {
+            left: 2,
+            right: 3,
+            bottom: 0,
+            top: 5,
+            area: 10
+        })
+    );
+}
+
+#[test]
+fn test_rectangle_must_sit_above_highest_floor_under_it() {
+    // The gaps are all 3, but the floors step up too far for one rectangle to
+    // fit across all four columns at once.
+    let floors = [0, 0, 2, 2];
+    let ceilings = [3, 3, 5, 5];
+    assert_eq!(
+        between(&floors, &ceilings),
+        Some(Rectangle2D {
+            left: 0,
+            right: 1,
+            bottom: 0,
+            top: 3,
+            area: 6
+        })
+    );
+    assert_eq!(brute_force_area_between(&floors, &ceilings), 6);
+}
+
+#[test]
+fn test_no_room_between_floor_and_ceiling() {
+    assert_eq!(between(&[], &[]), None);
+    assert_eq!(between(&[3, 4, 5], &[3, 2, 5]), None);
+}
+
+#[test]
+fn test_negative_and_extreme_heights() {
+    assert_eq!(
+        between(&[i32::MIN, -5], &[i32::MAX, 5]),
+        Some(Rectangle2D {
+            left: 0,
+            right: 0,
+            bottom: i32::MIN,
+            top: i32::MAX,
+            area: u32::MAX as u64
+        })
+    );
+}
+
+#[test]
+fn test_width_mismatch_is_an_error() {
+    let error = square_search::largest_rectangle_between(
+        &ConcreteHistogram::new(vec![0, 0]),
+        &ConcreteHistogram::new(vec![1, 1, 1]),
+    );
+    assert_eq!(
+        error,
+        Err(WidthMismatch {
+            floor_width: 2,
+            ceiling_width: 3
+        })
+    );
+    assert_eq!(
+        error.unwrap_err().to_string(),
+        "the floor has 2 bars but the ceiling has 3"
+    );
+}
+
+#[test]
+fn test_matches_brute_force() {
+    let mut rng = Rng::new(72);
+    for _ in 0..2000 {
+        let width = rng.below(10);
+        let floors: Vec<i32> = (0..width).map(|_| rng.below(8) as i32 - 4).collect();
+        let ceilings: Vec<i32> = (0..width).map(|_| rng.below(12) as i32 - 4).collect();
+        let found = between(&floors, &ceilings);
+        assert_eq!(
+            found.map_or(0, |rectangle| rectangle.area),
+            brute_force_area_between(&floors, &ceilings),
+            "{floors:?} {ceilings:?}"
+        );
+        if let Some(rectangle) = found {
+            assert!(rectangle.top > rectangle.bottom);
+            assert_eq!(
+                rectangle.area,
+                (rectangle.top - rectangle.bottom) as u64 * rectangle.width() as u64
+            );
+            for x_pos in rectangle.left..=rectangle.right {
+                assert!(floors[x_pos] <= rectangle.bottom, "{floors:?} {ceilings:?}");
+                assert!(ceilings[x_pos] >= rectangle.top, "{floors:?} {ceilings:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+mod common;
+
+use common::{all_histograms, Rng};
+use leetcode_largest_rectangle::square_search::{self, Boundaries};
+use leetcode_largest_rectangle::ConcreteHistogram;
+
+/// The largest area, as a fold over each bar's boundaries.
+fn area_from_boundaries(bars: &[i32], boundaries: &Boundaries) -> u64 {
+    (0..bars.len())
+        .map(|x_pos| {
+            let left = boundaries.prev_smaller[x_pos].map_or(0, |below| below + 1);
+            let right = boundaries.next_smaller_or_equal[x_pos].unwrap_or(bars.len());
+            bars[x_pos].max(0) as u64 * (right - left) as u64
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_boundaries_of_empty_histogram() {
+    let histogram = ConcreteHistogram::new(vec![]);
+    assert_eq!(square_search::boundaries(&histogram), Boundaries::default());
+}
+
+#[test]
+fn test_boundaries_of_single_bar() {
+    let histogram = ConcreteHistogram::new(vec![4]);
+    assert_eq!(
+        square_search::boundaries(&histogram),
+        Boundaries {
+            prev_smaller: vec![None],
+            next_smaller_or_equal: vec![None],
+        }
+    );
+}
+
+#[test]
+fn test_boundaries_of_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::boundaries(&histogram),
+        Boundaries {
+            prev_smaller: vec![None, None, Some(1), Some(2), Some(1), Some(4)],
+            next_smaller_or_equal: vec![Some(1), None, Some(4), Some(4), None, None],
+        }
+    );
+}
+
+#[test]
+fn test_boundaries_treat_ties_differently_on_each_side() {
+    let histogram = ConcreteHistogram::new(vec![3, 3, 3]);
+    assert_eq!(
+        square_search::boundaries(&histogram),
+        Boundaries {
+            prev_smaller: vec![None, None, None],
+            next_smaller_or_equal: vec![Some(1), Some(2), None],
+        }
+    );
+}
+
+#[test]
+fn test_boundaries_match_their_definitions() {
+    for bars in all_histograms(6, 3) {
+        let boundaries = square_search::boundaries(&ConcreteHistogram::new(bars.clone()));
+        for x_pos in 0..bars.len() {
+            let prev_smaller = (0..x_pos).rev().find(|&left| bars[left] < bars[x_pos]);
+            let next_smaller_or_equal =
+                (x_pos + 1..bars.len()).find(|&right| bars[right] <= bars[x_pos]);
+            assert_eq!(boundaries.prev_smaller[x_pos], prev_smaller, "{bars:?}");
+            assert_eq!(
+                boundaries.next_smaller_or_equal[x_pos], next_smaller_or_equal,
+                "{bars:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_largest_area_is_a_fold_over_boundaries() {
+    let mut rng = Rng::new(64);
+    for _ in 0..500 {
+        let mut bars = rng.bars(60, 20);
+        for bar in bars.iter_mut().step_by(7) {
+            *bar -= 10;
+        }
+        let histogram = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            area_from_boundaries(&bars, &square_search::boundaries(&histogram)),
+            square_search::compute_area_of_largest_rectangle(&histogram),
+            "{bars:?}"
+        );
+    }
+}
+
+fn brute_force_sum_of_subarray_minimums(bars: &[i32]) -> u128 {
+    let mut sum = 0;
+    for left in 0..bars.len() {
+        let mut minimum = i32::MAX;
+        for &bar in &bars[left..] {
+            minimum = minimum.min(bar.max(0));
+            sum += minimum as u128;
+        }
+    }
+    sum
+}
+
+#[test]
+fn test_sum_of_subarray_minimums_of_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![3, 1, 2, 4]);
+    assert_eq!(square_search::sum_of_subarray_minimums(&histogram), 17);
+}
+
+#[test]
+fn test_sum_of_subarray_minimums_of_empty_histogram() {
+    let histogram = ConcreteHistogram::new(vec![]);
+    assert_eq!(square_search::sum_of_subarray_minimums(&histogram), 0);
+}
+
+#[test]
+fn test_sum_of_subarray_minimums_matches_brute_force() {
+    for bars in all_histograms(6, 3) {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            square_search::sum_of_subarray_minimums(&histogram),
+            brute_force_sum_of_subarray_minimums(&bars),
+            "{bars:?}"
+        );
+    }
+    let mut rng = Rng::new(65);
+    for _ in 0..200 {
+        let mut bars = rng.bars(100, 1000);
+        for bar in bars.iter_mut().step_by(9) {
+            *bar = -*bar;
+        }
+        let histogram = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            square_search::sum_of_subarray_minimums(&histogram),
+            brute_force_sum_of_subarray_minimums(&bars),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_sum_of_subarray_minimums_of_wide_tall_histogram() {
+    let width: u128 = 100_000;
+    let histogram = ConcreteHistogram::new(vec![10_000; width as usize]);
+    assert_eq!(
+        square_search::sum_of_subarray_minimums(&histogram),
+        10_000 * width * (width + 1) / 2
+    );
+}
+
+#[test]
+fn test_per_bar_maximal_areas_of_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::per_bar_maximal_areas(&histogram),
+        vec![2, 6, 10, 6, 8, 3]
+    );
+}
+
+#[test]
+fn test_per_bar_maximal_areas_credit_every_bar_of_a_plateau() {
+    let histogram = ConcreteHistogram::new(vec![1, 3, 3, 3, 0, 3]);
+    assert_eq!(
+        square_search::per_bar_maximal_areas(&histogram),
+        vec![4, 9, 9, 9, 0, 3]
+    );
+}
+
+#[test]
+fn test_per_bar_maximal_areas_match_definition() {
+    let mut rng = Rng::new(75);
+    for _ in 0..300 {
+        let mut bars = rng.bars(60, 10);
+        for bar in bars.iter_mut().step_by(8) {
+            *bar = -*bar;
+        }
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let areas = square_search::per_bar_maximal_areas(&histogram);
+        let clamped: Vec<i32> = bars.iter().map(|&bar| bar.max(0)).collect();
+        for (x_pos, &height) in clamped.iter().enumerate() {
+            let start = (0..x_pos)
+                .rev()
+                .find(|&left| clamped[left] < height)
+                .map_or(0, |left| left + 1);
+            let end = (x_pos + 1..clamped.len())
+                .find(|&right| clamped[right] < height)
+                .unwrap_or(clamped.len());
+            assert_eq!(
+                areas[x_pos],
+                height as u64 * (end - start) as u64,
+                "{bars:?}"
+            );
+        }
+        assert_eq!(
+            areas.iter().copied().max().unwrap_or(0),
+            square_search::compute_area_of_largest_rectangle(&histogram),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_per_bar_maximal_areas_of_empty_histogram() {
+    let histogram = ConcreteHistogram::new(vec![]);
+    assert!(square_search::per_bar_maximal_areas(&histogram).is_empty());
+}
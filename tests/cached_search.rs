@@ -0,0 +1,143 @@
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::cached_search::CachedLargestRectangle;
+use leetcode_largest_rectangle::{square_search, ConcreteHistogram, Histogram, HistogramMut};
+use std::cell::Cell;
+
+struct CountingHistogram {
+    histogram: ConcreteHistogram,
+    height_at_calls: Cell<usize>,
+}
+
+impl Histogram for CountingHistogram {
+    fn width(&self) -> usize {
+        self.histogram.width()
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        self.height_at_calls.set(self.height_at_calls.get() + 1);
+        self.histogram.height_at(horizontal_position)
+    }
+}
+
+impl HistogramMut for CountingHistogram {
+    fn set_height(&mut self, horizontal_position: usize, height: i32) {
+        self.histogram.set_height(horizontal_position, height);
+    }
+}
+
+#[test]
+fn test_set_height_on_concrete_histogram() {
+    let mut histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    histogram.set_height(1, 4);
+    assert_eq!(histogram.height_at(1), 4);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram),
+        12
+    );
+}
+
+#[test]
+fn test_cached_answer_follows_scripted_edits() {
+    let mut cached = CachedLargestRectangle::new(ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]));
+    let edits = [
+        (0, 1),
+        (5, 0),
+        (1, 5),
+        (3, 2),
+        (3, 9),
+        (4, 7),
+        (2, 0),
+        (0, 4),
+        (4, 7),
+        (5, 8),
+    ];
+    assert_eq!(
+        cached.largest_rectangle(),
+        square_search::compute_largest_rectangle(cached.histogram())
+    );
+    for (x_pos, height) in edits {
+        cached.set_height(x_pos, height);
+        assert_eq!(
+            cached.largest_rectangle(),
+            square_search::compute_largest_rectangle(cached.histogram()),
+            "after setting bar {x_pos} to {height}"
+        );
+    }
+}
+
+#[test]
+fn test_cached_answer_follows_random_edits() {
+    let mut rng = Rng::new(23);
+    for _ in 0..200 {
+        let bars = rng.bars(12, 5);
+        if bars.is_empty() {
+            continue;
+        }
+        let width = bars.len();
+        let mut cached = CachedLargestRectangle::new(ConcreteHistogram::new(bars));
+        for _ in 0..30 {
+            cached.set_height(rng.below(width), rng.below(6) as i32);
+            assert_eq!(
+                cached.largest_rectangle(),
+                square_search::compute_largest_rectangle(cached.histogram())
+            );
+        }
+    }
+}
+
+#[test]
+fn test_lowering_bar_outside_largest_rectangle_skips_search() {
+    let histogram = CountingHistogram {
+        histogram: ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]),
+        height_at_calls: Cell::new(0),
+    };
+    let mut cached = CachedLargestRectangle::new(histogram);
+    assert_eq!(
+        cached.largest_rectangle().map(|largest| largest.area),
+        Some(10)
+    );
+    cached.histogram().height_at_calls.set(0);
+    cached.set_height(5, 1);
+    assert_eq!(
+        cached.largest_rectangle().map(|largest| largest.area),
+        Some(10)
+    );
+    assert_eq!(cached.histogram().height_at_calls.get(), 1);
+}
+
+#[test]
+fn test_cached_answer_follows_pushes_and_pops() {
+    let mut rng = Rng::new(101);
+    for _ in 0..200 {
+        let mut cached = CachedLargestRectangle::new(ConcreteHistogram::new(rng.bars(8, 5)));
+        for _ in 0..30 {
+            if rng.below(3) == 0 {
+                let width = cached.histogram().width();
+                let last = cached.histogram().heights().last().copied();
+                assert_eq!(cached.pop(), last);
+                assert_eq!(cached.histogram().width(), width.saturating_sub(1));
+            } else {
+                cached.push(rng.below(6) as i32);
+            }
+            assert_eq!(
+                cached.largest_rectangle(),
+                square_search::compute_largest_rectangle(cached.histogram())
+            );
+        }
+    }
+}
+
+#[test]
+fn test_popping_an_empty_histogram_returns_none() {
+    let mut cached = CachedLargestRectangle::new(ConcreteHistogram::new(vec![]));
+    assert_eq!(cached.pop(), None);
+    cached.push(3);
+    assert_eq!(
+        cached.largest_rectangle().map(|largest| largest.area),
+        Some(3)
+    );
+    assert_eq!(cached.pop(), Some(3));
+    assert_eq!(cached.largest_rectangle(), None);
+}
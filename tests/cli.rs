@@ -0,0 +1,1116 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_largest-rectangle"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn run_with_stdin(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_largest-rectangle"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+fn stderr(output: &Output) -> &str {
+    std::str::from_utf8(&output.stderr).unwrap()
+}
+
+#[test]
+fn test_heights_from_arguments() {
+    let output = run(&["rect", "2", "1", "5", "6", "2", "3"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "10 (bars 2..=3, height 5)\n");
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn test_negative_heights_are_accepted() {
+    let output = run(&["rect", "-1", "4", "4"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "8 (bars 1..=2, height 4)\n");
+}
+
+#[test]
+fn test_histogram_without_rectangle_prints_zero() {
+    let output = run(&["0", "0"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "0\n");
+}
+
+#[test]
+fn test_bad_height_is_named() {
+    let output = run(&["2", "x1", "5"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).contains("`x1` is not a bar height"));
+    let output = run(&["99999999999"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("`99999999999`"));
+}
+
+#[test]
+fn test_no_arguments_prints_usage() {
+    let output = run(&[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).starts_with("Usage: largest-rectangle"));
+}
+
+#[test]
+fn test_help_prints_usage() {
+    let output = run(&["--help"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Usage: largest-rectangle"));
+}
+
+#[test]
+fn test_heights_from_stdin() {
+    let output = run_with_stdin(&["rect", "--stdin"], b"2 1 5\n6 2 3\n");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "10 (bars 2..=3, height 5)\n");
+}
+
+#[test]
+fn test_stdin_tolerates_blank_lines_and_trailing_whitespace() {
+    let output = run_with_stdin(&["rect", "--stdin"], b"\n\n  2 1 \t5  \n\n6\r\n2 3   \n\n");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "10 (bars 2..=3, height 5)\n");
+    let output = run_with_stdin(&["rect", "--stdin"], b"4 4");
+    assert_eq!(stdout(&output), "8 (bars 0..=1, height 4)\n");
+}
+
+#[test]
+fn test_empty_stdin_prints_zero() {
+    for stdin in [&b""[..], b"\n \n"] {
+        let output = run_with_stdin(&["--stdin"], stdin);
+        assert!(output.status.success());
+        assert_eq!(stdout(&output), "0\n");
+    }
+}
+
+#[test]
+fn test_long_sequence_from_stdin() {
+    let sequence: String = (1..=100).map(|height| format!("{height}\n")).collect();
+    let output = run_with_stdin(&["rect", "--stdin"], sequence.as_bytes());
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "2550 (bars 49..=99, height 50)\n");
+}
+
+#[test]
+fn test_bad_token_on_stdin_is_located() {
+    let output = run_with_stdin(&["--stdin"], b"1 2\n3 four 5\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "error: stdin: line 2, column 3: `four` is not a bar height\n"
+    );
+}
+
+#[test]
+fn test_unknown_option_is_a_usage_error() {
+    let output = run(&["--frobnicate"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("unknown option `--frobnicate`"));
+}
+
+#[test]
+fn test_heights_from_file() {
+    let output = run(&["rect", "--input", "tests/fixtures/bars.txt"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "10 (bars 2..=3, height 5)\n");
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn test_dash_input_reads_stdin() {
+    let output = run_with_stdin(&["rect", "--input", "-"], b"2 1 5 6 2 3");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "10 (bars 2..=3, height 5)\n");
+}
+
+#[test]
+fn test_missing_file_is_an_io_error() {
+    let output = run(&["--input", "tests/fixtures/no_such_file.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).starts_with("error: tests/fixtures/no_such_file.txt: "));
+    assert!(stderr(&output).contains("No such file or directory"));
+}
+
+#[test]
+fn test_bad_token_in_file_is_located() {
+    let output = run(&["--input", "tests/fixtures/bad_token.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "error: tests/fixtures/bad_token.txt: line 3, column 3: `four` is not a bar height\n"
+    );
+}
+
+#[test]
+fn test_input_needs_a_path() {
+    let output = run(&["--input"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("`--input` needs a path"));
+}
+
+#[test]
+fn test_input_with_argument_heights_is_a_usage_error() {
+    let output = run(&["--input", "tests/fixtures/bars.txt", "4"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_json_array_and_object_inputs() {
+    for stdin in [&b"[2,1,5,6,2,3]"[..], b" {\"bars\": [2, 1, 5, 6, 2, 3]}\n"] {
+        let output = run_with_stdin(&["--json"], stdin);
+        assert!(output.status.success());
+        assert_eq!(
+            stdout(&output),
+            "{\"area\":10,\"left\":2,\"right\":3,\"height\":5}\n"
+        );
+        assert_eq!(stderr(&output), "");
+    }
+}
+
+#[test]
+fn test_json_output_keys_are_stable() {
+    let keys = |output: &Output| -> Vec<String> {
+        stdout(output)
+            .trim_end()
+            .trim_start_matches('{')
+            .trim_end_matches('}')
+            .split(',')
+            .map(|member| member.split(':').next().unwrap().to_string())
+            .collect()
+    };
+    let expected = ["\"area\"", "\"left\"", "\"right\"", "\"height\""];
+    let output = run(&["--json", "2", "1", "5"]);
+    assert_eq!(keys(&output), expected);
+    let output = run_with_stdin(&["--json"], b"[0, 0]");
+    assert_eq!(keys(&output), expected);
+    assert_eq!(
+        stdout(&output),
+        "{\"area\":0,\"left\":null,\"right\":null,\"height\":null}\n"
+    );
+}
+
+#[test]
+fn test_json_input_matches_plain_input() {
+    let mut state = 7_u64;
+    for width in 0..40 {
+        let bars: Vec<String> = (0..width)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 33) % 12).to_string()
+            })
+            .collect();
+        let array = format!("[{}]", bars.join(","));
+        let from_json = run_with_stdin(&["--json"], array.as_bytes());
+        let from_object = run_with_stdin(&["--json"], format!("{{\"bars\":{array}}}").as_bytes());
+        let from_text = run_with_stdin(&["rect", "--stdin"], bars.join(" ").as_bytes());
+        assert_eq!(stdout(&from_json), stdout(&from_object));
+        let plain = match stdout(&from_json)
+            .trim_end()
+            .trim_start_matches('{')
+            .trim_end_matches('}')
+            .split(',')
+            .map(|member| member.split(':').nth(1).unwrap())
+            .collect::<Vec<_>>()[..]
+        {
+            ["0", "null", "null", "null"] => "0\n".to_string(),
+            [area, left, right, height] => {
+                format!("{area} (bars {left}..={right}, height {height})\n")
+            }
+            _ => panic!("unexpected output {}", stdout(&from_json)),
+        };
+        assert_eq!(plain, stdout(&from_text), "bars {array}");
+    }
+}
+
+#[test]
+fn test_malformed_json_is_located() {
+    let output = run_with_stdin(&["--json"], b"[2, 1,\n 5 6]");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "{\"error\":\"syntax\",\"source\":\"stdin\",\"line\":2,\"column\":4,\
+         \"message\":\"expected `,` or `]`\"}\n"
+    );
+    for stdin in [
+        &b""[..],
+        b"[1, 2",
+        b"[1,]",
+        b"{\"bars\" [1]}",
+        b"[1] [2]",
+        b"[\"\\x\"]",
+    ] {
+        let output = run_with_stdin(&["--json"], stdin);
+        assert_eq!(output.status.code(), Some(2));
+        assert!(stderr(&output).starts_with("{\"error\":\"syntax\""));
+    }
+}
+
+#[test]
+fn test_json_of_the_wrong_shape_is_rejected() {
+    for (stdin, message) in [
+        (&b"{\"heights\": [1]}"[..], "object has no `bars` member"),
+        (b"{\"bars\": 3}", "`bars` is not an array"),
+        (b"\"1 2 3\"", "expected an array of bar heights"),
+        (b"[1, 2.5]", "bar 1: `2.5` is not a bar height"),
+        (b"[1, \"2\"]", "bar 1 is the string"),
+        (b"[1, true]", "bar 1 is `true`, not a number"),
+        (b"[null]", "bar 0 is not a number"),
+        (b"[99999999999]", "bar 0: `99999999999` is not a bar height"),
+    ] {
+        let output = run_with_stdin(&["--json"], stdin);
+        assert_eq!(output.status.code(), Some(2));
+        assert!(stderr(&output).starts_with("{\"error\":\"schema\""));
+        assert!(stderr(&output).contains(message), "{}", stderr(&output));
+    }
+}
+
+#[test]
+fn test_json_errors_escape_their_strings() {
+    let output = run(&["--json", "--input", "tests/fixtures/no \"such\" file.json"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output)
+        .starts_with("{\"error\":\"io\",\"source\":\"tests/fixtures/no \\\"such\\\" file.json\""));
+}
+
+#[test]
+fn test_json_object_may_have_other_members() {
+    let stdin =
+        "{\"name\": \"caf\\u00e9 \\ud83d\\ude00\", \"bars\": [4, 4], \"extra\": [null, true, {}]}";
+    let output = run_with_stdin(&["--json"], stdin.as_bytes());
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "{\"area\":8,\"left\":0,\"right\":1,\"height\":4}\n"
+    );
+}
+
+#[test]
+fn test_csv_column_by_name_and_index() {
+    let path = "tests/fixtures/measurements.csv";
+    for column in ["height", "3"] {
+        let output = run(&["rect", "--csv", path, "--column", column, "--has-header"]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert_eq!(stdout(&output), "10 (bars 2..=3, height 5)\n");
+    }
+}
+
+#[test]
+fn test_csv_without_header_reads_every_row() {
+    let output = run_with_stdin(
+        &["rect", "--csv", "-", "--column", "2"],
+        b"a,4\nb,\"4\"\n\nc,1\n",
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "8 (bars 0..=1, height 4)\n");
+    let output = run_with_stdin(&["rect", "--csv", "-"], b"3\n3\n3\n");
+    assert_eq!(stdout(&output), "9 (bars 0..=2, height 3)\n");
+}
+
+#[test]
+fn test_csv_bad_rows_abort_with_their_row() {
+    let path = "tests/fixtures/bad_rows.csv";
+    let output = run(&["--csv", path, "--column", "height", "--has-header"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "error: tests/fixtures/bad_rows.csv: line 3: row 3 has an empty cell\n"
+    );
+    let output = run_with_stdin(&["--csv", "-", "--column", "2"], b"1,2\n3,five\n");
+    assert_eq!(
+        stderr(&output),
+        "error: stdin: line 2: row 2: `five` is not a bar height\n"
+    );
+    let output = run_with_stdin(&["--csv", "-", "--column", "2"], b"1,2\n3\n");
+    assert_eq!(
+        stderr(&output),
+        "error: stdin: line 2: row 2 has no column 2\n"
+    );
+}
+
+#[test]
+fn test_csv_bad_rows_can_be_skipped() {
+    let path = "tests/fixtures/bad_rows.csv";
+    let output = run(&[
+        "rect",
+        "--csv",
+        path,
+        "--column",
+        "2",
+        "--has-header",
+        "--skip-invalid",
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "8 (bars 1..=2, height 4)\n");
+}
+
+#[test]
+fn test_csv_quoting_errors_are_located() {
+    let output = run(&["--csv", "tests/fixtures/unterminated.csv", "--column", "2"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "error: tests/fixtures/unterminated.csv: line 1: quoted field has no closing quote\n"
+    );
+    let output = run_with_stdin(&["--csv", "-"], b"1\n\"2\"x\n");
+    assert_eq!(
+        stderr(&output),
+        "error: stdin: line 2: text after a quoted field's closing quote\n"
+    );
+}
+
+#[test]
+fn test_csv_column_must_exist() {
+    let path = "tests/fixtures/measurements.csv";
+    let output = run(&["--csv", path, "--column", "weight", "--has-header"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("no column is named `weight`"));
+}
+
+#[test]
+fn test_csv_options_are_checked() {
+    let path = "tests/fixtures/measurements.csv";
+    for args in [
+        &["--csv", path, "--column", "height"][..],
+        &["--csv", path, "--column", "0"],
+        &["--column", "2", "1", "2"],
+        &["--has-header", "--stdin"],
+        &["--csv"],
+    ] {
+        let output = run(args);
+        assert_eq!(output.status.code(), Some(1), "{args:?}");
+    }
+}
+
+#[test]
+fn test_csv_missing_file_is_an_io_error() {
+    let output = run(&["--csv", "tests/fixtures/no_such_file.csv"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).starts_with("error: tests/fixtures/no_such_file.csv: "));
+}
+
+#[test]
+fn test_render_draws_the_rectangle_before_the_answer() {
+    let output = run(&["render", "2", "1", "5", "6", "2", "3"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        concat!(
+            "   #\n",
+            "  **\n",
+            "  **\n",
+            "  ** #\n",
+            "# **##\n",
+            "##**##\n",
+            "------\n",
+            "0    5\n",
+            "10 (bars 2..=3, height 5)\n",
+        )
+    );
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn test_render_is_not_colored_when_piped() {
+    let plain = run(&["render", "2", "1", "5", "6", "2", "3"]);
+    let no_color = run(&["render", "2", "1", "5", "6", "2", "3", "--no-color"]);
+    assert_eq!(stdout(&plain), stdout(&no_color));
+    assert!(!stdout(&plain).contains('\x1b'));
+}
+
+#[test]
+fn test_render_truncates_to_the_width_limit() {
+    let args: Vec<&str> = "render 1 3 2 4 4 4 1 1 1 1 --width-limit 9"
+        .split(' ')
+        .collect();
+    let output = run(&args);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        concat!(
+            "   ***\n",
+            " # ***\n",
+            " ##***\n",
+            "###***\n",
+            "------...\n",
+            "0    5\n",
+            "12 (bars 3..=5, height 4)\n",
+        )
+    );
+    let output = run(&["render", "1", "3", "2", "--width-limit", "3"]);
+    assert_eq!(
+        stdout(&output),
+        concat!(
+            " #\n",
+            " **\n",
+            "#**\n",
+            "---\n",
+            "0\n",
+            "4 (bars 1..=2, height 2)\n",
+        )
+    );
+}
+
+#[test]
+fn test_render_without_rectangle() {
+    let output = run(&["render", "0", "-2", "0"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "---\n0\n0\n");
+}
+
+#[test]
+fn test_render_wraps_wide_histograms_without_a_limit() {
+    let sequence: String = (0..100).map(|_| "1\n").collect();
+    let output = run_with_stdin(&["render", "--stdin"], sequence.as_bytes());
+    assert!(output.status.success());
+    let lines: Vec<&str> = stdout(&output).lines().collect();
+    assert_eq!(lines.len(), 8);
+    assert_eq!(lines[0], "*".repeat(80));
+    assert_eq!(lines[3], "");
+    assert_eq!(lines[4], "*".repeat(20));
+    assert_eq!(lines[7], "100 (bars 0..=99, height 1)");
+}
+
+#[test]
+fn test_render_options_are_checked() {
+    for args in [
+        &["render", "1", "--width-limit", "0"][..],
+        &["render", "1", "--width-limit", "wide"],
+        &["render", "1", "--width-limit"],
+        &["render", "1", "--json"],
+    ] {
+        let output = run(args);
+        assert_eq!(output.status.code(), Some(1), "{args:?}");
+        assert_eq!(stdout(&output), "");
+    }
+}
+
+#[test]
+fn test_render_option_runs_the_render_command() {
+    let expected = run(&["render", "2", "1", "5", "6", "2", "3", "--no-color"]);
+    for args in [
+        &["--render", "2", "1", "5", "6", "2", "3", "--no-color"][..],
+        &["2", "1", "5", "--render", "6", "2", "3", "--no-color"],
+    ] {
+        let output = run(args);
+        assert!(output.status.success(), "{args:?}");
+        assert_eq!(stdout(&output), stdout(&expected), "{args:?}");
+    }
+    let output = run(&["--render", "1", "--json"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("`--json` does not apply"));
+}
+
+#[test]
+fn test_render_refuses_bars_too_tall_to_draw() {
+    let too_tall =
+        "error: a bar of height 2000000000 is too tall to draw; at most 200 rows can be drawn\n";
+    let output = run(&["render", "2000000000", "1"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(stderr(&output), too_tall);
+    let output = run_with_stdin(&["render", "--stdin"], b"3\n2000000000\n1\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(stderr(&output), too_tall);
+    let output = run(&["render", "200", "1"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output).lines().count(), 203);
+}
+
+#[test]
+fn test_area_is_the_default_command() {
+    for args in [
+        &["2", "1", "5", "6", "2", "3"][..],
+        &["area", "2", "1", "5", "6", "2", "3"],
+    ] {
+        let output = run(args);
+        assert!(output.status.success());
+        assert_eq!(stdout(&output), "10\n");
+    }
+    let output = run_with_stdin(&["area", "--json"], b"{\"bars\": [2, 1, 5, 6, 2, 3]}");
+    assert_eq!(stdout(&output), "{\"area\":10}\n");
+    let output = run(&["area", "--json", "0"]);
+    assert_eq!(stdout(&output), "{\"area\":0}\n");
+}
+
+#[test]
+fn test_each_command_has_its_own_help() {
+    let output = run(&["--help"]);
+    assert!(output.status.success());
+    for command in ["area", "rect", "render", "matrix", "generate"] {
+        assert!(stdout(&output).contains(&format!("\n  {command} ")));
+        let help = run(&[command, "--help"]);
+        assert!(help.status.success());
+        assert!(
+            stdout(&help).starts_with(&format!("Usage: largest-rectangle {command}"))
+                || command == "area"
+                    && stdout(&help).starts_with("Usage: largest-rectangle [area]"),
+            "{}",
+            stdout(&help)
+        );
+    }
+    let output = run(&["generate", "--help"]);
+    assert!(stdout(&output).contains("--pattern NAME"));
+    assert!(!stdout(&output).contains("--csv"));
+}
+
+#[test]
+fn test_unknown_options_show_the_command_help() {
+    let output = run(&["matrix", "--frobnicate"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output)
+        .starts_with("error: unknown option `--frobnicate`\n\nUsage: largest-rectangle matrix"));
+    let output = run(&["generate", "--width", "3", "--stdin"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("unknown argument `--stdin`"));
+    let output = run(&["rect"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("Usage: largest-rectangle rect"));
+}
+
+#[test]
+fn test_matrix_from_arguments_stdin_and_file() {
+    let output = run(&["matrix", "10100", "10111", "11111", "10010"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "6 (rows 1..=2, columns 2..=4)\n");
+    let output = run_with_stdin(&["matrix", "--stdin"], b"\n0 1\n1 1\r\n\n");
+    assert_eq!(stdout(&output), "2 (rows 1..=1, columns 0..=1)\n");
+    let output = run(&["matrix", "--input", "tests/fixtures/grid.txt"]);
+    assert_eq!(stdout(&output), "6 (rows 1..=2, columns 2..=4)\n");
+    let output = run(&["matrix", "000", "000"]);
+    assert_eq!(stdout(&output), "0\n");
+}
+
+#[test]
+fn test_matrix_from_json_and_csv() {
+    let output = run_with_stdin(&["matrix", "--json"], b"[[0, 1, 1], [true, true, true]]");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "{\"area\":4,\"top\":0,\"left\":1,\"bottom\":1,\"right\":2}\n"
+    );
+    let output = run_with_stdin(&["matrix", "--json"], b"{\"rows\": [[0]]}");
+    assert_eq!(
+        stdout(&output),
+        "{\"area\":0,\"top\":null,\"left\":null,\"bottom\":null,\"right\":null}\n"
+    );
+    let output = run(&["matrix", "--csv", "tests/fixtures/grid.csv", "--has-header"]);
+    assert_eq!(stdout(&output), "4 (rows 0..=1, columns 1..=2)\n");
+}
+
+#[test]
+fn test_bad_matrices_are_located() {
+    let output = run(&["matrix", "0110", "01x0"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "error: row 2, column 3: `x` is not a cell, which must be 0 or 1\n"
+    );
+    let output = run_with_stdin(&["matrix", "--stdin"], b"011\n\n01\n");
+    assert_eq!(
+        stderr(&output),
+        "error: stdin: line 3: row has 2 cells, but the first row has 3\n"
+    );
+    let output = run_with_stdin(&["matrix", "--json"], b"[[0, 1], [2, 1]]");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("row 1, column 0 is not a cell"));
+    let output = run_with_stdin(&["matrix", "--csv", "-"], b"0,1\n1,0,1\n");
+    assert_eq!(
+        stderr(&output),
+        "error: stdin: line 2: row 2 has 3 cells, but the first row has 2\n"
+    );
+    let output = run_with_stdin(
+        &["matrix", "--csv", "-", "--skip-invalid"],
+        b"1,1\n1,0,1\n1,1\n",
+    );
+    assert_eq!(stdout(&output), "4 (rows 0..=1, columns 0..=1)\n");
+    let output = run(&[
+        "matrix",
+        "--csv",
+        "tests/fixtures/grid.csv",
+        "--column",
+        "2",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_generate_is_reproducible() {
+    let args = ["generate", "--width", "12", "--max", "9", "--seed", "42"];
+    let first = run(&args);
+    assert!(first.status.success(), "{}", stderr(&first));
+    assert_eq!(stdout(&first), stdout(&run(&args)));
+    let heights: Vec<i32> = stdout(&first)
+        .split_whitespace()
+        .map(|height| height.parse().unwrap())
+        .collect();
+    assert_eq!(heights.len(), 12);
+    assert!(heights.iter().all(|height| (0..=9).contains(height)));
+    let other_seed = run(&["generate", "--width", "12", "--max", "9", "--seed", "43"]);
+    assert_ne!(stdout(&first), stdout(&other_seed));
+}
+
+#[test]
+fn test_generate_patterns_and_json() {
+    let output = run(&[
+        "generate",
+        "--width",
+        "5",
+        "--pattern",
+        "increasing",
+        "--max",
+        "5",
+    ]);
+    assert_eq!(stdout(&output), "1 2 3 4 5\n");
+    let output = run(&["generate", "--width", "3", "--pattern", "spike", "--json"]);
+    assert_eq!(stdout(&output), "[1,100,1]\n");
+    let output = run(&["generate", "--width", "0"]);
+    assert_eq!(stdout(&output), "\n");
+    let output = run(&[
+        "generate",
+        "--width",
+        "4",
+        "--min",
+        "7",
+        "--max",
+        "7",
+        "--distribution",
+        "zipf",
+    ]);
+    assert_eq!(stdout(&output), "7 7 7 7\n");
+}
+
+#[test]
+fn test_generated_heights_read_back() {
+    let generated = run(&["generate", "--width", "30", "--seed", "5", "--json"]);
+    let from_json = run_with_stdin(&["rect", "--json"], &generated.stdout);
+    assert!(from_json.status.success(), "{}", stderr(&from_json));
+    let generated = run(&["generate", "--width", "30", "--seed", "5"]);
+    let from_text = run_with_stdin(&["rect", "--stdin"], &generated.stdout);
+    let area = stdout(&from_text).split(' ').next().unwrap();
+    assert!(stdout(&from_json).starts_with(&format!("{{\"area\":{area},")));
+}
+
+#[test]
+fn test_generate_options_are_checked() {
+    for args in [
+        &["generate"][..],
+        &["generate", "--width", "-1"],
+        &["generate", "--width", "3", "--min", "5", "--max", "4"],
+        &["generate", "--width", "3", "--distribution", "poisson"],
+        &["generate", "--width", "3", "--pattern", "zigzag"],
+        &[
+            "generate",
+            "--width",
+            "3",
+            "--pattern",
+            "spike",
+            "--seed",
+            "1",
+        ],
+        &[
+            "generate",
+            "--width",
+            "3",
+            "--pattern",
+            "spike",
+            "--max",
+            "-1",
+        ],
+        &["generate", "--width"],
+    ] {
+        let output = run(args);
+        assert_eq!(output.status.code(), Some(1), "{args:?}");
+        assert_eq!(stdout(&output), "");
+    }
+}
+
+#[test]
+fn test_batch_answers_each_line_in_order() {
+    let output = run(&["rect", "--batch", "tests/fixtures/batch.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stdout(&output),
+        "10 (bars 2..=3, height 5)\n0\n4 (bars 0..=1, height 2)\n\n3 (bars 0..=2, height 1)\n"
+    );
+    assert_eq!(
+        stderr(&output),
+        "error: tests/fixtures/batch.txt: line 4, column 1: `x` is not a bar height\n\
+         error: tests/fixtures/batch.txt: 1 of 5 lines could not be parsed\n"
+    );
+}
+
+#[test]
+fn test_strict_batch_stops_at_the_first_bad_line() {
+    let output = run(&["--batch", "tests/fixtures/batch.txt", "--strict"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "10\n0\n4\n");
+    assert_eq!(
+        stderr(&output),
+        "error: tests/fixtures/batch.txt: line 4, column 1: `x` is not a bar height\n"
+    );
+}
+
+#[test]
+fn test_batch_of_many_lines_from_stdin() {
+    let lines: String = (1..=2000)
+        .map(|width| format!("{}\n", "3 ".repeat(width % 7)))
+        .collect();
+    let output = run_with_stdin(&["area", "--batch", "-"], lines.as_bytes());
+    assert!(output.status.success(), "{}", stderr(&output));
+    let expected: String = (1..=2000)
+        .map(|width| format!("{}\n", 3 * (width % 7)))
+        .collect();
+    assert_eq!(stdout(&output), expected);
+}
+
+#[test]
+fn test_batch_of_json_lines() {
+    let lines = b"[2,1,5,6,2,3]\n\n{\"bars\": [4, 4]}\r\n[1,\n[7]";
+    let output = run_with_stdin(&["rect", "--batch", "-", "--json"], lines);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stdout(&output),
+        "{\"area\":10,\"left\":2,\"right\":3,\"height\":5}\n\
+         {\"area\":0,\"left\":null,\"right\":null,\"height\":null}\n\
+         {\"area\":8,\"left\":0,\"right\":1,\"height\":4}\n\
+         \n\
+         {\"area\":7,\"left\":0,\"right\":0,\"height\":7}\n"
+    );
+    assert!(stderr(&output).starts_with("{\"error\":\"syntax\",\"source\":\"stdin\",\"line\":4,"));
+}
+
+#[test]
+fn test_batch_options_are_checked() {
+    for args in [
+        &["--batch", "tests/fixtures/batch.txt", "1", "2"][..],
+        &["--batch", "tests/fixtures/batch.txt", "--stdin"],
+        &["--strict", "1", "2"],
+        &["rect", "--batch"],
+    ] {
+        let output = run(args);
+        assert_eq!(output.status.code(), Some(1), "{args:?}");
+    }
+    let output = run(&["--batch", "tests/fixtures/no_such_file.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_output_formats_of_a_single_answer() {
+    let args = ["rect", "2", "1", "5", "6", "2", "3", "--output-format"];
+    for (format, expected) in [
+        ("plain", "10 (bars 2..=3, height 5)\n"),
+        (
+            "json",
+            "{\"area\":10,\"left\":2,\"right\":3,\"height\":5}\n",
+        ),
+        ("csv", "area,left,right,height\n10,2,3,5\n"),
+        ("tsv", "area\tleft\tright\theight\n10\t2\t3\t5\n"),
+    ] {
+        let output = run(&[&args[..], &[format]].concat());
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert_eq!(stdout(&output), expected, "{format}");
+    }
+    let output = run(&["area", "--output-format", "csv", "2", "4"]);
+    assert_eq!(stdout(&output), "area\n4\n");
+    let output = run(&["matrix", "--output-format", "tsv", "10", "11"]);
+    assert_eq!(
+        stdout(&output),
+        "area\ttop\tleft\tbottom\tright\n2\t0\t0\t1\t0\n"
+    );
+}
+
+#[test]
+fn test_output_format_overrides_json_input() {
+    let output = run_with_stdin(&["area", "--json", "--output-format", "plain"], b"[2, 4]");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "4\n");
+}
+
+#[test]
+fn test_batch_in_csv_has_a_single_header() {
+    let output = run(&[
+        "rect",
+        "--batch",
+        "tests/fixtures/batch.txt",
+        "--output-format",
+        "csv",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stdout(&output),
+        "area,left,right,height\n10,2,3,5\n0,,,\n4,0,1,2\n,,,\n3,0,2,1\n"
+    );
+
+    let lines = "1 2\n".repeat(5000);
+    let output = run_with_stdin(
+        &["area", "--batch", "-", "--output-format", "tsv"],
+        lines.as_bytes(),
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), format!("area\n{}", "2\n".repeat(5000)));
+}
+
+#[test]
+fn test_output_format_is_checked() {
+    let output = run(&["area", "1", "--output-format", "xml"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output)
+        .starts_with("error: `xml` is not an output format; use plain, json, csv or tsv\n"));
+    let output = run(&["area", "1", "--output-format"]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = run(&["render", "--no-color", "2", "1", "--output-format", "plain"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let output = run(&["render", "2", "1", "--output-format", "csv"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with(
+        "error: a drawing can only be printed as plain text, so `--output-format` must be plain\n"
+    ));
+}
+
+#[test]
+fn test_closed_stdout_is_not_a_failure() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_largest-rectangle"))
+        .args(["generate", "--width", "200000"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    drop(child.stdout.take());
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_arguments_must_be_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_largest-rectangle"))
+        .args([OsStr::new("rect"), OsStr::from_bytes(b"2\xff")])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("error: `2\u{fffd}` is not valid UTF-8\n"));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn test_generating_more_bars_than_fit_in_memory() {
+    let output = run(&["generate", "--width", "18446744073709551615"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output)
+        .starts_with("error: `--width` 18446744073709551615 is more bars than fit in memory\n"));
+}
+
+#[test]
+fn test_parse_errors_name_the_token() {
+    let output = run_with_stdin(&["area", "--batch", "-", "--strict"], b"1 2\n3 y 4\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "error: stdin: line 2, column 3: `y` is not a bar height\n"
+    );
+    let output = run(&["2", "x", "--json"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "{\"error\":\"parse\",\"token\":\"x\",\
+         \"message\":\"`x` is not a bar height: invalid digit found in string\"}\n"
+    );
+    let output = run_with_stdin(&["matrix", "--stdin"], b"10\n1z\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "error: stdin: line 2, column 2: `z` is not a cell, which must be 0 or 1\n"
+    );
+}
+
+/// Runs the tool on a few hundred argument lists drawn from options, values
+/// and junk, none of which may make it panic or print an error to stdout.
+#[test]
+fn test_fuzzed_arguments_never_panic() {
+    const WORDS: &[&str] = &[
+        "area",
+        "rect",
+        "render",
+        "matrix",
+        "generate",
+        "--stdin",
+        "--input",
+        "--csv",
+        "--column",
+        "--has-header",
+        "--skip-invalid",
+        "--json",
+        "--batch",
+        "--strict",
+        "--output-format",
+        "csv",
+        "json",
+        "tsv",
+        "plain",
+        "--no-color",
+        "--width-limit",
+        "--width",
+        "--min",
+        "--max",
+        "--seed",
+        "--pattern",
+        "spike",
+        "--distribution",
+        "zipf",
+        "-",
+        "--",
+        "-h",
+        "0",
+        "1",
+        "-1",
+        "3",
+        "10",
+        "101",
+        "-2147483648",
+        "99999999999",
+        "18446744073709551615",
+        "",
+        " ",
+        "x",
+        "é",
+        "1e3",
+        "0x10",
+        "tests/fixtures/bars.txt",
+        "tests/fixtures/measurements.csv",
+        "tests/fixtures/grid.txt",
+        "tests/fixtures/batch.txt",
+        "tests/fixtures/missing",
+    ];
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = |bound: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % bound as u64) as usize
+    };
+    for _ in 0..300 {
+        let count = next(7);
+        let args: Vec<&str> = (0..count).map(|_| WORDS[next(WORDS.len())]).collect();
+        let output = run(&args);
+        let code = output.status.code();
+        assert!(
+            matches!(code, Some(0..=3)),
+            "{args:?} exited with {code:?}: {}",
+            stderr(&output)
+        );
+        assert!(!stderr(&output).contains("panicked"), "{args:?}");
+        if code == Some(1) || code == Some(3) {
+            assert_eq!(stdout(&output), "", "{args:?}");
+        }
+    }
+}
+
+#[test]
+fn test_repl_prints_the_answer_after_every_change() {
+    let script = b"set 2 1 5 6 2 3\npush 4\nheight 1 4\narea\nrect\npop\nundo\nundo\nundo\nundo\n";
+    let output = run_with_stdin(&["repl"], script);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "\
+10 (bars 2..=3, height 5)
+10 (bars 2..=3, height 5)
+14 (bars 0..=6, height 2)
+14
+14 (bars 0..=6, height 2)
+12 (bars 0..=5, height 2)
+14 (bars 0..=6, height 2)
+10 (bars 2..=3, height 5)
+10 (bars 2..=3, height 5)
+0
+"
+    );
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn test_repl_reports_mistakes_and_keeps_going() {
+    let script = b"pop\nundo\nfrobnicate\npush\npush tall\nset 3 x\nheight 4 1\n\nset 3 3\nrect\n";
+    let output = run_with_stdin(&["repl"], script);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "6 (bars 0..=1, height 3)\n6 (bars 0..=1, height 3)\n"
+    );
+    assert_eq!(
+        stderr(&output),
+        "\
+error: there is no bar to pop
+error: there is nothing to undo
+error: `frobnicate` is not a command; `help` lists them
+error: `push` has the wrong arguments; use `push HEIGHT`
+error: `tall` is not a bar height: invalid digit found in string
+error: `x` is not a bar height: invalid digit found in string
+error: bar 4 is out of range for a histogram of width 0
+"
+    );
+}
+
+#[test]
+fn test_repl_renders_and_stops_at_quit() {
+    let output = run_with_stdin(&["repl"], b"set 1 2\nrender\nquit\npush 5\n");
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "2 (bars 0..=1, height 1)\n #\n**\n--\n0\n2 (bars 0..=1, height 1)\n"
+    );
+}
+
+#[test]
+fn test_repl_refuses_to_render_bars_too_tall_to_draw() {
+    let output = run_with_stdin(&["repl"], b"set 2000000000 1\nrender\narea\n");
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "2000000000 (bars 0..=0, height 2000000000)\n2000000000\n"
+    );
+    assert_eq!(
+        stderr(&output),
+        "error: a bar of height 2000000000 is too tall to draw; at most 200 rows can be drawn\n"
+    );
+}
+
+#[test]
+fn test_repl_takes_no_arguments() {
+    let output = run(&["repl", "2", "1"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("error: unknown argument `2`"));
+    let output = run(&["repl", "--help"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Usage: largest-rectangle repl"));
+}
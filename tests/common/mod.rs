@@ -0,0 +1,51 @@
+#![allow(dead_code)]
+
+/// Every histogram of up to `max_width` bars with heights in `0..=max_height`.
+pub fn all_histograms(max_width: usize, max_height: i32) -> Vec<Vec<i32>> {
+    let mut histograms = vec![vec![]];
+    let mut previous_width = vec![vec![]];
+    for _ in 0..max_width {
+        previous_width = previous_width
+            .iter()
+            .flat_map(|bars: &Vec<i32>| {
+                (0..=max_height).map(move |height| {
+                    let mut longer = bars.clone();
+                    longer.push(height);
+                    longer
+                })
+            })
+            .collect();
+        histograms.extend(previous_width.iter().cloned());
+    }
+    histograms
+}
+
+/// A small deterministic xorshift generator, so randomized tests are
+/// reproducible without extra dependencies.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A value in `0..bound`.
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Bars with a random width up to `max_width` and heights in `0..=max_height`.
+    pub fn bars(&mut self, max_width: usize, max_height: i32) -> Vec<i32> {
+        let width = self.below(max_width + 1);
+        (0..width)
+            .map(|_| self.below(max_height as usize + 1) as i32)
+            .collect()
+    }
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::{all_histograms, Rng};
+use leetcode_largest_rectangle::histogram_concrete::OutOfRange;
+use leetcode_largest_rectangle::square_search;
+use leetcode_largest_rectangle::{ConcreteHistogram, Rectangle};
+
+fn brute_force_area_containing(bars: &[i32], x_pos: usize) -> u64 {
+    let mut best = 0;
+    for left in 0..=x_pos {
+        for right in x_pos..bars.len() {
+            let height = bars[left..=right].iter().min().unwrap().max(&0);
+            best = best.max(*height as u64 * (right - left + 1) as u64);
+        }
+    }
+    best
+}
+
+#[test]
+fn test_wider_rectangle_beats_the_bar_it_contains() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::largest_rectangle_containing(&histogram, 5),
+        Ok(Some(Rectangle {
+            left: 2,
+            right: 5,
+            height: 2,
+            area: 8
+        }))
+    );
+    assert_eq!(
+        square_search::largest_rectangle_containing(&histogram, 3),
+        Ok(Some(Rectangle {
+            left: 2,
+            right: 3,
+            height: 5,
+            area: 10
+        }))
+    );
+}
+
+#[test]
+fn test_zero_bar_is_in_no_rectangle() {
+    let histogram = ConcreteHistogram::new(vec![4, 0, 4]);
+    assert_eq!(
+        square_search::largest_rectangle_containing(&histogram, 1),
+        Ok(None)
+    );
+}
+
+#[test]
+fn test_out_of_range_column_is_an_error() {
+    let histogram = ConcreteHistogram::new(vec![1, 2]);
+    assert_eq!(
+        square_search::largest_rectangle_containing(&histogram, 2),
+        Err(OutOfRange {
+            horizontal_position: 2,
+            width: 2
+        })
+    );
+    let empty = ConcreteHistogram::new(vec![]);
+    assert!(square_search::largest_rectangle_containing(&empty, 0).is_err());
+}
+
+#[test]
+fn test_matches_brute_force() {
+    let mut rng = Rng::new(76);
+    let random = (0..300).map(|_| {
+        let mut bars = rng.bars(40, 30);
+        for bar in bars.iter_mut().step_by(9) {
+            *bar = -*bar;
+        }
+        bars
+    });
+    for bars in all_histograms(5, 3)
+        .into_iter()
+        .chain(random.collect::<Vec<_>>())
+    {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        for x_pos in 0..bars.len() {
+            let found = square_search::largest_rectangle_containing(&histogram, x_pos).unwrap();
+            assert_eq!(
+                found.map_or(0, |rectangle| rectangle.area),
+                brute_force_area_containing(&bars, x_pos),
+                "{bars:?} at {x_pos}"
+            );
+            if let Some(rectangle) = found {
+                assert!(rectangle.left <= x_pos && x_pos <= rectangle.right);
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::{all_histograms, Rng};
+use leetcode_largest_rectangle::square_search;
+use leetcode_largest_rectangle::{ConcreteHistogram, Histogram, Rectangle2D};
+
+/// Checks that every unit cell under the bars is covered by exactly one
+/// rectangle, and nothing above them is covered at all.
+fn assert_tiles_exactly(bars: &[i32], rectangles: &[Rectangle2D]) {
+    for rectangle in rectangles {
+        assert!(rectangle.bottom < rectangle.top, "{bars:?}: {rectangle:?}");
+        assert_eq!(
+            rectangle.area,
+            (rectangle.top - rectangle.bottom) as u64 * rectangle.width() as u64
+        );
+    }
+    for (x_pos, &bar) in bars.iter().enumerate() {
+        let tallest = rectangles.iter().map(|rectangle| rectangle.top).max();
+        for y in 0..tallest.unwrap_or(0).max(bar) {
+            let covering = rectangles
+                .iter()
+                .filter(|rectangle| {
+                    (rectangle.left..=rectangle.right).contains(&x_pos)
+                        && (rectangle.bottom..rectangle.top).contains(&y)
+                })
+                .count();
+            let expected = usize::from(y < bar);
+            assert_eq!(covering, expected, "{bars:?}: cell ({x_pos}, {y})");
+        }
+    }
+}
+
+/// Counts the bars that start a new level: those with no bar of the same
+/// height before them that nothing lower separates them from.
+fn minimum_rectangle_count(bars: &[i32]) -> usize {
+    (0..bars.len())
+        .filter(|&x_pos| {
+            let height = bars[x_pos];
+            height > 0
+                && !(0..x_pos).any(|before| {
+                    bars[before] == height && bars[before..x_pos].iter().all(|&bar| bar >= height)
+                })
+        })
+        .count()
+}
+
+#[test]
+fn test_hand_computed_counts() {
+    for (bars, count) in [
+        (vec![2, 1, 2], 3),
+        (vec![1, 2, 3], 3),
+        (vec![3, 3, 3], 1),
+        (vec![2, 3, 2], 2),
+        (vec![], 0),
+        (vec![0, 0], 0),
+    ] {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let rectangles = square_search::decompose_into_rectangles(&histogram);
+        assert_eq!(rectangles.len(), count, "{bars:?}");
+        assert_tiles_exactly(&bars, &rectangles);
+    }
+}
+
+#[test]
+fn test_union_area_is_total_area() {
+    let mut rng = Rng::new(74);
+    for _ in 0..300 {
+        let bars = rng.bars(50, 1000);
+        let histogram = ConcreteHistogram::new(bars);
+        let rectangles = square_search::decompose_into_rectangles(&histogram);
+        let union_area: u64 = rectangles.iter().map(|rectangle| rectangle.area).sum();
+        assert_eq!(union_area as i64, histogram.total_area());
+    }
+}
+
+#[test]
+fn test_decompositions_are_exact_and_minimal() {
+    for bars in all_histograms(6, 3) {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let rectangles = square_search::decompose_into_rectangles(&histogram);
+        assert_tiles_exactly(&bars, &rectangles);
+        assert_eq!(rectangles.len(), minimum_rectangle_count(&bars), "{bars:?}");
+    }
+}
+
+#[test]
+fn test_negative_bars_are_left_uncovered() {
+    let bars = vec![2, -3, 2];
+    let histogram = ConcreteHistogram::new(bars);
+    let rectangles = square_search::decompose_into_rectangles(&histogram);
+    assert_eq!(rectangles.len(), 2);
+    assert_tiles_exactly(&[2, 0, 2], &rectangles);
+}
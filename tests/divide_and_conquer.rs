@@ -0,0 +1,75 @@
+mod common;
+
+use common::{all_histograms, Rng};
+use leetcode_largest_rectangle::square_search::{self, compute_area_divide_and_conquer, Algorithm};
+use leetcode_largest_rectangle::ConcreteHistogram;
+
+#[test]
+fn test_divide_and_conquer_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(compute_area_divide_and_conquer(&histogram), 10);
+    assert_eq!(
+        square_search::compute_with(&histogram, Algorithm::DivideAndConquer),
+        10
+    );
+    assert_eq!(
+        square_search::compute_with(&histogram, Algorithm::default()),
+        10
+    );
+}
+
+#[test]
+fn test_divide_and_conquer_empty_and_negative() {
+    assert_eq!(
+        compute_area_divide_and_conquer(&ConcreteHistogram::new(vec![])),
+        0
+    );
+    assert_eq!(
+        compute_area_divide_and_conquer(&ConcreteHistogram::new(vec![-3, 4, -1, 2, 2])),
+        4
+    );
+}
+
+#[test]
+fn test_algorithms_agree_on_every_small_histogram() {
+    for bars in all_histograms(7, 3) {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            square_search::compute_with(&histogram, Algorithm::DivideAndConquer),
+            square_search::compute_with(&histogram, Algorithm::MonotonicStack),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_algorithms_agree_on_random_histograms() {
+    let mut rng = Rng::new(51);
+    for _ in 0..5000 {
+        let bars = rng.bars(60, 40);
+        let histogram = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            compute_area_divide_and_conquer(&histogram),
+            square_search::compute_area_of_largest_rectangle(&histogram),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_divide_and_conquer_wider_heights() {
+    let histogram = ConcreteHistogram::from_bars(vec![3_000_000_000_i64, 3_000_000_000, 1]);
+    assert_eq!(
+        compute_area_divide_and_conquer(&histogram),
+        6_000_000_000_u128
+    );
+}
+
+#[test]
+fn test_sorted_bars_do_not_overflow_the_stack() {
+    let increasing = ConcreteHistogram::new((1..=1_000_000).collect());
+    let decreasing = ConcreteHistogram::new((1..=1_000_000).rev().collect());
+    let expected = 500_000 * 500_001;
+    assert_eq!(compute_area_divide_and_conquer(&increasing), expected);
+    assert_eq!(compute_area_divide_and_conquer(&decreasing), expected);
+}
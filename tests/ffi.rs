@@ -0,0 +1,124 @@
+#![cfg(feature = "ffi")]
+
+use leetcode_largest_rectangle::ffi::*;
+use std::ptr;
+
+const LEETCODE: [i32; 6] = [2, 1, 5, 6, 2, 3];
+
+#[test]
+fn test_area() {
+    let mut area = -1;
+    let status =
+        unsafe { lrh_largest_rectangle_area(LEETCODE.as_ptr(), LEETCODE.len(), &mut area) };
+    assert_eq!(status, LRH_OK);
+    assert_eq!(area, 10);
+}
+
+#[test]
+fn test_rectangle() {
+    let mut rect = LrhRect::default();
+    let status = unsafe { lrh_largest_rectangle(LEETCODE.as_ptr(), LEETCODE.len(), &mut rect) };
+    assert_eq!(status, LRH_OK);
+    assert_eq!(
+        rect,
+        LrhRect {
+            left: 2,
+            right: 3,
+            height: 5,
+            area: 10,
+        }
+    );
+}
+
+#[test]
+fn test_no_rectangle() {
+    let bars = [0, -3, 0];
+    let mut rect = LrhRect {
+        left: 9,
+        right: 9,
+        height: 9,
+        area: 9,
+    };
+    let status = unsafe { lrh_largest_rectangle(bars.as_ptr(), bars.len(), &mut rect) };
+    assert_eq!(status, LRH_NO_RECTANGLE);
+    assert_eq!(rect, LrhRect::default());
+
+    let mut area = -1;
+    let status = unsafe { lrh_largest_rectangle_area(bars.as_ptr(), bars.len(), &mut area) };
+    assert_eq!(status, LRH_NO_RECTANGLE);
+    assert_eq!(area, 0);
+}
+
+#[test]
+fn test_no_bars_may_be_null() {
+    let mut area = -1;
+    assert_eq!(
+        unsafe { lrh_largest_rectangle_area(ptr::null(), 0, &mut area) },
+        LRH_NO_RECTANGLE
+    );
+    assert_eq!(area, 0);
+    let mut rect = LrhRect::default();
+    assert_eq!(
+        unsafe { lrh_largest_rectangle(ptr::null(), 0, &mut rect) },
+        LRH_NO_RECTANGLE
+    );
+}
+
+#[test]
+fn test_null_pointers_leave_the_output_untouched() {
+    let mut area = -1;
+    assert_eq!(
+        unsafe { lrh_largest_rectangle_area(ptr::null(), 3, &mut area) },
+        LRH_NULL_POINTER
+    );
+    assert_eq!(area, -1);
+    assert_eq!(
+        unsafe { lrh_largest_rectangle_area(LEETCODE.as_ptr(), LEETCODE.len(), ptr::null_mut()) },
+        LRH_NULL_POINTER
+    );
+    assert_eq!(
+        unsafe { lrh_largest_rectangle(LEETCODE.as_ptr(), LEETCODE.len(), ptr::null_mut()) },
+        LRH_NULL_POINTER
+    );
+    let mut rect = LrhRect::default();
+    assert_eq!(
+        unsafe { lrh_largest_rectangle(ptr::null(), 1, &mut rect) },
+        LRH_NULL_POINTER
+    );
+}
+
+#[test]
+fn test_unaddressable_length() {
+    let mut area = -1;
+    let status = unsafe { lrh_largest_rectangle_area(LEETCODE.as_ptr(), usize::MAX, &mut area) };
+    assert_eq!(status, LRH_INVALID_LENGTH);
+    assert_eq!(area, -1);
+}
+
+/// The header is written by hand, so its statuses are checked against the
+/// library's.
+#[test]
+fn test_header_matches_the_library() {
+    let header = include_str!("../include/lrh.h");
+    for (name, value) in [
+        ("LRH_OK", LRH_OK),
+        ("LRH_NO_RECTANGLE", LRH_NO_RECTANGLE),
+        ("LRH_NULL_POINTER", LRH_NULL_POINTER),
+        ("LRH_INVALID_LENGTH", LRH_INVALID_LENGTH),
+        ("LRH_OVERFLOW", LRH_OVERFLOW),
+        ("LRH_PANIC", LRH_PANIC),
+    ] {
+        let expected = if value < 0 {
+            format!("#define {name} ({value})\n")
+        } else {
+            format!("#define {name} {value}\n")
+        };
+        assert!(header.contains(&expected), "{expected}");
+    }
+    for declaration in [
+        "int32_t lrh_largest_rectangle_area(const int32_t *bars, size_t len, int64_t *out_area);",
+        "int32_t lrh_largest_rectangle(const int32_t *bars, size_t len, LrhRect *out_rect);",
+    ] {
+        assert!(header.contains(declaration), "{declaration}");
+    }
+}
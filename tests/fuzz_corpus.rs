@@ -0,0 +1,59 @@
+//! Replays the fuzz targets' regression corpus, so the cases there are
+//! checked by `cargo test` too, without a fuzzer installed.
+
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::generate;
+use leetcode_largest_rectangle::parse::{csv::CsvReader, json, plain};
+use leetcode_largest_rectangle::square_search::{self, naive};
+use std::fs;
+
+#[test]
+fn test_differential_corpus() {
+    let mut replayed = 0;
+    for entry in fs::read_dir("fuzz/corpus/differential").unwrap() {
+        let bytes = fs::read(entry.unwrap().path()).unwrap();
+        let histogram = generate::from_bytes(&bytes);
+        assert_eq!(
+            square_search::compute_area_of_largest_rectangle(&histogram),
+            naive::compute_area_of_largest_rectangle(&histogram),
+            "bars {:?}",
+            histogram.heights()
+        );
+        replayed += 1;
+    }
+    assert!(replayed >= 3);
+}
+
+#[test]
+fn test_corpus_exercises_plateaus() {
+    let bytes = fs::read("fuzz/corpus/differential/plateaus-around-a-valley").unwrap();
+    assert_eq!(
+        generate::from_bytes(&bytes).heights(),
+        [5, 5, 5, 5, 2, 2, 2, 2, 5, 5, 5, 5]
+    );
+}
+
+#[test]
+fn test_parsers_survive_noise() {
+    let mut rng = Rng::new(98);
+    const ALPHABET: &[u8] = b"0123456789-+ ,\"\r\n\t[]{}:.eE\\truefalsnbars\xff\xc3";
+    for _ in 0..2000 {
+        let len = rng.below(40);
+        let bytes: Vec<u8> = (0..len)
+            .map(|_| ALPHABET[rng.below(ALPHABET.len())])
+            .collect();
+        let _ = plain::read_heights(&bytes[..]);
+        let _ = plain::read_rows(&bytes[..]);
+        if let Ok(value) = json::parse(&bytes) {
+            let _ = json::bars(value);
+        }
+        if let Ok(value) = json::parse(&bytes) {
+            let _ = json::rows(value);
+        }
+        for record in CsvReader::new(&bytes[..]) {
+            let _ = record;
+        }
+    }
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::square_search::{self, compute_largest_rectangle_gapped};
+use leetcode_largest_rectangle::{ConcreteHistogram, GappedHistogram, Histogram, Rectangle};
+
+#[test]
+fn test_rectangle_does_not_span_gap() {
+    let histogram = GappedHistogram::new(vec![Some(3), None, Some(3)]);
+    assert_eq!(
+        compute_largest_rectangle_gapped(&histogram),
+        Some(Rectangle {
+            left: 0,
+            right: 0,
+            height: 3,
+            area: 3
+        })
+    );
+}
+
+#[test]
+fn test_all_gaps_give_no_rectangle() {
+    let histogram = GappedHistogram::new(vec![None, None, None]);
+    assert_eq!(compute_largest_rectangle_gapped(&histogram), None);
+    assert_eq!(
+        compute_largest_rectangle_gapped(&GappedHistogram::new(vec![])),
+        None
+    );
+}
+
+#[test]
+fn test_positions_are_those_of_the_whole_histogram() {
+    let histogram = GappedHistogram::new(vec![
+        Some(9),
+        None,
+        Some(2),
+        Some(1),
+        Some(5),
+        Some(6),
+        Some(2),
+        Some(3),
+        None,
+    ]);
+    assert_eq!(
+        compute_largest_rectangle_gapped(&histogram),
+        Some(Rectangle {
+            left: 4,
+            right: 5,
+            height: 5,
+            area: 10
+        })
+    );
+}
+
+#[test]
+fn test_gaps_and_segments() {
+    let histogram = GappedHistogram::new(vec![None, Some(1), Some(2), None, None, Some(3)]);
+    assert_eq!(histogram.width(), 6);
+    assert_eq!(histogram.height_at(2), Some(2));
+    assert_eq!(histogram.gaps().collect::<Vec<_>>(), vec![0, 3, 4]);
+    assert_eq!(histogram.segments().collect::<Vec<_>>(), vec![1..3, 5..6]);
+}
+
+#[test]
+fn test_without_gaps_matches_concrete_histogram() {
+    let mut rng = Rng::new(28);
+    for _ in 0..300 {
+        let bars = rng.bars(20, 6);
+        let gapped = GappedHistogram::new(bars.iter().copied().map(Some).collect());
+        assert_eq!(
+            compute_largest_rectangle_gapped(&gapped),
+            square_search::compute_largest_rectangle(&ConcreteHistogram::new(bars))
+        );
+    }
+}
+
+#[test]
+fn test_gaps_match_search_per_segment() {
+    let mut rng = Rng::new(2828);
+    for _ in 0..300 {
+        let bars: Vec<Option<i32>> = (0..rng.below(20))
+            .map(|_| Some(rng.below(6) as i32).filter(|_| rng.below(4) > 0))
+            .collect();
+        let histogram = GappedHistogram::new(bars.clone());
+        let expected = histogram
+            .segments()
+            .filter_map(|segment| {
+                let heights = bars[segment].iter().map(|bar| bar.unwrap()).collect();
+                square_search::compute_largest_rectangle(&ConcreteHistogram::new(heights))
+            })
+            .map(|rectangle| rectangle.area)
+            .max();
+        assert_eq!(
+            compute_largest_rectangle_gapped(&histogram).map(|rectangle| rectangle.area),
+            expected,
+            "{bars:?}"
+        );
+    }
+}
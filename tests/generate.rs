@@ -0,0 +1,254 @@
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::generate::{self, Distribution, Pattern, RandomHistogram};
+use leetcode_largest_rectangle::Histogram;
+
+const DISTRIBUTIONS: [Distribution; 3] = [
+    Distribution::Uniform,
+    Distribution::Zipf,
+    Distribution::Gaussian,
+];
+
+fn heights<H: Histogram>(histogram: &H) -> Vec<i32> {
+    histogram.iter_heights().collect()
+}
+
+#[test]
+fn test_same_seed_gives_same_histogram() {
+    for distribution in DISTRIBUTIONS {
+        let mut builder = RandomHistogram::builder();
+        builder
+            .width(1000)
+            .height_range(0..=50)
+            .distribution(distribution)
+            .seed(62);
+        assert_eq!(heights(&builder.build()), heights(&builder.build()));
+    }
+}
+
+#[test]
+fn test_different_seeds_give_different_histograms() {
+    for distribution in DISTRIBUTIONS {
+        let first = RandomHistogram::builder()
+            .width(1000)
+            .distribution(distribution)
+            .seed(1)
+            .build();
+        let second = RandomHistogram::builder()
+            .width(1000)
+            .distribution(distribution)
+            .seed(2)
+            .build();
+        assert_ne!(heights(&first), heights(&second));
+    }
+}
+
+#[test]
+fn test_heights_respect_range() {
+    for distribution in DISTRIBUTIONS {
+        for range in [0..=0, 0..=1, -5..=5, 10..=1000, i32::MIN..=i32::MAX] {
+            let histogram = RandomHistogram::builder()
+                .width(2000)
+                .height_range(range.clone())
+                .distribution(distribution)
+                .seed(7)
+                .build();
+            assert_eq!(histogram.width(), 2000);
+            assert!(
+                histogram
+                    .iter_heights()
+                    .all(|height| range.contains(&height)),
+                "{distribution:?} left {range:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_uniform_reaches_both_ends_of_range() {
+    let histogram = RandomHistogram::builder()
+        .width(1000)
+        .height_range(3..=6)
+        .seed(3)
+        .build();
+    assert_eq!(histogram.min_height(), Some(3));
+    assert_eq!(histogram.max_height(), Some(6));
+}
+
+#[test]
+fn test_zipf_favours_low_heights() {
+    let histogram = RandomHistogram::builder()
+        .width(10_000)
+        .height_range(0..=99)
+        .distribution(Distribution::Zipf)
+        .seed(4)
+        .build();
+    let lowest = histogram
+        .iter_heights()
+        .filter(|&height| height == 0)
+        .count();
+    let highest = histogram
+        .iter_heights()
+        .filter(|&height| height == 99)
+        .count();
+    assert!(lowest > 10 * highest.max(1), "{lowest} vs {highest}");
+}
+
+#[test]
+fn test_gaussian_clusters_around_middle() {
+    let histogram = RandomHistogram::builder()
+        .width(10_000)
+        .height_range(0..=600)
+        .distribution(Distribution::Gaussian)
+        .seed(5)
+        .build();
+    let within_one_deviation = histogram
+        .iter_heights()
+        .filter(|height| (200..=400).contains(height))
+        .count();
+    // About 68% of a normal distribution lies within one standard deviation.
+    assert!((6_300..7_300).contains(&within_one_deviation));
+}
+
+#[test]
+fn test_default_builder_gives_empty_histogram() {
+    assert!(RandomHistogram::builder().build().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "is empty")]
+fn test_empty_height_range_panics() {
+    #[allow(clippy::reversed_empty_ranges)]
+    RandomHistogram::builder().height_range(5..=4).build();
+}
+
+#[test]
+fn test_patterns_have_expected_shapes() {
+    let shape = |pattern| heights(&generate::pattern(pattern, 8, 8));
+    assert_eq!(shape(Pattern::Increasing), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(shape(Pattern::Decreasing), vec![8, 7, 6, 5, 4, 3, 2, 1]);
+    assert_eq!(shape(Pattern::Spike), vec![1, 1, 1, 1, 8, 1, 1, 1]);
+    assert_eq!(shape(Pattern::OrganPipe), vec![2, 4, 6, 8, 8, 6, 4, 2]);
+    assert_eq!(
+        heights(&generate::pattern(Pattern::Sawtooth, 8, 3)),
+        vec![1, 2, 3, 1, 2, 3, 1, 2]
+    );
+    assert_eq!(
+        heights(&generate::pattern(Pattern::Plateaus, 64, 8))
+            .chunks(4)
+            .map(|run| run[0])
+            .collect::<Vec<_>>(),
+        vec![8, 2, 6, 4, 8, 2, 6, 4, 8, 2, 6, 4, 8, 2, 6, 4]
+    );
+}
+
+#[test]
+fn test_patterns_respect_width_and_max_height() {
+    for pattern in Pattern::ALL {
+        for width in [0, 1, 2, 5, 100, 1001] {
+            for max_height in [0, 1, 7, i32::MAX] {
+                let histogram = generate::pattern(pattern, width, max_height);
+                assert_eq!(histogram.width(), width);
+                assert!(histogram
+                    .iter_heights()
+                    .all(|height| (0..=max_height).contains(&height)));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_increasing_pattern_is_strict_when_heights_allow() {
+    let bars = heights(&generate::pattern(Pattern::Increasing, 1000, 5000));
+    assert!(bars.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+fn fixed_byte_strings() -> Vec<Vec<u8>> {
+    let mut rng = Rng::new(96);
+    let mut strings = vec![Vec::new(), vec![0xff; 3], vec![0; 64], (0..=255).collect()];
+    strings.extend((0..400).map(|_| {
+        let len = rng.below(300);
+        (0..len).map(|_| rng.next_u64() as u8).collect()
+    }));
+    strings
+}
+
+#[test]
+fn test_histograms_from_bytes_stay_in_bounds() {
+    for bytes in fixed_byte_strings() {
+        let histogram = generate::from_bytes(&bytes);
+        assert!(histogram.width() <= generate::MAX_BYTES_WIDTH);
+        assert_eq!(histogram.iter_heights().count(), histogram.width());
+        assert!(histogram
+            .iter_heights()
+            .all(|height| (0..=generate::MAX_BYTES_HEIGHT).contains(&height)));
+        assert_eq!(heights(&generate::from_bytes(&bytes)), heights(&histogram));
+    }
+    assert_eq!(generate::from_bytes(&[]).width(), 0);
+}
+
+#[test]
+fn test_histograms_from_bytes_favour_structure() {
+    let (mut plateaus, mut zeros, mut rises, mut falls) = (0, 0, 0, 0);
+    for bytes in fixed_byte_strings() {
+        let heights = heights(&generate::from_bytes(&bytes));
+        for pair in heights.windows(2) {
+            match pair[0].cmp(&pair[1]) {
+                std::cmp::Ordering::Equal if pair[0] == 0 => zeros += 1,
+                std::cmp::Ordering::Equal => plateaus += 1,
+                std::cmp::Ordering::Less if pair[1] - pair[0] == 1 => rises += 1,
+                std::cmp::Ordering::Greater if pair[0] - pair[1] == 1 => falls += 1,
+                _ => {}
+            }
+        }
+    }
+    // Noise would almost never repeat a height or step by exactly one.
+    for (shape, count) in [
+        ("plateau", plateaus),
+        ("zero", zeros),
+        ("rise", rises),
+        ("fall", falls),
+    ] {
+        assert!(count > 1000, "{shape}: {count}");
+    }
+}
+
+#[test]
+fn test_matrices_from_bytes_stay_in_bounds() {
+    for bytes in fixed_byte_strings() {
+        let matrix = generate::matrix_from_bytes(&bytes);
+        assert!(matrix.rows() <= generate::MAX_BYTES_SIDE);
+        assert!(matrix.columns() <= generate::MAX_BYTES_SIDE);
+        assert_eq!(generate::matrix_from_bytes(&bytes), matrix, "{bytes:?}");
+    }
+}
+
+#[test]
+fn test_options_from_bytes_stay_in_bounds() {
+    let (mut set, mut unset) = (0, 0);
+    for bytes in fixed_byte_strings() {
+        let options = generate::options_from_bytes(&bytes);
+        let widths = [options.min_width, options.max_width];
+        let heights = [options.min_height, options.max_height];
+        assert!(widths
+            .iter()
+            .flatten()
+            .all(|width| (1..=generate::MAX_BYTES_WIDTH).contains(width)));
+        assert!(heights
+            .iter()
+            .flatten()
+            .all(|height| (0..=generate::MAX_BYTES_HEIGHT).contains(height)));
+        if let [Some(min), Some(max)] = widths {
+            assert!(min <= max, "{options:?}");
+        }
+        if let [Some(min), Some(max)] = heights {
+            assert!(min <= max, "{options:?}");
+        }
+        assert_eq!(generate::options_from_bytes(&bytes), options);
+        set += widths.iter().flatten().count() + heights.iter().flatten().count();
+        unset += widths.iter().filter(|width| width.is_none()).count()
+            + heights.iter().filter(|height| height.is_none()).count();
+    }
+    assert!(set > 400 && unset > 400, "set {set}, unset {unset}");
+}
@@ -0,0 +1,171 @@
+mod common;
+
+use common::{all_histograms, Rng};
+use leetcode_largest_rectangle::square_search::{self, HeightWidthIndex};
+use leetcode_largest_rectangle::{ConcreteHistogram, Histogram};
+
+fn naive_max_width_at_height(bars: &[i32], height: i32) -> usize {
+    bars.split(|&bar| bar.max(0) < height)
+        .map(<[i32]>::len)
+        .max()
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let index = HeightWidthIndex::new(&histogram);
+    let widths: Vec<usize> = (0..=7).map(|height| index.query(height)).collect();
+    assert_eq!(widths, vec![6, 6, 4, 2, 2, 2, 1, 0]);
+}
+
+#[test]
+fn test_heights_outside_the_bars() {
+    let histogram = ConcreteHistogram::new(vec![3, 0, 4, 4]);
+    let index = HeightWidthIndex::new(&histogram);
+    assert_eq!(index.query(histogram.max_height().unwrap() + 1), 0);
+    assert_eq!(index.query(i32::MAX), 0);
+    assert_eq!(index.query(0), 4);
+    assert_eq!(index.query(i32::MIN), 4);
+    assert_eq!(square_search::max_width_at_height(&histogram, 5), 0);
+    assert_eq!(square_search::max_width_at_height(&histogram, -1), 4);
+}
+
+#[test]
+fn test_empty_histogram() {
+    let histogram = ConcreteHistogram::new(vec![]);
+    let index = HeightWidthIndex::new(&histogram);
+    assert_eq!(index.query(0), 0);
+    assert_eq!(index.query(1), 0);
+}
+
+#[test]
+fn test_index_matches_naive_scan() {
+    let mut rng = Rng::new(77);
+    let random = (0..300).map(|_| {
+        let mut bars = rng.bars(60, 25);
+        for bar in bars.iter_mut().step_by(10) {
+            *bar = -*bar;
+        }
+        bars
+    });
+    for bars in all_histograms(5, 3)
+        .into_iter()
+        .chain(random.collect::<Vec<_>>())
+    {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let index = HeightWidthIndex::new(&histogram);
+        for height in -3..=28 {
+            let expected = naive_max_width_at_height(&bars, height);
+            assert_eq!(index.query(height), expected, "{bars:?} at {height}");
+            assert_eq!(
+                square_search::max_width_at_height(&histogram, height),
+                expected,
+                "{bars:?} at {height}"
+            );
+        }
+    }
+}
+
+fn brute_force_max_height_at_width(bars: &[i32], width: usize) -> Option<(usize, i32)> {
+    let mut best: Option<(usize, i32)> = None;
+    for (start, run) in bars.windows(width).enumerate() {
+        let height = run.iter().min().unwrap().max(&0);
+        if best.is_none_or(|(_, best)| *height > best) {
+            best = Some((start, *height));
+        }
+    }
+    best
+}
+
+#[test]
+fn test_max_height_at_width_of_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(square_search::max_height_at_width(&histogram, 2), 5);
+    assert_eq!(
+        square_search::max_height_at_width_with_position(&histogram, 2),
+        Some((2, 5))
+    );
+    assert_eq!(square_search::max_height_at_width(&histogram, 6), 1);
+}
+
+#[test]
+fn test_max_height_at_width_wider_than_histogram() {
+    let histogram = ConcreteHistogram::new(vec![4, 4]);
+    assert_eq!(square_search::max_height_at_width(&histogram, 3), 0);
+    assert_eq!(
+        square_search::max_height_at_width_with_position(&histogram, 3),
+        None
+    );
+}
+
+#[test]
+#[should_panic(expected = "window width must be non-zero")]
+fn test_max_height_at_zero_width_panics() {
+    let histogram = ConcreteHistogram::new(vec![4, 4]);
+    square_search::max_height_at_width(&histogram, 0);
+}
+
+#[test]
+fn test_max_height_at_width_matches_brute_force() {
+    let mut rng = Rng::new(78);
+    for _ in 0..300 {
+        let mut bars = rng.bars(50, 30);
+        for bar in bars.iter_mut().step_by(7) {
+            *bar = -*bar;
+        }
+        let histogram = ConcreteHistogram::new(bars.clone());
+        for width in 1..=bars.len() + 1 {
+            assert_eq!(
+                square_search::max_height_at_width_with_position(&histogram, width),
+                brute_force_max_height_at_width(&bars, width),
+                "{bars:?} at {width}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_best_height_per_width_of_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::best_height_per_width(&histogram),
+        vec![6, 5, 2, 2, 1, 1]
+    );
+}
+
+#[test]
+fn test_best_height_per_width_of_empty_histogram() {
+    let histogram = ConcreteHistogram::new(vec![]);
+    assert!(square_search::best_height_per_width(&histogram).is_empty());
+}
+
+#[test]
+fn test_best_height_per_width_matches_queries_and_largest_area() {
+    let mut rng = Rng::new(79);
+    for _ in 0..300 {
+        let mut bars = rng.bars(60, 40);
+        for bar in bars.iter_mut().step_by(9) {
+            *bar = -*bar;
+        }
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let best = square_search::best_height_per_width(&histogram);
+        for (w, &height) in (1..).zip(&best) {
+            assert_eq!(
+                height,
+                square_search::max_height_at_width(&histogram, w),
+                "{bars:?} at {w}"
+            );
+        }
+        let largest = (1..)
+            .zip(&best)
+            .map(|(w, &height)| w * height as u64)
+            .max()
+            .unwrap_or(0);
+        assert_eq!(
+            largest,
+            square_search::compute_area_of_largest_rectangle(&histogram),
+            "{bars:?}"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use leetcode_largest_rectangle::histogram::FnHistogram;
+use leetcode_largest_rectangle::{
+    square_search, ConcreteHistogram, Histogram, Rectangle, SliceHistogram,
+};
+use std::rc::Rc;
+use std::sync::Arc;
+
+struct ClosureHistogram<F> {
+    width: usize,
+    height_at: F,
+}
+
+impl<F: Fn(usize) -> i32> Histogram for ClosureHistogram<F> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        (self.height_at)(horizontal_position)
+    }
+}
+
+#[test]
+fn test_default_methods_on_concrete_histogram() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert!(!histogram.is_empty());
+    assert_eq!(histogram.max_height(), Some(6));
+    assert_eq!(histogram.min_height(), Some(1));
+    assert_eq!(histogram.total_area(), 19);
+    assert_eq!(
+        histogram.iter_heights().collect::<Vec<_>>(),
+        vec![2, 1, 5, 6, 2, 3]
+    );
+}
+
+#[test]
+fn test_default_methods_on_empty_histogram() {
+    let histogram = ConcreteHistogram::new(vec![]);
+    assert!(histogram.is_empty());
+    assert_eq!(histogram.max_height(), None);
+    assert_eq!(histogram.min_height(), None);
+    assert_eq!(histogram.total_area(), 0);
+    assert_eq!(histogram.iter_heights().count(), 0);
+}
+
+#[test]
+fn test_default_methods_on_closure_histogram() {
+    let histogram = ClosureHistogram {
+        width: 5,
+        height_at: |x_pos: usize| 3 - x_pos as i32,
+    };
+    assert!(!histogram.is_empty());
+    assert_eq!(histogram.max_height(), Some(3));
+    assert_eq!(histogram.min_height(), Some(-1));
+    assert_eq!(histogram.total_area(), 5);
+    assert_eq!(
+        histogram.iter_heights().collect::<Vec<_>>(),
+        vec![3, 2, 1, 0, -1]
+    );
+}
+
+#[test]
+fn test_total_area_does_not_overflow_i32() {
+    let histogram = ConcreteHistogram::new(vec![i32::MAX, i32::MAX]);
+    assert_eq!(histogram.total_area(), 2 * i32::MAX as i64);
+}
+
+#[test]
+fn test_default_methods_on_trait_objects() {
+    let histogram: Box<dyn Histogram> = Box::new(ConcreteHistogram::new(vec![4, 2]));
+    assert_eq!(histogram.width(), 2);
+    assert_eq!(histogram.max_height(), Some(4));
+    assert_eq!(histogram.total_area(), 6);
+}
+
+#[test]
+fn test_solve_boxed_trait_objects() {
+    let histograms: Vec<Box<dyn Histogram>> = vec![
+        Box::new(ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3])),
+        Box::new(ClosureHistogram {
+            width: 4,
+            height_at: |_| 3,
+        }),
+        Box::new(ConcreteHistogram::new(vec![])),
+    ];
+    let areas: Vec<u64> = histograms
+        .iter()
+        .map(square_search::compute_area_of_largest_rectangle)
+        .collect();
+    assert_eq!(areas, vec![10, 12, 0]);
+    let areas: Vec<u64> = histograms
+        .iter()
+        .map(|histogram| square_search::compute_area_of_largest_rectangle(histogram.as_ref()))
+        .collect();
+    assert_eq!(areas, vec![10, 12, 0]);
+}
+
+#[test]
+fn test_solve_through_references_and_shared_pointers() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let as_dyn: &dyn Histogram = &histogram;
+    assert_eq!(square_search::compute_area_of_largest_rectangle(as_dyn), 10);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&&histogram),
+        10
+    );
+    let shared = Rc::new(ConcreteHistogram::new(vec![3, 3]));
+    assert_eq!(square_search::compute_area_of_largest_rectangle(&shared), 6);
+    let shared: Arc<dyn Histogram + Send + Sync> = Arc::new(ConcreteHistogram::new(vec![4, 4]));
+    assert_eq!(square_search::compute_area_of_largest_rectangle(&shared), 8);
+    assert_eq!(
+        square_search::compute_largest_rectangle(&*shared),
+        Some(Rectangle {
+            left: 0,
+            right: 1,
+            height: 4,
+            area: 8
+        })
+    );
+}
+
+#[test]
+fn test_solve_arrays_slices_and_vecs() {
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&[2, 1, 5, 6, 2, 3]),
+        10
+    );
+    let bars = vec![2, 1, 5, 6, 2, 3];
+    assert_eq!(square_search::compute_area_of_largest_rectangle(&bars), 10);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&bars[2..4]),
+        10
+    );
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&bars[4..]),
+        4
+    );
+    let empty: [i32; 0] = [];
+    assert_eq!(square_search::compute_area_of_largest_rectangle(&empty), 0);
+}
+
+#[test]
+fn test_largest_rectangle_of_slices() {
+    assert_eq!(
+        square_search::compute_largest_rectangle(&[2, 4]),
+        Some(Rectangle {
+            left: 0,
+            right: 1,
+            height: 2,
+            area: 4
+        })
+    );
+    let bars: &[i32] = &[7, 7, 1];
+    assert_eq!(square_search::compute_area_of_largest_rectangle(bars), 14);
+}
+
+#[test]
+fn test_fn_histogram_matches_concrete_histogram() {
+    let height_at = |x_pos: usize| ((x_pos * 37) % 101) as i32;
+    for width in [0, 1, 2, 10, 500] {
+        let from_fn = FnHistogram::new(width, height_at);
+        let concrete = ConcreteHistogram::new((0..width).map(height_at).collect());
+        assert_eq!(
+            square_search::compute_largest_rectangle(&from_fn),
+            square_search::compute_largest_rectangle(&concrete)
+        );
+    }
+}
+
+#[test]
+fn test_fn_histogram_over_a_million_bars() {
+    let histogram = FnHistogram::new(1_000_000, |x_pos| ((x_pos * 37) % 101) as i32);
+    let expected: Vec<i32> = (0..1_000_000_usize)
+        .map(|x_pos| ((x_pos * 37) % 101) as i32)
+        .collect();
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram),
+        square_search::compute_area_of_largest_rectangle(&expected)
+    );
+}
+
+#[test]
+fn test_slice_histogram_borrows_from_a_larger_buffer() {
+    let mut buffer = vec![9, 9, 2, 1, 5, 6, 2, 3, 9];
+    let view = SliceHistogram::new(&buffer[2..8]);
+    assert_eq!(view.width(), 6);
+    assert_eq!(square_search::compute_area_of_largest_rectangle(&view), 10);
+    assert_eq!(
+        square_search::compute_largest_rectangle(&view),
+        Some(Rectangle {
+            left: 2,
+            right: 3,
+            height: 5,
+            area: 10
+        })
+    );
+    buffer.push(1);
+    buffer[0] = 0;
+    buffer[3] = 5;
+    assert_eq!(buffer.len(), 10);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&buffer),
+        16
+    );
+}
+
+#[test]
+fn test_view_of_concrete_histogram() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let view = histogram.as_view();
+    assert_eq!(
+        square_search::compute_largest_rectangle(&view),
+        square_search::compute_largest_rectangle(&histogram)
+    );
+    assert_eq!(
+        square_search::top_k_rectangles(&view, 3),
+        square_search::top_k_rectangles(&histogram, 3)
+    );
+    let heights = ConcreteHistogram::from_bars(vec![3_u8, 3, 1]);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&heights.as_view()),
+        6
+    );
+}
+
+#[test]
+fn test_try_height_at_stops_at_the_last_bar() {
+    let bars = vec![2, 1, 5];
+    let concrete = ConcreteHistogram::new(bars.clone());
+    let slice = SliceHistogram::new(&bars);
+    let function = FnHistogram::new(3, |x_pos| bars[x_pos]);
+    let boxed: Box<dyn Histogram> = Box::new(ConcreteHistogram::new(bars.clone()));
+    let histograms: [&dyn Histogram; 5] = [&concrete, &slice, &function, &bars, &boxed];
+    for histogram in histograms {
+        assert_eq!(histogram.try_height_at(2), Some(5));
+        assert_eq!(histogram.try_height_at(3), None);
+        assert_eq!(histogram.try_height_at(usize::MAX), None);
+    }
+}
@@ -0,0 +1,166 @@
+use leetcode_largest_rectangle::histogram_builder::BuildError;
+use leetcode_largest_rectangle::{
+    square_search, ConcreteHistogram, ConcreteHistogramBuilder, Histogram,
+};
+
+#[test]
+fn test_build_pushed_bars_and_runs() {
+    let histogram = ConcreteHistogram::builder()
+        .push(2)
+        .push(1)
+        .push_run(5, 1)
+        .push(6)
+        .push_run(2, 1)
+        .push_run(7, 0)
+        .push(3)
+        .build()
+        .unwrap();
+    assert_eq!(
+        histogram.iter_heights().collect::<Vec<_>>(),
+        vec![2, 1, 5, 6, 2, 3]
+    );
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram),
+        10
+    );
+}
+
+#[test]
+fn test_build_empty_histogram() {
+    let histogram = ConcreteHistogramBuilder::new().build().unwrap();
+    assert!(histogram.is_empty());
+}
+
+#[test]
+fn test_negative_heights_are_rejected_by_default() {
+    let mut builder = ConcreteHistogramBuilder::new();
+    builder.push(2).push_run(3, 4).push(-1).push(5);
+    assert_eq!(
+        builder.build().err(),
+        Some(BuildError::NegativeHeight {
+            bar_index: 5,
+            height: -1
+        })
+    );
+    let histogram = builder.allow_negative_heights(true).build().unwrap();
+    assert_eq!(histogram.width(), 7);
+}
+
+#[test]
+fn test_bar_above_max_height_is_rejected() {
+    let error = ConcreteHistogramBuilder::new()
+        .max_height(5)
+        .push_run(5, 3)
+        .push(6)
+        .build()
+        .err();
+    assert_eq!(
+        error,
+        Some(BuildError::TooTall {
+            bar_index: 3,
+            height: 6,
+            max_height: 5
+        })
+    );
+    assert_eq!(
+        error.unwrap().to_string(),
+        "bar 3 has height 6, above the maximum of 5"
+    );
+}
+
+#[test]
+fn test_bars_beyond_max_width_are_rejected() {
+    let error = ConcreteHistogramBuilder::new()
+        .max_width(10)
+        .push_run(1, 4)
+        .push_run(2, usize::MAX - 1)
+        .build()
+        .err();
+    assert_eq!(
+        error,
+        Some(BuildError::TooWide {
+            bar_index: 10,
+            max_width: 10
+        })
+    );
+    let histogram = ConcreteHistogramBuilder::new()
+        .max_width(10)
+        .push_run(1, 10)
+        .build()
+        .unwrap();
+    assert_eq!(histogram.width(), 10);
+}
+
+#[test]
+fn test_merged_runs_that_overflow_are_rejected() {
+    let error = ConcreteHistogramBuilder::new()
+        .max_width(10)
+        .push_run(1, usize::MAX)
+        .push_run(1, 5)
+        .build()
+        .err();
+    assert_eq!(
+        error,
+        Some(BuildError::TooWide {
+            bar_index: 10,
+            max_width: 10
+        })
+    );
+    let error = ConcreteHistogramBuilder::new()
+        .push(2)
+        .push_run(1, usize::MAX)
+        .push_run(1, 5)
+        .build()
+        .err();
+    assert_eq!(error, Some(BuildError::WidthOverflow { bar_index: 1 }));
+    assert_eq!(
+        error.unwrap().to_string(),
+        "bar 1 is beyond the most bars a histogram can hold"
+    );
+    let error = ConcreteHistogramBuilder::new()
+        .push_run(1, usize::MAX / 2)
+        .push_run(1, usize::MAX / 2)
+        .push_run(1, 5)
+        .build()
+        .err();
+    assert_eq!(error, Some(BuildError::WidthOverflow { bar_index: 0 }));
+}
+
+#[test]
+fn test_earliest_offending_bar_is_reported() {
+    let error = ConcreteHistogramBuilder::new()
+        .max_width(3)
+        .max_height(4)
+        .push(1)
+        .push(9)
+        .push(-1)
+        .push(1)
+        .build()
+        .err();
+    assert_eq!(
+        error,
+        Some(BuildError::TooTall {
+            bar_index: 1,
+            height: 9,
+            max_height: 4
+        })
+    );
+}
+
+#[test]
+fn test_large_build_from_runs() {
+    let mut builder = ConcreteHistogramBuilder::new();
+    for height in 1..=1000 {
+        builder.push_run(height, 1000);
+    }
+    let histogram = builder
+        .max_width(1_000_000)
+        .max_height(1000)
+        .build()
+        .unwrap();
+    assert_eq!(histogram.width(), 1_000_000);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram),
+        500 * 501 * 1000
+    );
+}
@@ -0,0 +1,161 @@
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::histogram_concrete::OutOfRange;
+use leetcode_largest_rectangle::{square_search, ConcreteHistogram, Histogram};
+
+#[test]
+fn test_from_vec() {
+    let histogram = ConcreteHistogram::from(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram),
+        10
+    );
+    let histogram: ConcreteHistogram = vec![3, 3].into();
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram),
+        6
+    );
+}
+
+#[test]
+fn test_collect_from_values_and_references() {
+    let data = [1, 0, 4, 5, 1, 2];
+    let histogram: ConcreteHistogram = data.iter().map(|score| score + 1).collect();
+    assert_eq!(
+        histogram.iter_heights().collect::<Vec<_>>(),
+        vec![2, 1, 5, 6, 2, 3]
+    );
+    let histogram: ConcreteHistogram = data.iter().collect();
+    assert_eq!(histogram.iter_heights().collect::<Vec<_>>(), data);
+}
+
+#[test]
+fn test_collect_from_empty_iterator() {
+    let histogram: ConcreteHistogram = std::iter::empty::<i32>().collect();
+    assert!(histogram.is_empty());
+    assert_eq!(square_search::compute_largest_rectangle(&histogram), None);
+}
+
+#[test]
+fn test_extend_changes_answer() {
+    let mut histogram = ConcreteHistogram::with_capacity(6);
+    histogram.extend([2, 1, 5]);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram),
+        5
+    );
+    histogram.extend(vec![6, 2, 3]);
+    assert_eq!(histogram.width(), 6);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram),
+        10
+    );
+    histogram.extend(std::iter::repeat_n(4, 5));
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram),
+        20
+    );
+}
+
+#[test]
+fn test_edits_keep_answers_consistent() {
+    let mut histogram = ConcreteHistogram::new(vec![2, 1, 5]);
+    let mut expected = vec![2, 1, 5];
+    let check = |histogram: &ConcreteHistogram, expected: &[i32]| {
+        assert_eq!(histogram.heights(), expected);
+        assert_eq!(histogram.width(), expected.len());
+        assert_eq!(
+            square_search::compute_largest_rectangle(histogram),
+            square_search::compute_largest_rectangle(&ConcreteHistogram::new(expected.to_vec()))
+        );
+    };
+    check(&histogram, &expected);
+
+    histogram.push(6);
+    expected.push(6);
+    check(&histogram, &expected);
+
+    histogram.insert(0, 7);
+    expected.insert(0, 7);
+    check(&histogram, &expected);
+
+    histogram.try_set_height(2, 6).unwrap();
+    expected[2] = 6;
+    check(&histogram, &expected);
+
+    assert_eq!(histogram.remove(1), 2);
+    expected.remove(1);
+    check(&histogram, &expected);
+
+    assert_eq!(histogram.pop(), Some(6));
+    expected.pop();
+    check(&histogram, &expected);
+
+    histogram.truncate(1);
+    expected.truncate(1);
+    check(&histogram, &expected);
+
+    histogram.truncate(5);
+    check(&histogram, &expected);
+
+    assert_eq!(histogram.pop(), Some(7));
+    assert_eq!(histogram.pop(), None);
+    check(&histogram, &[]);
+}
+
+#[test]
+fn test_try_set_height_out_of_range() {
+    let mut histogram = ConcreteHistogram::new(vec![2, 1, 5]);
+    let error = histogram.try_set_height(3, 4).unwrap_err();
+    assert_eq!(
+        error,
+        OutOfRange {
+            horizontal_position: 3,
+            width: 3
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "bar 3 is out of range for a histogram of width 3"
+    );
+    assert_eq!(histogram.heights(), &[2, 1, 5]);
+}
+
+#[test]
+fn test_random_edits_match_fresh_histogram() {
+    let mut rng = Rng::new(44);
+    let mut histogram = ConcreteHistogram::new(Vec::new());
+    let mut expected = Vec::new();
+    for _ in 0..2000 {
+        let height = rng.below(8) as i32;
+        match rng.below(6) {
+            0 | 1 => {
+                histogram.push(height);
+                expected.push(height);
+            }
+            2 => assert_eq!(histogram.pop(), expected.pop()),
+            3 if !expected.is_empty() => {
+                let pos = rng.below(expected.len() + 1);
+                histogram.insert(pos, height);
+                expected.insert(pos, height);
+            }
+            4 if !expected.is_empty() => {
+                let pos = rng.below(expected.len());
+                assert_eq!(histogram.remove(pos), expected.remove(pos));
+            }
+            _ if !expected.is_empty() => {
+                let pos = rng.below(expected.len());
+                histogram.try_set_height(pos, height).unwrap();
+                expected[pos] = height;
+            }
+            _ => {}
+        }
+        assert_eq!(
+            square_search::compute_area_of_largest_rectangle(&histogram),
+            square_search::compute_area_of_largest_rectangle(&ConcreteHistogram::new(
+                expected.clone()
+            ))
+        );
+    }
+}
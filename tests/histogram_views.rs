@@ -0,0 +1,360 @@
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::histogram_views::SubHistogram;
+use leetcode_largest_rectangle::square_search::{NegativeHeight, NegativePolicy, TieBreak};
+use leetcode_largest_rectangle::{
+    histogram, square_search, ConcreteHistogram, Histogram, Rectangle,
+};
+
+#[test]
+fn test_slice_offsets_positions() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let sub = histogram.slice(2..5);
+    assert_eq!(sub.width(), 3);
+    assert_eq!(sub.iter_heights().collect::<Vec<_>>(), vec![5, 6, 2]);
+    assert_eq!(
+        square_search::compute_largest_rectangle(&sub),
+        Some(Rectangle {
+            left: 0,
+            right: 1,
+            height: 5,
+            area: 10
+        })
+    );
+}
+
+#[test]
+fn test_empty_slices() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5]);
+    assert!(histogram.slice(1..1).is_empty());
+    assert!(histogram.slice(3..3).is_empty());
+    assert_eq!(
+        square_search::compute_largest_rectangle(&histogram.slice(0..0)),
+        None
+    );
+}
+
+#[test]
+fn test_slice_of_slice_and_of_trait_object() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let sub = histogram.slice(1..6);
+    assert_eq!(
+        sub.slice(1..3).iter_heights().collect::<Vec<_>>(),
+        vec![5, 6]
+    );
+    let as_dyn: &dyn Histogram = &histogram;
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&SubHistogram::new(as_dyn, 3..6)),
+        6
+    );
+}
+
+#[test]
+#[should_panic(expected = "range 4..7 is out of bounds for a histogram of width 6")]
+fn test_slice_past_the_end_panics() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    histogram.slice(4..7);
+}
+
+#[test]
+#[should_panic(expected = "range 3..2 is out of bounds")]
+fn test_backwards_slice_panics() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    #[allow(clippy::reversed_empty_ranges)]
+    histogram.slice(3..2);
+}
+
+#[test]
+fn test_slice_matches_copied_bars() {
+    let mut rng = Rng::new(36);
+    for _ in 0..500 {
+        let bars = rng.bars(20, 6);
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let start = rng.below(bars.len() + 1);
+        let end = start + rng.below(bars.len() - start + 1);
+        let copied = ConcreteHistogram::new(bars[start..end].to_vec());
+        assert_eq!(
+            square_search::compute_area_of_largest_rectangle(&histogram.slice(start..end)),
+            square_search::compute_area_of_largest_rectangle(&copied),
+            "{bars:?}[{start}..{end}]"
+        );
+    }
+}
+
+#[test]
+fn test_reversed_bars() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let reversed = histogram.reversed();
+    assert_eq!(reversed.width(), 6);
+    assert_eq!(
+        reversed.iter_heights().collect::<Vec<_>>(),
+        vec![3, 2, 6, 5, 1, 2]
+    );
+    assert_eq!(
+        reversed.reversed().iter_heights().collect::<Vec<_>>(),
+        vec![2, 1, 5, 6, 2, 3]
+    );
+    assert!(ConcreteHistogram::new(vec![]).reversed().is_empty());
+}
+
+#[test]
+fn test_reversal_preserves_area_and_mirrors_rectangles() {
+    let mut rng = Rng::new(37);
+    for _ in 0..1000 {
+        let bars = rng.bars(16, 6);
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let reversed = histogram.reversed();
+        assert_eq!(
+            square_search::compute_area_of_largest_rectangle(&histogram),
+            square_search::compute_area_of_largest_rectangle(&reversed),
+            "{bars:?}"
+        );
+        let mirrored =
+            square_search::compute_largest_rectangle(&reversed).map(|rectangle| Rectangle {
+                left: bars.len() - 1 - rectangle.right,
+                right: bars.len() - 1 - rectangle.left,
+                ..rectangle
+            });
+        assert_eq!(
+            mirrored,
+            square_search::compute_largest_rectangle_with(&histogram, TieBreak::Rightmost),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_chain_concatenates_bars() {
+    let first = ConcreteHistogram::new(vec![2, 1, 5]);
+    let second = ConcreteHistogram::new(vec![6, 2, 3]);
+    let chained = first.chain(&second);
+    assert_eq!(chained.width(), 6);
+    assert_eq!(
+        chained.iter_heights().collect::<Vec<_>>(),
+        vec![2, 1, 5, 6, 2, 3]
+    );
+    assert_eq!(
+        square_search::compute_largest_rectangle(&chained),
+        Some(Rectangle {
+            left: 2,
+            right: 3,
+            height: 5,
+            area: 10
+        })
+    );
+}
+
+#[test]
+fn test_chaining_empty_histogram_is_a_no_op() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let empty = ConcreteHistogram::new(vec![]);
+    for chained in [histogram.chain(&empty), empty.chain(&histogram)] {
+        assert_eq!(
+            chained.iter_heights().collect::<Vec<_>>(),
+            vec![2, 1, 5, 6, 2, 3]
+        );
+    }
+    assert!(empty.chain(&empty).is_empty());
+}
+
+#[test]
+fn test_nested_chains() {
+    let first = ConcreteHistogram::new(vec![2, 1]);
+    let second = ConcreteHistogram::new(vec![5, 6]);
+    let third = [2, 3];
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&first.chain(&second).chain(&third)),
+        10
+    );
+    let first_two = first.chain(&second);
+    let all_three = first_two.chain(&third);
+    let after_first = second.chain(&third);
+    let grouped_right = first.chain(&after_first);
+    assert_eq!(
+        all_three.iter_heights().collect::<Vec<_>>(),
+        grouped_right.iter_heights().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_chain_matches_concatenated_bars() {
+    let mut rng = Rng::new(38);
+    for _ in 0..500 {
+        let bars = rng.bars(20, 6);
+        let split = rng.below(bars.len() + 1);
+        let first = ConcreteHistogram::new(bars[..split].to_vec());
+        let second = ConcreteHistogram::new(bars[split..].to_vec());
+        assert_eq!(
+            square_search::compute_largest_rectangle(&first.chain(&second)),
+            square_search::compute_largest_rectangle(&ConcreteHistogram::new(bars.clone())),
+            "{bars:?} split at {split}"
+        );
+    }
+}
+
+#[test]
+fn test_map_heights_transforms_lazily() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let doubled = histogram.map_heights(|height| 2 * height);
+    assert_eq!(
+        doubled.iter_heights().collect::<Vec<_>>(),
+        vec![4, 2, 10, 12, 4, 6]
+    );
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&doubled),
+        20
+    );
+}
+
+#[test]
+fn test_offset_and_scale() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let above_floor = histogram.offset(4);
+    assert_eq!(
+        above_floor.iter_heights().collect::<Vec<_>>(),
+        vec![-2, -3, 1, 2, -2, -1]
+    );
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&above_floor),
+        2
+    );
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram.scale(3)),
+        30
+    );
+    let saturated = ConcreteHistogram::new(vec![i32::MAX, i32::MIN]);
+    assert_eq!(
+        saturated.scale(2).iter_heights().collect::<Vec<_>>(),
+        vec![i32::MAX, i32::MIN]
+    );
+    assert_eq!(
+        saturated.offset(1).iter_heights().collect::<Vec<_>>(),
+        vec![i32::MAX - 1, i32::MIN]
+    );
+    assert_eq!(
+        saturated.offset(-1).iter_heights().collect::<Vec<_>>(),
+        vec![i32::MAX, i32::MIN + 1]
+    );
+}
+
+#[test]
+fn test_negative_mapped_heights_follow_negative_policy() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let negated = histogram.map_heights(|height| -height);
+    assert_eq!(square_search::compute_largest_rectangle(&negated), None);
+    assert_eq!(
+        square_search::compute_largest_rectangle_with_policy(&negated, NegativePolicy::Reject),
+        Err(NegativeHeight { bar_index: 0 })
+    );
+}
+
+#[test]
+fn test_map_over_map() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let shifted = histogram.map_heights(|height| height + 1);
+    let shifted_and_scaled = shifted.map_heights(|height| height * 10);
+    assert_eq!(
+        shifted_and_scaled.iter_heights().collect::<Vec<_>>(),
+        vec![30, 20, 60, 70, 30, 40]
+    );
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram.offset(1).scale(2)),
+        square_search::compute_area_of_largest_rectangle(
+            &histogram.map_heights(|height| 2 * (height - 1))
+        )
+    );
+}
+
+#[test]
+fn test_overlay_min_and_max() {
+    let first = ConcreteHistogram::new(vec![2, 5, 2]);
+    let second = ConcreteHistogram::new(vec![4, 1, 4]);
+    let lower = histogram::overlay_min(&first, &second);
+    let higher = histogram::overlay_max(&first, &second);
+    assert_eq!(lower.iter_heights().collect::<Vec<_>>(), vec![2, 1, 2]);
+    assert_eq!(higher.iter_heights().collect::<Vec<_>>(), vec![4, 5, 4]);
+    assert_eq!(square_search::compute_area_of_largest_rectangle(&lower), 3);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&higher),
+        12
+    );
+}
+
+#[test]
+#[should_panic(expected = "overlaid histograms must have the same width")]
+fn test_overlay_of_different_widths_panics() {
+    let first = ConcreteHistogram::new(vec![2, 5, 2]);
+    let second = ConcreteHistogram::new(vec![4, 1]);
+    histogram::overlay_min(&first, &second);
+}
+
+#[test]
+fn test_overlay_areas_are_bounded_by_each_histogram() {
+    let mut rng = Rng::new(40);
+    for _ in 0..500 {
+        let first = rng.bars(12, 6);
+        let second: Vec<i32> = (0..first.len()).map(|_| rng.below(7) as i32).collect();
+        let area =
+            |histogram: &dyn Histogram| square_search::compute_area_of_largest_rectangle(histogram);
+        let (first, second) = (
+            ConcreteHistogram::new(first),
+            ConcreteHistogram::new(second),
+        );
+        let lower = area(&histogram::overlay_min(&first, &second));
+        let higher = area(&histogram::overlay_max(&first, &second));
+        assert!(lower <= area(&first).min(area(&second)));
+        assert!(higher >= area(&first).max(area(&second)));
+    }
+}
+
+#[test]
+fn test_clamp_heights() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let capped = histogram.clamp(2, 4);
+    assert_eq!(
+        capped.iter_heights().collect::<Vec<_>>(),
+        vec![2, 2, 4, 4, 2, 3]
+    );
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&capped),
+        12
+    );
+}
+
+#[test]
+fn test_clamp_at_or_above_max_height_is_a_no_op() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    for max in [6, 7, i32::MAX] {
+        assert_eq!(
+            square_search::compute_largest_rectangle(&histogram.clamp(0, max)),
+            square_search::compute_largest_rectangle(&histogram)
+        );
+    }
+}
+
+#[test]
+fn test_clamp_to_zero_leaves_no_area() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram.clamp(0, 0)),
+        0
+    );
+}
+
+#[test]
+#[should_panic(expected = "clamp range must not have min above max")]
+fn test_clamp_with_min_above_max_panics() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    histogram.clamp(4, 2);
+}
+
+#[test]
+fn test_clamp_composes_with_other_views() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let sub = histogram.slice(1..5);
+    let view = sub.clamp(1, 5);
+    let view = view.reversed();
+    assert_eq!(view.iter_heights().collect::<Vec<_>>(), vec![2, 5, 5, 1]);
+    assert_eq!(square_search::compute_area_of_largest_rectangle(&view), 10);
+}
@@ -0,0 +1,266 @@
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::matrix::{self, BinaryMatrix, Matrix, MatrixRectangle, RaggedRows};
+
+fn parse(rows: &[&str]) -> BinaryMatrix {
+    BinaryMatrix::new(
+        rows.iter()
+            .map(|row| row.chars().map(|cell| cell == '1').collect())
+            .collect(),
+    )
+    .unwrap()
+}
+
+fn brute_force_maximal_area(matrix: &BinaryMatrix) -> u64 {
+    let mut best = 0;
+    for top in 0..matrix.rows() {
+        for bottom in top..matrix.rows() {
+            for left in 0..matrix.columns() {
+                for right in left..matrix.columns() {
+                    let all_set = (top..=bottom)
+                        .all(|row| matrix.row(row)[left..=right].iter().all(|&cell| cell));
+                    if all_set {
+                        best = best.max(((bottom - top + 1) * (right - left + 1)) as u64);
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+#[test]
+fn test_leetcode_example() {
+    let matrix = parse(&["10100", "10111", "11111", "10010"]);
+    assert_eq!(
+        matrix::maximal_rectangle(&matrix),
+        Some(MatrixRectangle {
+            top: 1,
+            left: 2,
+            bottom: 2,
+            right: 4,
+            area: 6
+        })
+    );
+}
+
+#[test]
+fn test_all_zero_matrix() {
+    let matrix = parse(&["000", "000"]);
+    assert_eq!(matrix::maximal_rectangle(&matrix), None);
+}
+
+#[test]
+fn test_single_row_matrix() {
+    let matrix = parse(&["0110111"]);
+    assert_eq!(
+        matrix::maximal_rectangle(&matrix),
+        Some(MatrixRectangle {
+            top: 0,
+            left: 4,
+            bottom: 0,
+            right: 6,
+            area: 3
+        })
+    );
+}
+
+#[test]
+fn test_empty_matrices() {
+    let matrix = BinaryMatrix::new(vec![]).unwrap();
+    assert_eq!((matrix.rows(), matrix.columns()), (0, 0));
+    assert_eq!(matrix::maximal_rectangle(&matrix), None);
+    let matrix = BinaryMatrix::new(vec![vec![], vec![]]).unwrap();
+    assert_eq!((matrix.rows(), matrix.columns()), (2, 0));
+    assert_eq!(matrix::maximal_rectangle(&matrix), None);
+}
+
+#[test]
+fn test_ragged_rows_are_rejected() {
+    let error = BinaryMatrix::new(vec![vec![true, false], vec![true, true], vec![true]]);
+    assert_eq!(
+        error,
+        Err(RaggedRows {
+            row: 2,
+            columns: 1,
+            expected_columns: 2
+        })
+    );
+    assert_eq!(
+        error.unwrap_err().to_string(),
+        "row 2 has 1 columns, but the first row has 2"
+    );
+}
+
+#[test]
+fn test_maximal_rectangle_matches_brute_force() {
+    let mut rng = Rng::new(67);
+    for _ in 0..300 {
+        let (rows, columns) = (rng.below(7), 1 + rng.below(7));
+        let matrix = BinaryMatrix::new(
+            (0..rows)
+                .map(|_| (0..columns).map(|_| rng.below(4) != 0).collect())
+                .collect(),
+        )
+        .unwrap();
+        let found = matrix::maximal_rectangle(&matrix);
+        assert_eq!(
+            found.map_or(0, |rectangle| rectangle.area),
+            brute_force_maximal_area(&matrix)
+        );
+        if let Some(rectangle) = found {
+            assert_eq!(
+                rectangle.area,
+                ((rectangle.bottom - rectangle.top + 1) * (rectangle.right - rectangle.left + 1))
+                    as u64
+            );
+            for row in rectangle.top..=rectangle.bottom {
+                assert!(matrix.row(row)[rectangle.left..=rectangle.right]
+                    .iter()
+                    .all(|&cell| cell));
+            }
+        }
+    }
+}
+
+fn brute_force_count_all_ones_submatrices(matrix: &BinaryMatrix) -> u64 {
+    let mut count = 0;
+    for top in 0..matrix.rows() {
+        for bottom in top..matrix.rows() {
+            for left in 0..matrix.columns() {
+                for right in left..matrix.columns() {
+                    let all_set = (top..=bottom)
+                        .all(|row| matrix.row(row)[left..=right].iter().all(|&cell| cell));
+                    count += u64::from(all_set);
+                }
+            }
+        }
+    }
+    count
+}
+
+#[test]
+fn test_count_all_ones_submatrices_of_leetcode_examples() {
+    let matrix = parse(&["101", "110", "110"]);
+    assert_eq!(matrix::count_all_ones_submatrices(&matrix), 13);
+    let matrix = parse(&["0110", "0111", "1110"]);
+    assert_eq!(matrix::count_all_ones_submatrices(&matrix), 24);
+}
+
+#[test]
+fn test_count_all_ones_submatrices_of_empty_and_all_zero_matrices() {
+    let matrix = BinaryMatrix::new(vec![]).unwrap();
+    assert_eq!(matrix::count_all_ones_submatrices(&matrix), 0);
+    let matrix = parse(&["000", "000"]);
+    assert_eq!(matrix::count_all_ones_submatrices(&matrix), 0);
+}
+
+#[test]
+fn test_count_all_ones_submatrices_matches_brute_force() {
+    let mut rng = Rng::new(69);
+    for _ in 0..300 {
+        let (rows, columns) = (rng.below(6), 1 + rng.below(6));
+        let matrix = BinaryMatrix::new(
+            (0..rows)
+                .map(|_| (0..columns).map(|_| rng.below(3) != 0).collect())
+                .collect(),
+        )
+        .unwrap();
+        assert_eq!(
+            matrix::count_all_ones_submatrices(&matrix),
+            brute_force_count_all_ones_submatrices(&matrix)
+        );
+    }
+}
+
+#[test]
+fn test_count_all_ones_submatrices_of_large_all_ones_matrix() {
+    let matrix = BinaryMatrix::new(vec![vec![true; 1000]; 1000]).unwrap();
+    let ranges_of_1000: u64 = 1000 * 1001 / 2;
+    assert_eq!(
+        matrix::count_all_ones_submatrices(&matrix),
+        ranges_of_1000 * ranges_of_1000
+    );
+}
+
+/// Four rows, each counting 0 to 5 from left to right.
+fn gradient() -> Matrix<i32> {
+    Matrix::new(vec![(0..6).collect(); 4]).unwrap()
+}
+
+#[test]
+fn test_largest_rectangle_above_thresholds_of_gradient() {
+    let matrix = gradient();
+    let whole = MatrixRectangle {
+        top: 0,
+        left: 0,
+        bottom: 3,
+        right: 5,
+        area: 24,
+    };
+    assert_eq!(
+        matrix::largest_rectangle_where(&matrix, |cell| cell >= 0),
+        Some(whole)
+    );
+    assert_eq!(
+        matrix::largest_rectangle_where(&matrix, |cell| cell >= 3),
+        Some(MatrixRectangle {
+            left: 3,
+            area: 12,
+            ..whole
+        })
+    );
+    assert_eq!(
+        matrix::largest_rectangle_where(&matrix, |cell| cell >= 5),
+        Some(MatrixRectangle {
+            left: 5,
+            area: 4,
+            ..whole
+        })
+    );
+    assert_eq!(
+        matrix::largest_rectangle_where(&matrix, |cell| cell >= 6),
+        None
+    );
+}
+
+#[test]
+fn test_largest_rectangle_below_threshold_of_gradient() {
+    assert_eq!(
+        matrix::largest_rectangle_where(&gradient(), |cell| cell <= 1),
+        Some(MatrixRectangle {
+            top: 0,
+            left: 0,
+            bottom: 3,
+            right: 1,
+            area: 8
+        })
+    );
+}
+
+#[test]
+fn test_largest_rectangle_where_matches_binary_search_of_thresholded_matrix() {
+    let mut rng = Rng::new(70);
+    for _ in 0..200 {
+        let (rows, columns) = (rng.below(7), 1 + rng.below(7));
+        let cells: Vec<Vec<i32>> = (0..rows)
+            .map(|_| (0..columns).map(|_| rng.below(10) as i32 - 3).collect())
+            .collect();
+        let threshold = rng.below(8) as i32 - 2;
+        let thresholded = BinaryMatrix::new(
+            cells
+                .iter()
+                .map(|row| row.iter().map(|&cell| cell >= threshold).collect())
+                .collect(),
+        )
+        .unwrap();
+        let matrix = Matrix::new(cells).unwrap();
+        let found = matrix::largest_rectangle_where(&matrix, |cell| cell >= threshold);
+        assert_eq!(found, matrix::maximal_rectangle(&thresholded));
+        assert_eq!(
+            found.map_or(0, |rectangle| rectangle.area),
+            brute_force_maximal_area(&thresholded)
+        );
+    }
+}
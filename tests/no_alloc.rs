@@ -0,0 +1,125 @@
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::square_search::{self, ScratchTooSmall};
+use leetcode_largest_rectangle::{ArrayHistogram, ConcreteHistogram};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations_during<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn test_array_histogram_with_scratch_does_not_allocate() {
+    const HISTOGRAM: ArrayHistogram<6> = ArrayHistogram::new([2, 1, 5, 6, 2, 3]);
+    let mut scratch = [0_i64; 7];
+    let (area, allocations) = allocations_during(|| {
+        square_search::compute_area_of_largest_rectangle_with_scratch(&HISTOGRAM, &mut scratch)
+    });
+    assert_eq!(area, Ok(10));
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_scratch_must_hold_one_entry_per_bar_and_a_sentinel() {
+    let histogram = ArrayHistogram::new([2, 1, 5, 6, 2, 3]);
+    let mut scratch = [0_i64; 6];
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle_with_scratch(&histogram, &mut scratch),
+        Err(ScratchTooSmall {
+            required: 7,
+            provided: 6
+        })
+    );
+    let empty = ArrayHistogram::new([]);
+    let mut scratch = [0_i64; 1];
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle_with_scratch(&empty, &mut scratch),
+        Ok(0)
+    );
+}
+
+#[test]
+fn test_scratch_search_matches_allocating_search() {
+    let mut rng = Rng::new(34);
+    let mut scratch = vec![0_i64; 32];
+    for _ in 0..1000 {
+        let bars: Vec<i32> = rng.bars(31, 8).into_iter().map(|bar| bar - 1).collect();
+        let histogram = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            square_search::compute_area_of_largest_rectangle_with_scratch(&histogram, &mut scratch),
+            Ok(square_search::compute_area_of_largest_rectangle(&histogram)),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_increasing_bars_fill_the_scratch_buffer() {
+    let histogram = ArrayHistogram::new([1, 2, 3, 4, 5]);
+    let mut scratch = [0_i64; 6];
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle_with_scratch(&histogram, &mut scratch),
+        Ok(9)
+    );
+}
+
+#[test]
+fn test_small_histograms_do_not_allocate() {
+    let mut rng = Rng::new(57);
+    let histograms: Vec<ConcreteHistogram> = (0..500)
+        .map(|_| ConcreteHistogram::new(rng.bars(32, 8)))
+        .collect();
+    let increasing = ArrayHistogram::new(std::array::from_fn::<i32, 32, _>(|x_pos| x_pos as i32));
+    let (_, allocations) = allocations_during(|| {
+        for histogram in &histograms {
+            square_search::compute_area_of_largest_rectangle(histogram);
+            square_search::compute_largest_rectangle(histogram);
+        }
+        assert_eq!(
+            square_search::compute_area_of_largest_rectangle(&increasing),
+            16 * 16
+        );
+    });
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_wide_histogram_allocates_the_stack_once() {
+    let histogram = ConcreteHistogram::new((1..=100_000).collect());
+    let (area, allocations) =
+        allocations_during(|| square_search::compute_area_of_largest_rectangle(&histogram));
+    assert_eq!(area, 50_000 * 50_001);
+    assert_eq!(allocations, 1);
+}
+
+#[test]
+fn test_trapped_water_does_not_allocate() {
+    let histogram = ConcreteHistogram::new((0..10_000).map(|x_pos| x_pos % 7).collect());
+    let (water, allocations) = allocations_during(|| square_search::trapped_water(&histogram));
+    assert!(water > 0);
+    assert_eq!(allocations, 0);
+}
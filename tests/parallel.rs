@@ -0,0 +1,134 @@
+mod common;
+
+use common::{all_histograms, Rng};
+use leetcode_largest_rectangle::square_search::{
+    self, compute_area_parallel, compute_area_parallel_with,
+};
+use leetcode_largest_rectangle::{ConcreteHistogram, Histogram};
+use std::thread::{self, ThreadId};
+
+#[test]
+fn test_parallel_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(compute_area_parallel(&histogram), 10);
+    for chunks in 1..=8 {
+        assert_eq!(
+            compute_area_parallel_with(&histogram, chunks),
+            10,
+            "{chunks}"
+        );
+    }
+}
+
+#[test]
+fn test_parallel_matches_serial_on_every_small_histogram() {
+    for bars in all_histograms(6, 3) {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let expected = square_search::compute_area_of_largest_rectangle(&histogram);
+        for chunks in 1..=4 {
+            assert_eq!(
+                compute_area_parallel_with(&histogram, chunks),
+                expected,
+                "{bars:?} in {chunks} chunks"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_parallel_matches_serial_on_random_histograms() {
+    let mut rng = Rng::new(58);
+    for _ in 0..1000 {
+        let bars: Vec<i32> = rng
+            .bars(200, 12)
+            .into_iter()
+            .map(|height| height - 2)
+            .collect();
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let chunks = 1 + rng.below(9);
+        assert_eq!(
+            compute_area_parallel_with(&histogram, chunks),
+            square_search::compute_area_of_largest_rectangle(&histogram),
+            "{bars:?} in {chunks} chunks"
+        );
+    }
+}
+
+#[test]
+fn test_parallel_on_sorted_and_flat_histograms() {
+    let increasing = ConcreteHistogram::new((1..=300_000).collect());
+    let decreasing = ConcreteHistogram::new((1..=300_000).rev().collect());
+    let flat = ConcreteHistogram::new(vec![7; 300_000]);
+    for chunks in [1, 3, 8] {
+        assert_eq!(
+            compute_area_parallel_with(&increasing, chunks),
+            150_000 * 150_001
+        );
+        assert_eq!(
+            compute_area_parallel_with(&decreasing, chunks),
+            150_000 * 150_001
+        );
+        assert_eq!(compute_area_parallel_with(&flat, chunks), 2_100_000);
+    }
+}
+
+/// Records the threads its bars are read on.
+struct ThreadRecordingHistogram {
+    bars: Vec<i32>,
+    readers: std::sync::Mutex<Vec<ThreadId>>,
+}
+
+impl Histogram for ThreadRecordingHistogram {
+    fn width(&self) -> usize {
+        self.bars.len()
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        self.readers.lock().unwrap().push(thread::current().id());
+        self.bars[horizontal_position]
+    }
+}
+
+#[test]
+fn test_one_chunk_is_searched_on_the_calling_thread() {
+    let histogram = ThreadRecordingHistogram {
+        bars: vec![2, 1, 5, 6, 2, 3],
+        readers: Default::default(),
+    };
+    assert_eq!(compute_area_parallel_with(&histogram, 1), 10);
+    let readers = histogram.readers.into_inner().unwrap();
+    assert!(!readers.is_empty());
+    assert!(readers
+        .iter()
+        .all(|&reader| reader == thread::current().id()));
+}
+
+#[test]
+fn test_parallel_on_empty_histogram() {
+    let empty = ConcreteHistogram::new(vec![]);
+    assert_eq!(compute_area_parallel(&empty), 0);
+    assert_eq!(compute_area_parallel_with(&empty, 4), 0);
+}
+
+#[test]
+#[should_panic(expected = "the number of chunks must be non-zero")]
+fn test_zero_chunks_panics() {
+    compute_area_parallel_with(&ConcreteHistogram::new(vec![1]), 0);
+}
+
+#[test]
+fn test_batch_of_histograms_in_parallel_keeps_order() {
+    let mut rng = Rng::new(59);
+    let histograms: Vec<ConcreteHistogram> = (0..3000)
+        .map(|_| ConcreteHistogram::new(rng.bars(40, 20)))
+        .collect();
+    let areas = square_search::compute_areas_parallel(&histograms);
+    assert_eq!(areas.len(), histograms.len());
+    for (histogram, area) in histograms.iter().zip(areas) {
+        assert_eq!(
+            area,
+            square_search::compute_area_of_largest_rectangle(histogram)
+        );
+    }
+    assert!(square_search::compute_areas_parallel(&[]).is_empty());
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "paranoid")]
+
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::{square_search, ConcreteHistogram, Histogram};
+use std::cell::Cell;
+
+/// A histogram whose bars all grow by one each time the first bar is read, so
+/// that two searches of it see different bars.
+struct GrowingHistogram {
+    width: usize,
+    growth: Cell<i32>,
+}
+
+impl Histogram for GrowingHistogram {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        if horizontal_position == 0 {
+            self.growth.set(self.growth.get() + 1);
+        }
+        self.growth.get()
+    }
+}
+
+#[test]
+fn test_no_false_positives_on_random_histograms() {
+    let mut rng = Rng::new(53);
+    for _ in 0..3000 {
+        let bars: Vec<i32> = rng
+            .bars(80, 12)
+            .into_iter()
+            .map(|height| height - 2)
+            .collect();
+        let histogram = ConcreteHistogram::new(bars);
+        square_search::compute_area_of_largest_rectangle(&histogram);
+        square_search::compute_largest_rectangle(&histogram.as_view().reversed());
+    }
+}
+
+// The limit is shared by the whole process, so it is only changed here.
+#[test]
+fn test_disagreement_panics_within_the_limit() {
+    let growing = || GrowingHistogram {
+        width: 3,
+        growth: Cell::new(0),
+    };
+    let error = std::panic::catch_unwind(|| {
+        square_search::compute_area_of_largest_rectangle(&growing());
+    })
+    .unwrap_err();
+    let message = error.downcast_ref::<String>().unwrap();
+    assert!(
+        message.starts_with("cross-check failed for bars ["),
+        "{message}"
+    );
+    assert!(
+        message.contains("the naive search found Some(Rectangle"),
+        "{message}"
+    );
+
+    square_search::set_crosscheck_limit(2);
+    square_search::compute_area_of_largest_rectangle(&growing());
+    square_search::set_crosscheck_limit(256);
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "testing")]
+
+use leetcode_largest_rectangle::square_search::naive;
+use leetcode_largest_rectangle::strategies::histogram_strategy;
+use leetcode_largest_rectangle::{compute_area_of_largest_rectangle, ConcreteHistogram, Histogram};
+
+const CASES: u64 = 2000;
+
+#[test]
+fn test_search_matches_naive_search() {
+    histogram_strategy(40, 60).assert_holds(CASES, |histogram| {
+        compute_area_of_largest_rectangle(histogram)
+            == naive::compute_area_of_largest_rectangle(histogram)
+    });
+}
+
+#[test]
+fn test_area_is_unchanged_by_reversal() {
+    histogram_strategy(40, 1000).assert_holds(CASES, |histogram| {
+        compute_area_of_largest_rectangle(&histogram.reversed())
+            == compute_area_of_largest_rectangle(histogram)
+    });
+}
+
+#[test]
+fn test_appending_a_zero_bar_never_lowers_the_area() {
+    histogram_strategy(40, 1000).assert_holds(CASES, |histogram| {
+        let mut appended = histogram.heights().to_vec();
+        appended.push(0);
+        compute_area_of_largest_rectangle(&ConcreteHistogram::new(appended))
+            >= compute_area_of_largest_rectangle(histogram)
+    });
+}
+
+#[test]
+fn test_scaling_heights_scales_the_area() {
+    for factor in [0, 1, 2, 7, 1000] {
+        histogram_strategy(40, 1000).assert_holds(CASES / 4, |histogram| {
+            compute_area_of_largest_rectangle(&histogram.scale(factor))
+                == factor as u64 * compute_area_of_largest_rectangle(histogram)
+        });
+    }
+}
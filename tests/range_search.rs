@@ -0,0 +1,94 @@
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::range_search::RangeLargestRectangle;
+use leetcode_largest_rectangle::{square_search, ConcreteHistogram, Rectangle};
+
+#[test]
+fn test_query_whole_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let range_search = RangeLargestRectangle::new(&histogram);
+    assert_eq!(
+        range_search.query(0, 6),
+        Some(Rectangle {
+            left: 2,
+            right: 3,
+            height: 5,
+            area: 10
+        })
+    );
+}
+
+#[test]
+fn test_query_sub_interval() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let range_search = RangeLargestRectangle::new(&histogram);
+    assert_eq!(
+        range_search.query(3, 6),
+        Some(Rectangle {
+            left: 3,
+            right: 3,
+            height: 6,
+            area: 6
+        })
+    );
+    assert_eq!(
+        range_search.query(0, 2),
+        Some(Rectangle {
+            left: 0,
+            right: 0,
+            height: 2,
+            area: 2
+        })
+    );
+}
+
+#[test]
+fn test_query_empty_or_out_of_range_interval() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let range_search = RangeLargestRectangle::new(&histogram);
+    assert_eq!(range_search.query(3, 3), None);
+    assert_eq!(range_search.query(4, 2), None);
+    assert_eq!(range_search.query(2, 7), None);
+}
+
+#[test]
+fn test_query_on_empty_histogram() {
+    let histogram = ConcreteHistogram::new(vec![]);
+    let range_search = RangeLargestRectangle::new(&histogram);
+    assert_eq!(range_search.query(0, 0), None);
+    assert_eq!(range_search.query(0, 1), None);
+}
+
+#[test]
+fn test_query_zero_bars() {
+    let histogram = ConcreteHistogram::new(vec![0, 0, 3, 0]);
+    let range_search = RangeLargestRectangle::new(&histogram);
+    assert_eq!(range_search.query(0, 2), None);
+    assert_eq!(range_search.query(1, 4).map(|r| r.area), Some(3));
+}
+
+#[test]
+fn test_queries_match_solver_on_copied_bars() {
+    let mut rng = Rng::new(18);
+    for _ in 0..200 {
+        let bars = rng.bars(40, 6);
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let range_search = RangeLargestRectangle::new(&histogram);
+        for _ in 0..20 {
+            let left = rng.below(bars.len() + 1);
+            let right = left + rng.below(bars.len() - left + 1);
+            let copied = ConcreteHistogram::new(bars[left..right].to_vec());
+            let expected = square_search::compute_largest_rectangle(&copied).map(|r| Rectangle {
+                left: r.left + left,
+                right: r.right + left,
+                ..r
+            });
+            assert_eq!(
+                range_search.query(left, right),
+                expected,
+                "{bars:?} {left}..{right}"
+            );
+        }
+    }
+}
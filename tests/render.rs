@@ -0,0 +1,445 @@
+use leetcode_largest_rectangle::histogram_concrete::OutlineError;
+use leetcode_largest_rectangle::render::{
+    self, AsciiOptions, Overflow, RectangleMisfit, SvgOptions,
+};
+use leetcode_largest_rectangle::{square_search, ConcreteHistogram, Rectangle};
+
+#[test]
+fn test_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let expected = concat!(
+        "   #\n", "  ##\n", "  ##\n", "  ## #\n", "# ####\n", "######\n", "------\n", "0    5\n",
+    );
+    assert_eq!(render::to_ascii(&histogram), expected);
+    assert_eq!(histogram.to_string(), expected);
+}
+
+#[test]
+fn test_zero_and_negative_bars_are_blank() {
+    let histogram = ConcreteHistogram::new(vec![0, 2, -3, 1, 0]);
+    let expected = concat!(" #\n", " # #\n", "-----\n", "0\n",);
+    assert_eq!(render::to_ascii(&histogram), expected);
+}
+
+#[test]
+fn test_flat_and_empty_histograms() {
+    assert_eq!(
+        render::to_ascii(&ConcreteHistogram::new(vec![0, 0, 0])),
+        "---\n0\n"
+    );
+    assert_eq!(render::to_ascii(&ConcreteHistogram::new(vec![])), "");
+}
+
+#[test]
+fn test_labels_that_would_collide_are_skipped() {
+    let histogram = ConcreteHistogram::new(vec![1; 12]);
+    let options = AsciiOptions {
+        label_interval: 2,
+        ..AsciiOptions::default()
+    };
+    let expected = concat!("############\n", "------------\n", "0 2 4 6 8 10\n",);
+    assert_eq!(
+        render::to_ascii_with_options(&histogram, &options),
+        expected
+    );
+    let unlabelled = AsciiOptions {
+        label_interval: 0,
+        ..AsciiOptions::default()
+    };
+    assert_eq!(
+        render::to_ascii_with_options(&histogram, &unlabelled),
+        "############\n------------\n"
+    );
+}
+
+#[test]
+fn test_wide_histogram_wraps() {
+    let histogram = ConcreteHistogram::new(vec![1, 2, 3, 1, 2, 3, 1]);
+    let options = AsciiOptions {
+        max_columns: Some(3),
+        overflow: Overflow::Wrap,
+        label_interval: 3,
+    };
+    let expected = concat!(
+        "  #\n", " ##\n", "###\n", "---\n", "0\n", "\n", "  #\n", " ##\n", "###\n", "---\n", "3\n",
+        "\n", "#\n", "-\n", "6\n",
+    );
+    assert_eq!(
+        render::to_ascii_with_options(&histogram, &options),
+        expected
+    );
+}
+
+#[test]
+fn test_wide_histogram_truncates() {
+    let histogram = ConcreteHistogram::new(vec![1, 2, 3, 1, 9]);
+    let options = AsciiOptions {
+        max_columns: Some(3),
+        overflow: Overflow::Truncate,
+        label_interval: 5,
+    };
+    let expected = concat!("  #\n", " ##\n", "###\n", "---...\n", "0\n",);
+    assert_eq!(
+        render::to_ascii_with_options(&histogram, &options),
+        expected
+    );
+}
+
+#[test]
+fn test_very_wide_histogram_renders() {
+    let histogram = ConcreteHistogram::new(vec![1; 100_000]);
+    let rendered = render::to_ascii(&histogram);
+    assert_eq!(
+        rendered.lines().filter(|line| line.is_empty()).count(),
+        1249
+    );
+    let truncated = render::to_ascii_with_options(
+        &histogram,
+        &AsciiOptions {
+            overflow: Overflow::Truncate,
+            ..AsciiOptions::default()
+        },
+    );
+    assert_eq!(truncated.lines().count(), 3);
+}
+
+#[test]
+#[should_panic(expected = "max_columns must be non-zero")]
+fn test_zero_columns_panics() {
+    let options = AsciiOptions {
+        max_columns: Some(0),
+        ..AsciiOptions::default()
+    };
+    render::to_ascii_with_options(&ConcreteHistogram::new(vec![1]), &options);
+}
+
+#[test]
+fn test_largest_rectangle_is_highlighted() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let rectangle = square_search::compute_largest_rectangle(&histogram).unwrap();
+    let expected = concat!(
+        "   #\n", "  **\n", "  **\n", "  ** #\n", "# **##\n", "##**##\n", "------\n", "0    5\n",
+    );
+    assert_eq!(
+        render::to_ascii_with_rectangle(&histogram, &rectangle).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_highlighting_keeps_the_plain_layout() {
+    let histogram = ConcreteHistogram::new(vec![3, 0, 4, 4, 1]);
+    let rectangle = Rectangle {
+        left: 2,
+        right: 4,
+        height: 1,
+        area: 3,
+    };
+    let highlighted = render::to_ascii_with_rectangle(&histogram, &rectangle).unwrap();
+    assert_eq!(highlighted.replace('*', "#"), render::to_ascii(&histogram));
+    assert_eq!(highlighted.matches('*').count(), 3);
+}
+
+#[test]
+fn test_highlighting_with_options() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let rectangle = square_search::compute_largest_rectangle(&histogram).unwrap();
+    let options = AsciiOptions {
+        max_columns: Some(3),
+        overflow: Overflow::Truncate,
+        label_interval: 2,
+    };
+    let expected = concat!("  *\n", "  *\n", "  *\n", "# *\n", "##*\n", "---...\n", "0 2\n",);
+    assert_eq!(
+        render::to_ascii_with_rectangle_and_options(&histogram, &rectangle, &options).unwrap(),
+        expected
+    );
+    let options = AsciiOptions {
+        overflow: Overflow::Wrap,
+        ..options
+    };
+    let wrapped =
+        render::to_ascii_with_rectangle_and_options(&histogram, &rectangle, &options).unwrap();
+    assert_eq!(wrapped.matches('*').count(), 10);
+    assert_eq!(
+        wrapped.replace('*', "#"),
+        render::to_ascii_with_options(&histogram, &options)
+    );
+}
+
+#[test]
+fn test_misfitting_rectangles_are_rejected() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let too_tall = Rectangle {
+        left: 2,
+        right: 4,
+        height: 5,
+        area: 15,
+    };
+    assert_eq!(
+        render::to_ascii_with_rectangle(&histogram, &too_tall),
+        Err(RectangleMisfit::AboveBar { bar_index: 4 })
+    );
+    let too_wide = Rectangle {
+        left: 4,
+        right: 6,
+        height: 1,
+        area: 3,
+    };
+    let error = render::to_ascii_with_rectangle(&histogram, &too_wide).unwrap_err();
+    assert_eq!(
+        error,
+        RectangleMisfit::OutOfBounds {
+            left: 4,
+            right: 6,
+            width: 6
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "columns 4..=6 do not fit a histogram of width 6"
+    );
+}
+
+#[test]
+fn test_rectangles_that_cover_nothing_or_misstate_their_area_are_rejected() {
+    let histogram = ConcreteHistogram::new(vec![0, -2, 3, 3]);
+    for left in [0, 1] {
+        let flat = Rectangle {
+            left,
+            right: left,
+            height: 0,
+            area: 0,
+        };
+        assert_eq!(
+            render::to_ascii_with_rectangle(&histogram, &flat),
+            Err(RectangleMisfit::NotPositive { height: 0 })
+        );
+    }
+    let over_negative_bar = Rectangle {
+        left: 1,
+        right: 2,
+        height: 1,
+        area: 2,
+    };
+    assert_eq!(
+        render::to_ascii_with_rectangle(&histogram, &over_negative_bar),
+        Err(RectangleMisfit::AboveBar { bar_index: 1 })
+    );
+    let misstated = Rectangle {
+        left: 2,
+        right: 3,
+        height: 3,
+        area: 5,
+    };
+    let error = render::to_ascii_with_rectangle(&histogram, &misstated).unwrap_err();
+    assert_eq!(
+        error,
+        RectangleMisfit::WrongArea {
+            area: 5,
+            expected: 6
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "the rectangle has area 5, but its height and width make 6"
+    );
+}
+
+/// Checks that `document` is a single well-formed XML element, returning the
+/// attributes of every element in document order.
+fn parse_xml(document: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut elements = Vec::new();
+    let mut open = Vec::new();
+    let mut rest = document.trim();
+    assert!(rest.starts_with('<'), "document must start with a tag");
+    while let Some(start) = rest.find('<') {
+        assert!(
+            rest[..start].trim().is_empty(),
+            "unexpected text {:?}",
+            &rest[..start]
+        );
+        let end = start + rest[start..].find('>').expect("unterminated tag");
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+        if let Some(name) = tag.strip_prefix('/') {
+            assert_eq!(open.pop().as_deref(), Some(name), "mismatched closing tag");
+            if open.is_empty() {
+                assert!(rest.trim().is_empty(), "content after the root element");
+            }
+            continue;
+        }
+        assert!(
+            !open.is_empty() || elements.is_empty(),
+            "more than one root element"
+        );
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let (name, mut attributes_text) = tag.split_once(' ').unwrap_or((tag, ""));
+        let mut attributes = Vec::new();
+        while !attributes_text.trim().is_empty() {
+            let (key, value_text) = attributes_text.trim_start().split_once("=\"").unwrap();
+            let (value, remainder) = value_text.split_once('"').unwrap();
+            assert!(!value.contains('<'), "unescaped < in {value:?}");
+            assert!(
+                value.split('&').skip(1).all(|entity| {
+                    ["amp;", "lt;", "gt;", "quot;", "apos;"]
+                        .iter()
+                        .any(|name| entity.starts_with(name))
+                }),
+                "unescaped & in {value:?}"
+            );
+            assert!(
+                attributes.iter().all(|(seen, _)| seen != key),
+                "duplicate attribute {key}"
+            );
+            attributes.push((key.to_string(), value.to_string()));
+            attributes_text = remainder;
+        }
+        elements.push((name.to_string(), attributes));
+        if !self_closing {
+            open.push(name.to_string());
+        }
+    }
+    assert!(open.is_empty(), "unclosed elements {open:?}");
+    elements
+}
+
+fn attribute<'a>(attributes: &'a [(String, String)], key: &str) -> &'a str {
+    &attributes.iter().find(|(seen, _)| seen == key).unwrap().1
+}
+
+#[test]
+fn test_svg_of_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let rectangle = square_search::compute_largest_rectangle(&histogram).unwrap();
+    let options = SvgOptions {
+        bar_width: 10,
+        unit_height: 5,
+        margin: 2,
+        ..SvgOptions::default()
+    };
+    let elements = parse_xml(&render::to_svg(&histogram, Some(&rectangle), &options));
+    assert_eq!(elements.len(), 8);
+    let (name, svg) = &elements[0];
+    assert_eq!(name, "svg");
+    assert_eq!(attribute(svg, "width"), "64");
+    assert_eq!(attribute(svg, "height"), "34");
+    let bars: Vec<_> = elements[1..7]
+        .iter()
+        .map(|(_, attributes)| {
+            ["x", "y", "width", "height"]
+                .map(|key| attribute(attributes, key).parse::<u32>().unwrap())
+        })
+        .collect();
+    assert_eq!(bars[0], [2, 22, 10, 10]);
+    assert_eq!(bars[3], [32, 2, 10, 30]);
+    let (_, overlay) = &elements[7];
+    assert_eq!(attribute(overlay, "x"), "22");
+    assert_eq!(attribute(overlay, "y"), "7");
+    assert_eq!(attribute(overlay, "width"), "20");
+    assert_eq!(attribute(overlay, "height"), "25");
+    assert_eq!(attribute(overlay, "fill"), "orange");
+    assert_eq!(attribute(overlay, "fill-opacity"), "0.5");
+}
+
+#[test]
+fn test_svg_leaves_out_empty_bars() {
+    let histogram = ConcreteHistogram::new(vec![0, 3, -1, 2]);
+    let elements = parse_xml(&render::to_svg(&histogram, None, &SvgOptions::default()));
+    assert_eq!(elements.len(), 3);
+}
+
+#[test]
+fn test_svg_of_empty_histogram() {
+    let elements = parse_xml(&render::to_svg(
+        &ConcreteHistogram::new(vec![]),
+        None,
+        &SvgOptions::default(),
+    ));
+    assert_eq!(elements.len(), 1);
+    assert_eq!(attribute(&elements[0].1, "width"), "20");
+    assert_eq!(attribute(&elements[0].1, "height"), "20");
+}
+
+#[test]
+fn test_svg_escapes_colors() {
+    let options = SvgOptions {
+        bar_fill: "a&b\"<c".to_string(),
+        ..SvgOptions::default()
+    };
+    let elements = parse_xml(&render::to_svg(
+        &ConcreteHistogram::new(vec![1]),
+        None,
+        &options,
+    ));
+    assert_eq!(attribute(&elements[1].1, "fill"), "a&amp;b&quot;&lt;c");
+}
+
+#[test]
+fn test_outline_merges_runs_of_equal_bars() {
+    let histogram = ConcreteHistogram::new(vec![2, 2, 1, 5, 5, 5, 3]);
+    assert_eq!(
+        render::outline(&histogram),
+        vec![(0, 2), (2, 1), (3, 5), (6, 3), (7, 0)]
+    );
+}
+
+#[test]
+fn test_outline_keeps_leading_and_trailing_ground() {
+    let histogram = ConcreteHistogram::new(vec![0, 0, 4, 0, 0]);
+    assert_eq!(
+        render::outline(&histogram),
+        vec![(0, 0), (2, 4), (3, 0), (5, 0)]
+    );
+}
+
+#[test]
+fn test_outline_of_flat_histogram_has_two_points() {
+    let histogram = ConcreteHistogram::new(vec![3; 10]);
+    assert_eq!(render::outline(&histogram), vec![(0, 3), (10, 0)]);
+}
+
+#[test]
+fn test_outline_of_empty_histogram_is_empty() {
+    let histogram = ConcreteHistogram::new(vec![]);
+    assert_eq!(render::outline(&histogram), vec![]);
+}
+
+#[test]
+fn test_from_outline_inverts_outline() {
+    for bars in [
+        vec![],
+        vec![0],
+        vec![7],
+        vec![2, 1, 5, 6, 2, 3],
+        vec![0, 0, 4, 4, 0, 0],
+        vec![-1, -1, 3, 0],
+    ] {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let rebuilt = ConcreteHistogram::from_outline(&render::outline(&histogram)).unwrap();
+        assert_eq!(rebuilt.heights(), &bars[..]);
+    }
+}
+
+#[test]
+fn test_from_outline_fills_leading_ground() {
+    let histogram = ConcreteHistogram::from_outline(&[(2, 3), (4, 0)]).unwrap();
+    assert_eq!(histogram.heights(), &[0, 0, 3, 3]);
+}
+
+#[test]
+fn test_from_outline_rejects_invalid_outlines() {
+    assert_eq!(
+        ConcreteHistogram::from_outline(&[(0, 1), (3, 2), (3, 0)]).err(),
+        Some(OutlineError::NotIncreasing { index: 2 })
+    );
+    assert_eq!(
+        ConcreteHistogram::from_outline(&[(4, 1), (2, 0)]).err(),
+        Some(OutlineError::NotIncreasing { index: 1 })
+    );
+    assert_eq!(
+        ConcreteHistogram::from_outline(&[(0, 1), (3, 2)]).err(),
+        Some(OutlineError::OpenEnd { height: 2 })
+    );
+}
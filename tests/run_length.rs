@@ -0,0 +1,95 @@
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::square_search::{
+    self, compute_area_of_largest_rectangle_run_length,
+};
+use leetcode_largest_rectangle::{ConcreteHistogram, Histogram, RunLengthHistogram};
+
+fn expand(runs: &[(i32, u64)]) -> Vec<i32> {
+    runs.iter()
+        .flat_map(|&(height, length)| std::iter::repeat_n(height, length as usize))
+        .collect()
+}
+
+#[test]
+fn test_runs_are_merged_and_empty_runs_dropped() {
+    let histogram = RunLengthHistogram::new(vec![(2, 1), (2, 3), (5, 0), (1, 2), (0, 0)]);
+    assert_eq!(histogram.runs(), &[(2, 4), (1, 2)]);
+    assert_eq!(histogram.width(), 6);
+    assert_eq!(
+        histogram.iter_heights().collect::<Vec<_>>(),
+        vec![2, 2, 2, 2, 1, 1]
+    );
+    assert_eq!(histogram.height_at(3), 2);
+    assert_eq!(histogram.height_at(4), 1);
+}
+
+#[test]
+fn test_run_length_leetcode_example() {
+    let histogram = RunLengthHistogram::new(vec![(2, 1), (1, 1), (5, 1), (6, 1), (2, 1), (3, 1)]);
+    assert_eq!(compute_area_of_largest_rectangle_run_length(&histogram), 10);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram),
+        10
+    );
+}
+
+#[test]
+fn test_long_plateaus() {
+    let histogram = RunLengthHistogram::new(vec![(3, 4_000_000), (7, 1_000_000), (3, 4_000_000)]);
+    assert_eq!(
+        compute_area_of_largest_rectangle_run_length(&histogram),
+        27_000_000
+    );
+    let huge = RunLengthHistogram::new(vec![(i32::MAX, u64::MAX / 2), (1, 5)]);
+    assert_eq!(
+        compute_area_of_largest_rectangle_run_length(&huge),
+        i32::MAX as u128 * (u64::MAX / 2) as u128
+    );
+}
+
+#[test]
+fn test_to_run_length_round_trip() {
+    let histogram = ConcreteHistogram::new(vec![4, 4, 0, 0, 0, 4, 1, 1]);
+    let run_length = histogram.to_run_length();
+    assert_eq!(run_length.runs(), &[(4, 2), (0, 3), (4, 1), (1, 2)]);
+    assert_eq!(
+        run_length.iter_heights().collect::<Vec<_>>(),
+        histogram.heights()
+    );
+    assert!(ConcreteHistogram::new(vec![]).to_run_length().is_empty());
+}
+
+#[test]
+fn test_random_runs_match_expanded_histogram() {
+    let mut rng = Rng::new(49);
+    for _ in 0..2000 {
+        let runs: Vec<(i32, u64)> = (0..rng.below(12))
+            .map(|_| (rng.below(7) as i32 - 1, rng.below(6) as u64))
+            .collect();
+        let bars = expand(&runs);
+        let histogram = RunLengthHistogram::new(runs.clone());
+        let expected =
+            square_search::compute_area_of_largest_rectangle(&ConcreteHistogram::new(bars.clone()));
+        assert_eq!(
+            compute_area_of_largest_rectangle_run_length(&histogram),
+            u128::from(expected),
+            "{runs:?}"
+        );
+        assert_eq!(histogram.width(), bars.len());
+        assert!((0..bars.len()).all(|x_pos| histogram.height_at(x_pos) == bars[x_pos]));
+    }
+}
+
+#[test]
+#[should_panic(expected = "bar 6 is out of range for a histogram of width 6")]
+fn test_height_past_the_last_run_panics() {
+    RunLengthHistogram::new(vec![(2, 4), (1, 2)]).height_at(6);
+}
+
+#[test]
+#[should_panic(expected = "run lengths overflow the width of a histogram")]
+fn test_overflowing_run_lengths_panic() {
+    RunLengthHistogram::new(vec![(1, u64::MAX), (2, 1)]);
+}
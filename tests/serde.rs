@@ -0,0 +1,270 @@
+#![cfg(feature = "serde")]
+
+use leetcode_largest_rectangle::square_search::{self, SearchEvent, SearchTrace};
+use leetcode_largest_rectangle::{ConcreteHistogram, Rectangle};
+use serde::de::value::{Error, MapDeserializer, SeqDeserializer};
+use serde::de::{DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{Impossible, SerializeSeq, SerializeStruct};
+use serde::{forward_to_deserialize_any, Deserializer, Serialize, Serializer};
+
+/// A self-describing data model just rich enough for the library's types, so
+/// the impls can be round-tripped without a particular format.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i64),
+    Str(String),
+    Seq(Vec<Value>),
+    Struct(Vec<(&'static str, Value)>),
+}
+
+struct ValueSerializer;
+
+struct SeqSerializer(Vec<Value>);
+
+struct StructSerializer(Vec<(&'static str, Value)>);
+
+macro_rules! unsupported {
+    ($($method:ident($($arg:ty),*) -> $ok:ty;)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<$ok, Error> {
+                Err(serde::ser::Error::custom(stringify!($method)))
+            }
+        )*
+    };
+}
+
+impl Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = Impossible<Value, Error>;
+    type SerializeTupleStruct = Impossible<Value, Error>;
+    type SerializeTupleVariant = Impossible<Value, Error>;
+    type SerializeMap = Impossible<Value, Error>;
+    type SerializeStruct = StructSerializer;
+    type SerializeStructVariant = Impossible<Value, Error>;
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(Value::Int(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(Value::Int(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(Value::Int(v.try_into().map_err(serde::ser::Error::custom)?))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::Str(v.to_owned()))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, Error> {
+        Ok(SeqSerializer(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_struct(self, _: &'static str, len: usize) -> Result<StructSerializer, Error> {
+        Ok(StructSerializer(Vec::with_capacity(len)))
+    }
+
+    unsupported! {
+        serialize_bool(bool) -> Value;
+        serialize_i8(i8) -> Value;
+        serialize_i16(i16) -> Value;
+        serialize_u8(u8) -> Value;
+        serialize_u16(u16) -> Value;
+        serialize_u32(u32) -> Value;
+        serialize_f32(f32) -> Value;
+        serialize_f64(f64) -> Value;
+        serialize_char(char) -> Value;
+        serialize_bytes(&[u8]) -> Value;
+        serialize_none() -> Value;
+        serialize_unit() -> Value;
+        serialize_unit_struct(&'static str) -> Value;
+        serialize_unit_variant(&'static str, u32, &'static str) -> Value;
+        serialize_tuple(usize) -> Impossible<Value, Error>;
+        serialize_tuple_struct(&'static str, usize) -> Impossible<Value, Error>;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Impossible<Value, Error>;
+        serialize_map(Option<usize>) -> Impossible<Value, Error>;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Impossible<Value, Error>;
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _: &T) -> Result<Value, Error> {
+        Err(serde::ser::Error::custom("serialize_some"))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Value, Error> {
+        Err(serde::ser::Error::custom("serialize_newtype_struct"))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Value, Error> {
+        Err(serde::ser::Error::custom("serialize_newtype_variant"))
+    }
+}
+
+impl SerializeSeq for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.0.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Seq(self.0))
+    }
+}
+
+impl SerializeStruct for StructSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.0.push((key, value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Struct(self.0))
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Value::Int(v) => visitor.visit_i64(v),
+            Value::Str(v) => visitor.visit_string(v),
+            Value::Seq(values) => visitor.visit_seq(SeqDeserializer::new(values.into_iter())),
+            Value::Struct(fields) => visitor.visit_map(MapDeserializer::new(fields.into_iter())),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Value {
+    value.serialize(ValueSerializer).unwrap()
+}
+
+fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, Error> {
+    T::deserialize(value)
+}
+
+fn ints(values: &[i64]) -> Value {
+    Value::Seq(values.iter().map(|&v| Value::Int(v)).collect())
+}
+
+#[test]
+fn test_histogram_serializes_as_plain_sequence() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(to_value(&histogram), ints(&[2, 1, 5, 6, 2, 3]));
+}
+
+#[test]
+fn test_histogram_round_trip() {
+    for bars in [
+        vec![],
+        vec![0],
+        vec![2, 1, 5, 6, 2, 3],
+        vec![i32::MAX, 0, 7],
+    ] {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let restored: ConcreteHistogram = from_value(to_value(&histogram)).unwrap();
+        assert_eq!(restored.heights(), &bars[..]);
+        assert_eq!(
+            square_search::compute_largest_rectangle(&restored),
+            square_search::compute_largest_rectangle(&histogram)
+        );
+    }
+}
+
+#[test]
+fn test_histogram_with_wider_heights_round_trip() {
+    let histogram = ConcreteHistogram::from_bars(vec![3_000_000_000_i64, 1, 4]);
+    let restored: ConcreteHistogram<i64> = from_value(to_value(&histogram)).unwrap();
+    assert_eq!(restored.heights(), histogram.heights());
+}
+
+#[test]
+fn test_negative_height_is_rejected() {
+    let error = from_value::<ConcreteHistogram>(ints(&[2, 1, -5, 6]))
+        .err()
+        .unwrap();
+    assert_eq!(error.to_string(), "bar 2 has a negative height");
+}
+
+#[test]
+fn test_non_sequence_is_rejected() {
+    assert!(from_value::<ConcreteHistogram>(Value::Int(3)).is_err());
+}
+
+#[test]
+fn test_rectangle_round_trip() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let rectangle = square_search::compute_largest_rectangle(&histogram).unwrap();
+    let value = to_value(&rectangle);
+    assert_eq!(
+        value,
+        Value::Struct(vec![
+            ("left", Value::Int(2)),
+            ("right", Value::Int(3)),
+            ("height", Value::Int(5)),
+            ("area", Value::Int(10)),
+        ])
+    );
+    let restored: Rectangle = from_value(value).unwrap();
+    assert_eq!(restored, rectangle);
+    assert_eq!(restored.width(), 2);
+}
+
+#[test]
+fn test_search_event_is_tagged_with_its_kind() {
+    let event = SearchEvent::Visit { pos: 4, height: 2 };
+    assert_eq!(
+        to_value(&event),
+        Value::Struct(vec![
+            ("kind", Value::Str("Visit".to_owned())),
+            ("pos", Value::Int(4)),
+            ("height", Value::Int(2)),
+        ])
+    );
+}
+
+#[test]
+fn test_search_trace_round_trip() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let (rectangle, trace) = square_search::compute_with_trace(&histogram);
+    let restored: SearchTrace = from_value(to_value(&trace)).unwrap();
+    assert_eq!(restored, trace);
+    assert_eq!(restored.replay(), rectangle);
+}
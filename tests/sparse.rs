@@ -0,0 +1,96 @@
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::square_search::{self, compute_largest_rectangle_sparse};
+use leetcode_largest_rectangle::{ConcreteHistogram, Histogram, Rectangle, SparseHistogram};
+
+#[test]
+fn test_missing_bars_have_height_zero() {
+    let histogram = SparseHistogram::new(10, vec![(7, 3), (2, 5)]);
+    assert_eq!(histogram.width(), 10);
+    assert_eq!(histogram.nonzero_bars(), &[(2, 5), (7, 3)]);
+    assert_eq!(
+        histogram.iter_heights().collect::<Vec<_>>(),
+        vec![0, 0, 5, 0, 0, 0, 0, 3, 0, 0]
+    );
+}
+
+#[test]
+fn test_sparse_leetcode_example() {
+    let histogram = SparseHistogram::new(
+        20,
+        vec![(12, 2), (13, 1), (14, 5), (15, 6), (16, 2), (17, 3)],
+    );
+    assert_eq!(
+        compute_largest_rectangle_sparse(&histogram),
+        Some(Rectangle {
+            left: 14,
+            right: 15,
+            height: 5,
+            area: 10
+        })
+    );
+}
+
+#[test]
+fn test_sparse_without_positive_bars() {
+    assert_eq!(
+        compute_largest_rectangle_sparse(&SparseHistogram::new(0, vec![])),
+        None
+    );
+    let histogram = SparseHistogram::new(5, vec![(1, -2), (3, 0)]);
+    assert_eq!(compute_largest_rectangle_sparse(&histogram), None);
+}
+
+#[test]
+fn test_random_sparse_matches_dense() {
+    let mut rng = Rng::new(50);
+    for _ in 0..2000 {
+        let width = rng.below(30);
+        let mut positions: Vec<u64> = (0..width as u64).collect();
+        let mut nonzero = Vec::new();
+        for _ in 0..rng.below(width + 1) {
+            let x_pos = positions.swap_remove(rng.below(positions.len()));
+            nonzero.push((x_pos, rng.below(8) as i32 - 1));
+        }
+        let sparse = SparseHistogram::new(width as u64, nonzero.clone());
+        let dense = ConcreteHistogram::new(sparse.iter_heights().collect());
+        assert_eq!(
+            compute_largest_rectangle_sparse(&sparse),
+            square_search::compute_largest_rectangle(&dense),
+            "{width} {nonzero:?}"
+        );
+    }
+}
+
+#[test]
+fn test_width_too_large_to_materialize() {
+    let width = 1 << 50;
+    let mut nonzero: Vec<(u64, i32)> = (0..5000).map(|i| (i * 1_000_003, 7)).collect();
+    nonzero.extend((0..100).map(|i| ((1 << 49) + i, 4)));
+    nonzero.push((width - 1, 100));
+    let histogram = SparseHistogram::new(width, nonzero);
+    assert_eq!(histogram.height_at((1 << 49) + 50), 4);
+    assert_eq!(histogram.height_at(1 << 48), 0);
+    assert_eq!(
+        compute_largest_rectangle_sparse(&histogram),
+        Some(Rectangle {
+            left: 1 << 49,
+            right: (1 << 49) + 99,
+            height: 4,
+            area: 400
+        })
+    );
+}
+
+#[test]
+#[should_panic(expected = "bar 3 is listed more than once")]
+fn test_duplicate_bars_panic() {
+    SparseHistogram::new(5, vec![(3, 1), (1, 2), (3, 4)]);
+}
+
+#[test]
+#[should_panic(expected = "bar 5 is out of range for a histogram of width 5")]
+fn test_bar_beyond_width_panics() {
+    SparseHistogram::new(5, vec![(5, 1)]);
+}
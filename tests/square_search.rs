@@ -0,0 +1,2214 @@
+mod common;
+
+use common::{all_histograms, Rng};
+use leetcode_largest_rectangle::generate::{self, Pattern};
+use leetcode_largest_rectangle::histogram::FnHistogram;
+use leetcode_largest_rectangle::square_search::{
+    self, naive, Algorithm, AreaOverflow, BatchSolver, LargestRectangleSearcher, NanHeight,
+    NegativeHeight, NegativePolicy, SearchError, SearchOptions, StreamingSearcher, TieBreak,
+};
+use leetcode_largest_rectangle::{ConcreteHistogram, Histogram, Rectangle};
+use std::cell::Cell;
+use std::cmp;
+
+#[test]
+fn test_two_bar_histogram() {
+    let histogram = ConcreteHistogram::new(vec![2, 3]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 4);
+}
+
+#[test]
+fn test_constant_histogram() {
+    let histogram = ConcreteHistogram::new(vec![1, 1, 1]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 3);
+}
+
+#[test]
+fn test_histogram_with_insignificant_peak() {
+    let histogram = ConcreteHistogram::new(vec![1, 2, 1]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 3);
+}
+
+#[test]
+fn test_histogram_with_trough() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 2]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 3);
+}
+
+#[test]
+fn test_histogram_with_significant_peak() {
+    let histogram = ConcreteHistogram::new(vec![1, 4, 1]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 4);
+}
+
+#[test]
+fn test_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 10);
+}
+
+#[test]
+fn test_leetcode_example_as_array() {
+    let area_of_largest_rectangle =
+        square_search::compute_area_of_largest_rectangle(&[2, 1, 5, 6, 2, 3]);
+    assert_eq!(area_of_largest_rectangle, 10);
+}
+
+#[test]
+fn test_histogram_with_trough_as_slice() {
+    let bars = vec![2, 1, 2];
+    let area_of_largest_rectangle =
+        square_search::compute_area_of_largest_rectangle(bars.as_slice());
+    assert_eq!(area_of_largest_rectangle, 3);
+}
+
+#[test]
+fn test_crate_root_reexports() {
+    let histogram = leetcode_largest_rectangle::ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let area_of_largest_rectangle =
+        leetcode_largest_rectangle::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 10);
+}
+
+#[test]
+fn test_largest_rectangle_in_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let largest_rectangle = square_search::compute_largest_rectangle(&histogram);
+    assert_eq!(
+        largest_rectangle,
+        Some(Rectangle {
+            left: 2,
+            right: 3,
+            height: 5,
+            area: 10
+        })
+    );
+}
+
+#[test]
+fn test_largest_rectangle_in_plateau() {
+    let histogram = ConcreteHistogram::new(vec![3, 3, 3]);
+    let largest_rectangle = square_search::compute_largest_rectangle(&histogram);
+    assert_eq!(
+        largest_rectangle,
+        Some(Rectangle {
+            left: 0,
+            right: 2,
+            height: 3,
+            area: 9
+        })
+    );
+}
+
+#[test]
+fn test_largest_rectangle_keeps_left_edge_of_equal_height_run() {
+    let histogram = ConcreteHistogram::new(vec![1, 3, 2, 3, 2, 1]);
+    let largest_rectangle = square_search::compute_largest_rectangle(&histogram);
+    assert_eq!(
+        largest_rectangle,
+        Some(Rectangle {
+            left: 1,
+            right: 4,
+            height: 2,
+            area: 8
+        })
+    );
+}
+
+#[test]
+fn test_largest_rectangle_tie_prefers_leftmost() {
+    let histogram = ConcreteHistogram::new(vec![3, 1, 3]);
+    let largest_rectangle = square_search::compute_largest_rectangle(&histogram);
+    assert_eq!(
+        largest_rectangle,
+        Some(Rectangle {
+            left: 0,
+            right: 0,
+            height: 3,
+            area: 3
+        })
+    );
+}
+
+#[test]
+fn test_largest_rectangle_tie_between_tall_and_wide() {
+    let histogram = ConcreteHistogram::new(vec![1, 4, 1, 1]);
+    let largest_rectangle = square_search::compute_largest_rectangle(&histogram);
+    assert_eq!(
+        largest_rectangle,
+        Some(Rectangle {
+            left: 0,
+            right: 3,
+            height: 1,
+            area: 4
+        })
+    );
+}
+
+#[test]
+fn test_empty_histogram() {
+    let histogram = ConcreteHistogram::new(vec![]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 0);
+    assert_eq!(square_search::compute_largest_rectangle(&histogram), None);
+}
+
+#[test]
+fn test_single_zero_bar_histogram() {
+    let histogram = ConcreteHistogram::new(vec![0]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 0);
+    assert_eq!(square_search::compute_largest_rectangle(&histogram), None);
+}
+
+#[test]
+fn test_all_zero_histogram() {
+    let histogram = ConcreteHistogram::new(vec![0, 0, 0]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 0);
+    assert_eq!(square_search::compute_largest_rectangle(&histogram), None);
+}
+
+#[test]
+fn test_zero_bars_separate_rectangles() {
+    let histogram = ConcreteHistogram::new(vec![0, 2, 0, 3, 0]);
+    let largest_rectangle = square_search::compute_largest_rectangle(&histogram);
+    assert_eq!(
+        largest_rectangle,
+        Some(Rectangle {
+            left: 3,
+            right: 3,
+            height: 3,
+            area: 3
+        })
+    );
+}
+
+#[test]
+fn test_area_larger_than_i32_max() {
+    let histogram = ConcreteHistogram::new(vec![100_000; 100_000]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 10_000_000_000);
+    assert!(area_of_largest_rectangle > i32::MAX as u64);
+}
+
+#[test]
+fn test_wide_rectangle_larger_than_i32_max_after_tall_bar() {
+    let mut bars = vec![i32::MAX];
+    bars.extend(vec![40_000; 100_000]);
+    let histogram = ConcreteHistogram::new(bars);
+    let largest_rectangle = square_search::compute_largest_rectangle(&histogram);
+    assert_eq!(
+        largest_rectangle,
+        Some(Rectangle {
+            left: 0,
+            right: 100_000,
+            height: 40_000,
+            area: 4_000_040_000
+        })
+    );
+}
+
+#[test]
+fn test_checked_area_of_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(square_search::compute_area_checked(&histogram), Ok(10));
+}
+
+#[test]
+fn test_checked_area_of_single_maximal_bar() {
+    let histogram = ConcreteHistogram::new(vec![i32::MAX]);
+    assert_eq!(
+        square_search::compute_area_checked(&histogram),
+        Ok(i32::MAX)
+    );
+}
+
+#[test]
+fn test_checked_area_reports_overflow() {
+    let histogram = ConcreteHistogram::new(vec![1, i32::MAX - 1, i32::MAX, 1]);
+    assert_eq!(
+        square_search::compute_area_checked(&histogram),
+        Err(AreaOverflow {
+            bar_index: 1,
+            width: 2,
+            height: i32::MAX - 1
+        })
+    );
+}
+
+#[test]
+fn test_u8_heights() {
+    let histogram = ConcreteHistogram::from_bars(vec![2u8, 1, 5, 6, 2, 3]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 10u64);
+}
+
+#[test]
+fn test_u8_heights_with_area_beyond_u8() {
+    let histogram = ConcreteHistogram::from_bars(vec![u8::MAX; 1_000]);
+    let largest_rectangle = square_search::compute_largest_rectangle(&histogram);
+    assert_eq!(
+        largest_rectangle,
+        Some(Rectangle {
+            left: 0,
+            right: 999,
+            height: u8::MAX,
+            area: 255_000
+        })
+    );
+}
+
+#[test]
+fn test_u16_heights() {
+    let histogram = ConcreteHistogram::from_bars(vec![1u16, 4, 1]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 4u64);
+}
+
+#[test]
+fn test_i64_heights() {
+    let histogram = ConcreteHistogram::from_bars(vec![2i64, 1, 5, 6, 2, 3]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 10u128);
+}
+
+#[test]
+fn test_i64_heights_with_area_beyond_u64() {
+    let histogram = ConcreteHistogram::from_bars(vec![i64::MAX; 4]);
+    let area_of_largest_rectangle = square_search::compute_area_of_largest_rectangle(&histogram);
+    assert_eq!(area_of_largest_rectangle, 4 * i64::MAX as u128);
+}
+
+#[test]
+fn test_f64_fractional_heights() {
+    let histogram = ConcreteHistogram::from_bars(vec![2.5, 1.1, 5.9]);
+    let area_of_largest_rectangle =
+        square_search::compute_area_of_largest_rectangle_f64(&histogram);
+    assert_eq!(area_of_largest_rectangle, Ok(5.9));
+}
+
+#[test]
+fn test_f64_wide_fractional_rectangle() {
+    let histogram = ConcreteHistogram::from_bars(vec![2.5, 2.5, 1.1, 0.5]);
+    let area_of_largest_rectangle =
+        square_search::compute_area_of_largest_rectangle_f64(&histogram);
+    assert_eq!(area_of_largest_rectangle, Ok(5.0));
+}
+
+#[test]
+fn test_f64_negative_zero_is_zero() {
+    let histogram = ConcreteHistogram::from_bars(vec![-0.0, 1.5, -0.0]);
+    let area_of_largest_rectangle =
+        square_search::compute_area_of_largest_rectangle_f64(&histogram);
+    assert_eq!(area_of_largest_rectangle, Ok(1.5));
+}
+
+#[test]
+fn test_f64_nan_height_is_an_error() {
+    let histogram = ConcreteHistogram::from_bars(vec![2.5, f64::NAN, 5.9]);
+    let area_of_largest_rectangle =
+        square_search::compute_area_of_largest_rectangle_f64(&histogram);
+    assert_eq!(area_of_largest_rectangle, Err(NanHeight { bar_index: 1 }));
+}
+
+#[test]
+fn test_f64_empty_histogram() {
+    let histogram = ConcreteHistogram::<f64>::from_bars(vec![]);
+    let area_of_largest_rectangle =
+        square_search::compute_area_of_largest_rectangle_f64(&histogram);
+    assert_eq!(area_of_largest_rectangle, Ok(0.0));
+}
+
+#[test]
+fn test_count_single_largest_rectangle() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 2]);
+    assert_eq!(square_search::count_largest_rectangles(&histogram), (3, 1));
+}
+
+#[test]
+fn test_count_three_way_tie() {
+    let histogram = ConcreteHistogram::new(vec![3, 1, 3]);
+    assert_eq!(square_search::count_largest_rectangles(&histogram), (3, 3));
+}
+
+#[test]
+fn test_count_plateau_is_one_rectangle() {
+    let histogram = ConcreteHistogram::new(vec![3, 3, 3]);
+    assert_eq!(square_search::count_largest_rectangles(&histogram), (9, 1));
+}
+
+#[test]
+fn test_count_stacked_plateaus() {
+    let histogram = ConcreteHistogram::new(vec![2, 2, 2, 1, 1, 1]);
+    assert_eq!(square_search::count_largest_rectangles(&histogram), (6, 2));
+}
+
+#[test]
+fn test_count_strictly_increasing() {
+    let histogram = ConcreteHistogram::new(vec![1, 2, 3, 4]);
+    assert_eq!(square_search::count_largest_rectangles(&histogram), (6, 2));
+}
+
+#[test]
+fn test_count_strictly_decreasing() {
+    let histogram = ConcreteHistogram::new(vec![5, 4, 3, 2, 1]);
+    assert_eq!(square_search::count_largest_rectangles(&histogram), (9, 1));
+}
+
+#[test]
+fn test_count_empty_histogram() {
+    let histogram = ConcreteHistogram::new(vec![]);
+    assert_eq!(square_search::count_largest_rectangles(&histogram), (0, 0));
+}
+
+#[test]
+fn test_top_k_rectangles_of_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let rectangles = square_search::top_k_rectangles(&histogram, 4);
+    assert_eq!(
+        rectangles,
+        vec![
+            Rectangle {
+                left: 2,
+                right: 3,
+                height: 5,
+                area: 10
+            },
+            Rectangle {
+                left: 2,
+                right: 5,
+                height: 2,
+                area: 8
+            },
+            Rectangle {
+                left: 0,
+                right: 5,
+                height: 1,
+                area: 6
+            },
+            Rectangle {
+                left: 3,
+                right: 3,
+                height: 6,
+                area: 6
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_top_k_rectangles_with_k_larger_than_candidate_count() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 2]);
+    let rectangles = square_search::top_k_rectangles(&histogram, 10);
+    assert_eq!(
+        rectangles,
+        vec![
+            Rectangle {
+                left: 0,
+                right: 2,
+                height: 1,
+                area: 3
+            },
+            Rectangle {
+                left: 0,
+                right: 0,
+                height: 2,
+                area: 2
+            },
+            Rectangle {
+                left: 2,
+                right: 2,
+                height: 2,
+                area: 2
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_top_k_rectangles_with_k_zero() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert!(square_search::top_k_rectangles(&histogram, 0).is_empty());
+}
+
+#[test]
+fn test_top_1_rectangle_is_largest_rectangle() {
+    let histogram = ConcreteHistogram::new(vec![3, 1, 3, 2, 2]);
+    let rectangles = square_search::top_k_rectangles(&histogram, 1);
+    assert_eq!(
+        rectangles.first().copied(),
+        square_search::compute_largest_rectangle(&histogram)
+    );
+}
+
+#[test]
+fn test_maximal_rectangles_of_trough() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 2]);
+    let rectangles: Vec<_> = square_search::maximal_rectangles(&histogram).collect();
+    assert_eq!(
+        rectangles,
+        vec![
+            Rectangle {
+                left: 0,
+                right: 0,
+                height: 2,
+                area: 2
+            },
+            Rectangle {
+                left: 0,
+                right: 2,
+                height: 1,
+                area: 3
+            },
+            Rectangle {
+                left: 2,
+                right: 2,
+                height: 2,
+                area: 2
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_maximal_rectangles_of_plateau() {
+    let histogram = ConcreteHistogram::new(vec![3, 3, 3]);
+    let rectangles: Vec<_> = square_search::maximal_rectangles(&histogram).collect();
+    assert_eq!(
+        rectangles,
+        vec![Rectangle {
+            left: 0,
+            right: 2,
+            height: 3,
+            area: 9
+        }]
+    );
+}
+
+#[test]
+fn test_maximal_rectangles_are_maximal_and_distinct() {
+    for bars in [
+        vec![2, 1, 5, 6, 2, 3],
+        vec![2, 2, 1, 2, 2],
+        vec![0, 3, 3, 0, 1, 4, 4, 1],
+        vec![1, 2, 3, 2, 1],
+    ] {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let rectangles: Vec<_> = square_search::maximal_rectangles(&histogram).collect();
+        for rectangle in &rectangles {
+            let covered = &bars[rectangle.left..=rectangle.right];
+            assert_eq!(covered.iter().min(), Some(&rectangle.height));
+            assert!(rectangle.left == 0 || bars[rectangle.left - 1] < rectangle.height);
+            assert!(
+                rectangle.right == bars.len() - 1 || bars[rectangle.right + 1] < rectangle.height
+            );
+        }
+        let mut spans: Vec<_> = rectangles.iter().map(|r| (r.left, r.right)).collect();
+        spans.dedup();
+        assert_eq!(spans.len(), rectangles.len());
+        assert!(spans.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
+
+#[test]
+fn test_for_each_candidate_max_matches_largest_area() {
+    for bars in [
+        vec![2, 1, 5, 6, 2, 3],
+        vec![2, 1, 2],
+        vec![3, 3, 3],
+        vec![1, 4, 1, 1],
+    ] {
+        let histogram = ConcreteHistogram::new(bars);
+        let mut candidates = Vec::new();
+        square_search::for_each_candidate(&histogram, |candidate| candidates.push(candidate));
+        let largest_candidate_area = candidates.iter().map(|candidate| candidate.area).max();
+        assert_eq!(
+            largest_candidate_area.unwrap_or(0),
+            square_search::compute_area_of_largest_rectangle(&histogram)
+        );
+    }
+}
+
+#[test]
+fn test_for_each_candidate_sees_every_maximal_rectangle() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let mut candidates = Vec::new();
+    square_search::for_each_candidate(&histogram, |candidate| candidates.push(candidate));
+    candidates.sort_by_key(|candidate| (candidate.left, candidate.right));
+    let maximal: Vec<_> = square_search::maximal_rectangles(&histogram).collect();
+    assert_eq!(candidates, maximal);
+}
+
+#[test]
+fn test_compute_best_by_custom_score() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let tallest = square_search::compute_best_by(&histogram, |candidate| candidate.height);
+    assert_eq!(
+        tallest,
+        Some(Rectangle {
+            left: 3,
+            right: 3,
+            height: 6,
+            area: 6
+        })
+    );
+    let widest = square_search::compute_best_by(&histogram, |candidate| candidate.width());
+    assert_eq!(
+        widest,
+        Some(Rectangle {
+            left: 0,
+            right: 5,
+            height: 1,
+            area: 6
+        })
+    );
+}
+
+#[test]
+fn test_compute_best_by_breaks_ties_leftmost() {
+    let histogram = ConcreteHistogram::new(vec![3, 1, 3]);
+    let best = square_search::compute_best_by(&histogram, |_| 0);
+    assert_eq!(
+        best,
+        Some(Rectangle {
+            left: 0,
+            right: 0,
+            height: 3,
+            area: 3
+        })
+    );
+}
+
+#[test]
+fn test_tie_break_policies_with_tall_bar_first() {
+    let histogram = ConcreteHistogram::new(vec![4, 0, 2, 2, 0, 1, 1, 1, 1]);
+    let tall = Some(Rectangle {
+        left: 0,
+        right: 0,
+        height: 4,
+        area: 4,
+    });
+    let wide = Some(Rectangle {
+        left: 5,
+        right: 8,
+        height: 1,
+        area: 4,
+    });
+    let largest_with =
+        |tie_break| square_search::compute_largest_rectangle_with(&histogram, tie_break);
+    assert_eq!(largest_with(TieBreak::Leftmost), tall);
+    assert_eq!(largest_with(TieBreak::Rightmost), wide);
+    assert_eq!(largest_with(TieBreak::Widest), wide);
+    assert_eq!(largest_with(TieBreak::Tallest), tall);
+}
+
+#[test]
+fn test_tie_break_policies_with_wide_block_first() {
+    let histogram = ConcreteHistogram::new(vec![1, 1, 1, 1, 0, 2, 2, 0, 4]);
+    let wide = Some(Rectangle {
+        left: 0,
+        right: 3,
+        height: 1,
+        area: 4,
+    });
+    let tall = Some(Rectangle {
+        left: 8,
+        right: 8,
+        height: 4,
+        area: 4,
+    });
+    let largest_with =
+        |tie_break| square_search::compute_largest_rectangle_with(&histogram, tie_break);
+    assert_eq!(largest_with(TieBreak::Leftmost), wide);
+    assert_eq!(largest_with(TieBreak::Rightmost), tall);
+    assert_eq!(largest_with(TieBreak::Widest), wide);
+    assert_eq!(largest_with(TieBreak::Tallest), tall);
+}
+
+#[test]
+fn test_default_tie_break_is_leftmost() {
+    let histogram = ConcreteHistogram::new(vec![3, 1, 3]);
+    assert_eq!(TieBreak::default(), TieBreak::Leftmost);
+    assert_eq!(
+        square_search::compute_largest_rectangle(&histogram),
+        square_search::compute_largest_rectangle_with(&histogram, TieBreak::Leftmost)
+    );
+}
+
+#[test]
+fn test_tie_break_policies_with_three_way_tie() {
+    let histogram = ConcreteHistogram::new(vec![3, 1, 3]);
+    let largest_with =
+        |tie_break| square_search::compute_largest_rectangle_with(&histogram, tie_break);
+    let spans_and_heights = [
+        TieBreak::Leftmost,
+        TieBreak::Rightmost,
+        TieBreak::Widest,
+        TieBreak::Tallest,
+    ]
+    .map(|tie_break| largest_with(tie_break).map(|r| (r.left, r.right, r.height)));
+    assert_eq!(
+        spans_and_heights,
+        [
+            Some((0, 0, 3)),
+            Some((2, 2, 3)),
+            Some((0, 2, 1)),
+            Some((0, 0, 3))
+        ]
+    );
+}
+
+#[test]
+fn test_constrained_with_minimum_width_and_height() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let options = SearchOptions {
+        min_width: Some(3),
+        min_height: Some(2),
+        ..SearchOptions::default()
+    };
+    assert_eq!(
+        square_search::compute_largest_rectangle_constrained(&histogram, &options),
+        Some(Rectangle {
+            left: 2,
+            right: 5,
+            height: 2,
+            area: 8
+        })
+    );
+}
+
+#[test]
+fn test_constrained_maximum_width_clamps_instead_of_filtering() {
+    let histogram = ConcreteHistogram::new(vec![3, 3, 3, 3]);
+    let options = SearchOptions {
+        max_width: Some(2),
+        ..SearchOptions::default()
+    };
+    assert_eq!(
+        square_search::compute_largest_rectangle_constrained(&histogram, &options),
+        Some(Rectangle {
+            left: 0,
+            right: 1,
+            height: 3,
+            area: 6
+        })
+    );
+}
+
+#[test]
+fn test_constrained_maximum_width_changes_winner() {
+    let histogram = ConcreteHistogram::new(vec![1, 1, 1, 1, 1, 1, 4, 4]);
+    let options = SearchOptions {
+        max_width: Some(3),
+        ..SearchOptions::default()
+    };
+    assert_eq!(
+        square_search::compute_largest_rectangle(&histogram).map(|r| r.height),
+        Some(1)
+    );
+    assert_eq!(
+        square_search::compute_largest_rectangle_constrained(&histogram, &options),
+        Some(Rectangle {
+            left: 6,
+            right: 7,
+            height: 4,
+            area: 8
+        })
+    );
+}
+
+#[test]
+fn test_constrained_maximum_height_changes_winner() {
+    let histogram = ConcreteHistogram::new(vec![1, 10, 10, 2, 2, 2]);
+    let options = SearchOptions {
+        max_height: Some(2),
+        ..SearchOptions::default()
+    };
+    assert_eq!(
+        square_search::compute_largest_rectangle_constrained(&histogram, &options),
+        Some(Rectangle {
+            left: 1,
+            right: 5,
+            height: 2,
+            area: 10
+        })
+    );
+}
+
+#[test]
+fn test_constrained_combined_maximums() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let options = SearchOptions {
+        max_width: Some(1),
+        max_height: Some(4),
+        min_height: Some(3),
+        ..SearchOptions::default()
+    };
+    assert_eq!(
+        square_search::compute_largest_rectangle_constrained(&histogram, &options),
+        Some(Rectangle {
+            left: 2,
+            right: 2,
+            height: 4,
+            area: 4
+        })
+    );
+}
+
+#[test]
+fn test_constrained_without_fitting_rectangle() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let options = SearchOptions {
+        min_width: Some(3),
+        min_height: Some(3),
+        ..SearchOptions::default()
+    };
+    assert_eq!(
+        square_search::compute_largest_rectangle_constrained(&histogram, &options),
+        None
+    );
+}
+
+#[test]
+fn test_unconstrained_options_match_largest_rectangle() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_constrained(&histogram, &SearchOptions::default()),
+        square_search::compute_largest_rectangle(&histogram)
+    );
+}
+
+#[test]
+fn test_largest_square_in_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::compute_largest_square(&histogram),
+        Some(Rectangle {
+            left: 2,
+            right: 3,
+            height: 2,
+            area: 4
+        })
+    );
+}
+
+#[test]
+fn test_largest_square_in_strictly_increasing_histogram() {
+    let histogram = ConcreteHistogram::new(vec![1, 2, 3, 4, 5]);
+    assert_eq!(
+        square_search::compute_largest_square(&histogram),
+        Some(Rectangle {
+            left: 2,
+            right: 4,
+            height: 3,
+            area: 9
+        })
+    );
+}
+
+#[test]
+fn test_largest_square_in_empty_histogram() {
+    let histogram = ConcreteHistogram::new(vec![0, 0]);
+    assert_eq!(square_search::compute_largest_square(&histogram), None);
+}
+
+#[test]
+fn test_largest_square_matches_brute_force() {
+    for bars in all_histograms(5, 4) {
+        let brute_force_side = (0..bars.len())
+            .flat_map(|left| (left..bars.len()).map(move |right| (left, right)))
+            .map(|(left, right)| {
+                let lowest = *bars[left..=right].iter().min().unwrap() as usize;
+                cmp::min(right - left + 1, lowest)
+            })
+            .max()
+            .unwrap_or(0);
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let square = square_search::compute_largest_square(&histogram);
+        assert_eq!(
+            square.map_or(0, |s| s.width()),
+            brute_force_side,
+            "{bars:?}"
+        );
+        if let Some(square) = square {
+            assert_eq!(square.width(), square.height as usize);
+            assert!(bars[square.left..=square.right]
+                .iter()
+                .all(|&bar| bar >= square.height));
+        }
+    }
+}
+
+#[test]
+fn test_largest_perimeter_prefers_tall_thin_bar() {
+    let histogram = ConcreteHistogram::new(vec![3, 3, 3, 3, 0, 10]);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram),
+        12
+    );
+    assert_eq!(
+        square_search::compute_largest_rectangle_by_perimeter(&histogram),
+        Some(Rectangle {
+            left: 5,
+            right: 5,
+            height: 10,
+            area: 10
+        })
+    );
+}
+
+#[test]
+fn test_largest_perimeter_with_tall_spike_before_shallow_block() {
+    let histogram = ConcreteHistogram::new(vec![100, 1, 1, 1]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_by_perimeter(&histogram),
+        Some(Rectangle {
+            left: 0,
+            right: 0,
+            height: 100,
+            area: 100
+        })
+    );
+}
+
+#[test]
+fn test_largest_perimeter_prefers_wide_shallow_block() {
+    let mut bars = vec![1; 50];
+    bars.extend([10, 10]);
+    let histogram = ConcreteHistogram::new(bars);
+    assert_eq!(
+        square_search::compute_largest_rectangle_by_perimeter(&histogram),
+        Some(Rectangle {
+            left: 0,
+            right: 51,
+            height: 1,
+            area: 52
+        })
+    );
+}
+
+#[test]
+fn test_largest_perimeter_of_empty_histogram() {
+    let histogram = ConcreteHistogram::new(vec![]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_by_perimeter(&histogram),
+        None
+    );
+}
+
+#[test]
+fn test_aspect_ratio_shrinks_width() {
+    let histogram = ConcreteHistogram::new(vec![2, 2, 2, 2, 2, 2]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_with_aspect(&histogram, 1.0, 1.0),
+        Some(Rectangle {
+            left: 0,
+            right: 1,
+            height: 2,
+            area: 4
+        })
+    );
+}
+
+#[test]
+fn test_aspect_ratio_shrinks_height() {
+    let histogram = ConcreteHistogram::new(vec![10, 10, 1]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_with_aspect(&histogram, 1.0, 2.0),
+        Some(Rectangle {
+            left: 0,
+            right: 1,
+            height: 2,
+            area: 4
+        })
+    );
+}
+
+#[test]
+fn test_aspect_ratio_sixteen_by_nine() {
+    let histogram = ConcreteHistogram::new(vec![9; 20]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_with_aspect(&histogram, 16.0 / 9.0, 16.0 / 9.0),
+        Some(Rectangle {
+            left: 0,
+            right: 15,
+            height: 9,
+            area: 144
+        })
+    );
+}
+
+#[test]
+fn test_aspect_ratio_satisfied_by_unconstrained_optimum() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_with_aspect(&histogram, 0.25, 0.5),
+        square_search::compute_largest_rectangle(&histogram)
+    );
+}
+
+#[test]
+fn test_aspect_ratio_impossible() {
+    let histogram = ConcreteHistogram::new(vec![1]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_with_aspect(&histogram, 2.0, 3.0),
+        None
+    );
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_with_aspect(&histogram, 2.0, 1.0),
+        None
+    );
+}
+
+#[test]
+fn test_aspect_ratio_negative_minimum_acts_as_zero() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    for max_ratio in [0.25, 1.0, 4.0] {
+        assert_eq!(
+            square_search::compute_largest_rectangle_with_aspect(&histogram, -1.0, max_ratio),
+            square_search::compute_largest_rectangle_with_aspect(&histogram, 0.0, max_ratio),
+        );
+    }
+    assert_eq!(
+        square_search::compute_largest_rectangle_with_aspect(&histogram, -1.0, 1.0),
+        Some(Rectangle {
+            left: 2,
+            right: 3,
+            height: 5,
+            area: 10
+        })
+    );
+}
+
+#[test]
+fn test_aspect_ratio_matches_brute_force() {
+    for (min_ratio, max_ratio) in [(0.5, 1.0), (1.0, 3.0), (1.5, 1.5), (0.2, 0.3)] {
+        for bars in all_histograms(4, 4) {
+            let brute_force_area = (0..bars.len())
+                .flat_map(|left| (left..bars.len()).map(move |right| (left, right)))
+                .flat_map(|(left, right)| {
+                    let width = right - left + 1;
+                    let lowest = *bars[left..=right].iter().min().unwrap();
+                    (1..=lowest).filter_map(move |height| {
+                        let ratio = width as f64 / height as f64;
+                        (min_ratio <= ratio && ratio <= max_ratio)
+                            .then_some(width as u64 * height as u64)
+                    })
+                })
+                .max();
+            let histogram = ConcreteHistogram::new(bars.clone());
+            let rectangle = square_search::compute_largest_rectangle_with_aspect(
+                &histogram, min_ratio, max_ratio,
+            );
+            assert_eq!(rectangle.map(|r| r.area), brute_force_area, "{bars:?}");
+        }
+    }
+}
+
+struct CountingHistogram<H> {
+    histogram: H,
+    height_at_calls: Cell<usize>,
+}
+
+impl<H: Histogram> CountingHistogram<H> {
+    fn new(histogram: H) -> Self {
+        Self {
+            histogram,
+            height_at_calls: Cell::new(0),
+        }
+    }
+}
+
+impl<H: Histogram> Histogram for CountingHistogram<H> {
+    fn width(&self) -> usize {
+        self.histogram.width()
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        self.height_at_calls.set(self.height_at_calls.get() + 1);
+        self.histogram.height_at(horizontal_position)
+    }
+}
+
+#[test]
+fn test_find_rectangle_with_area_at_least_stops_early() {
+    let mut bars = vec![100, 1];
+    bars.extend(vec![7; 10_000]);
+    let histogram = CountingHistogram::new(ConcreteHistogram::new(bars));
+    let found = square_search::find_rectangle_with_area_at_least(&histogram, 50);
+    assert_eq!(
+        found,
+        Some(Rectangle {
+            left: 0,
+            right: 0,
+            height: 100,
+            area: 100
+        })
+    );
+    assert!(histogram.height_at_calls.get() < histogram.width());
+}
+
+#[test]
+fn test_find_rectangle_with_area_at_least_in_open_plateau() {
+    let histogram = CountingHistogram::new(ConcreteHistogram::new(vec![5; 10_000]));
+    let found = square_search::find_rectangle_with_area_at_least(&histogram, 20);
+    assert_eq!(
+        found,
+        Some(Rectangle {
+            left: 0,
+            right: 3,
+            height: 5,
+            area: 20
+        })
+    );
+    assert!(histogram.height_at_calls.get() < histogram.width());
+}
+
+#[test]
+fn test_find_rectangle_with_area_at_least_after_pop() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let found = square_search::find_rectangle_with_area_at_least(&histogram, 10);
+    assert_eq!(found.map(|r| r.area), Some(10));
+    let found = square_search::find_rectangle_with_area_at_least(&histogram, 11);
+    assert_eq!(found, None);
+}
+
+#[test]
+fn test_find_rectangle_with_area_at_least_matches_largest_area() {
+    for bars in all_histograms(5, 3) {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let largest_area = square_search::compute_area_of_largest_rectangle(&histogram);
+        for threshold in 1..=largest_area + 1 {
+            let found = square_search::find_rectangle_with_area_at_least(&histogram, threshold);
+            assert_eq!(found.is_some(), threshold <= largest_area, "{bars:?}");
+            if let Some(found) = found {
+                assert!(found.area >= threshold);
+                assert!(bars[found.left..=found.right]
+                    .iter()
+                    .all(|&bar| bar >= found.height));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_batch_solver_matches_one_shot_solver() {
+    let mut rng = Rng::new(19);
+    let mut solver = BatchSolver::new();
+    for _ in 0..300 {
+        let histogram = ConcreteHistogram::new(rng.bars(30, 10));
+        assert_eq!(
+            solver.solve(&histogram),
+            square_search::compute_area_of_largest_rectangle(&histogram)
+        );
+    }
+}
+
+#[test]
+fn test_batch_solver_solve_all() {
+    let mut rng = Rng::new(1919);
+    let histograms: Vec<_> = (0..300)
+        .map(|_| ConcreteHistogram::new(rng.bars(30, 10)))
+        .collect();
+    let expected: Vec<_> = histograms
+        .iter()
+        .map(square_search::compute_area_of_largest_rectangle)
+        .collect();
+    assert_eq!(BatchSolver::new().solve_all(&histograms), expected);
+}
+
+#[test]
+fn test_batch_solver_with_mixed_height_types() {
+    let mut solver = BatchSolver::new();
+    assert_eq!(
+        solver.solve(&ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3])),
+        10
+    );
+    assert_eq!(
+        solver.solve(&ConcreteHistogram::from_bars(vec![3u8, 3, 3])),
+        9
+    );
+    assert_eq!(solver.solve(&ConcreteHistogram::new(vec![])), 0);
+}
+
+#[test]
+fn test_searcher_can_be_run_repeatedly() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let mut searcher = LargestRectangleSearcher::new(&histogram);
+    assert_eq!(searcher.compute_area_of_largest_rectangle(), 10);
+    assert_eq!(searcher.compute_area_of_largest_rectangle(), 10);
+}
+
+#[test]
+fn test_searcher_can_be_pointed_at_another_histogram() {
+    let first = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let second = ConcreteHistogram::new(vec![3, 3]);
+    let empty = ConcreteHistogram::new(vec![]);
+    let mut searcher = LargestRectangleSearcher::new(&first);
+    assert_eq!(searcher.compute_area_of_largest_rectangle(), 10);
+    searcher.set_histogram(&second);
+    assert_eq!(searcher.compute_area_of_largest_rectangle(), 6);
+    searcher.set_histogram(&empty);
+    assert_eq!(searcher.compute_area_of_largest_rectangle(), 0);
+    searcher.set_histogram(&first);
+    searcher.reset();
+    assert_eq!(searcher.compute_area_of_largest_rectangle(), 10);
+}
+
+#[test]
+fn test_searcher_candidates_match_free_function() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let mut from_searcher = Vec::new();
+    LargestRectangleSearcher::new(&histogram).for_each_candidate(|c| from_searcher.push(c));
+    let mut from_function = Vec::new();
+    square_search::for_each_candidate(&histogram, |c| from_function.push(c));
+    assert_eq!(from_searcher, from_function);
+}
+
+#[test]
+fn test_streaming_searcher_reports_prefix_answers() {
+    let mut searcher = StreamingSearcher::new();
+    let answers: Vec<u64> = [2, 1, 5, 6, 2, 3]
+        .into_iter()
+        .map(|height| searcher.push_bar(height))
+        .collect();
+    assert_eq!(answers, vec![2, 2, 5, 10, 10, 10]);
+    assert_eq!(
+        searcher.finish(),
+        Some(Rectangle {
+            left: 2,
+            right: 3,
+            height: 5,
+            area: 10
+        })
+    );
+}
+
+#[test]
+fn test_streaming_searcher_with_no_bars() {
+    assert_eq!(StreamingSearcher::new().finish(), None);
+}
+
+#[test]
+fn test_streaming_searcher_matches_batch_search_on_every_prefix() {
+    let mut rng = Rng::new(21);
+    for _ in 0..500 {
+        let bars = rng.bars(30, 6);
+        let mut searcher = StreamingSearcher::new();
+        for end in 1..=bars.len() {
+            let prefix = ConcreteHistogram::new(bars[..end].to_vec());
+            assert_eq!(
+                searcher.push_bar(bars[end - 1]),
+                square_search::compute_area_of_largest_rectangle(&prefix),
+                "{bars:?}"
+            );
+        }
+        let histogram = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            searcher.finish(),
+            square_search::compute_largest_rectangle(&histogram),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_streaming_searcher_on_increasing_bars() {
+    let mut searcher = StreamingSearcher::new();
+    let mut answer = 0;
+    for height in 1..=1000 {
+        answer = searcher.push_bar(height);
+    }
+    assert_eq!(answer, 500 * 501);
+}
+
+fn naive_sliding_window(bars: &[i32], window: usize) -> Vec<u64> {
+    if bars.is_empty() {
+        return Vec::new();
+    }
+    bars.windows(window.min(bars.len()))
+        .map(|run| {
+            square_search::compute_area_of_largest_rectangle(&ConcreteHistogram::new(run.to_vec()))
+        })
+        .collect()
+}
+
+#[test]
+fn test_sliding_window_largest_rectangle() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::sliding_window_largest_rectangle(&histogram, 3),
+        vec![5, 10, 10, 6]
+    );
+}
+
+#[test]
+fn test_sliding_window_of_one_bar_gives_each_height() {
+    let histogram = ConcreteHistogram::new(vec![2, 0, 5, 6]);
+    assert_eq!(
+        square_search::sliding_window_largest_rectangle(&histogram, 1),
+        vec![2, 0, 5, 6]
+    );
+}
+
+#[test]
+fn test_sliding_window_as_wide_as_histogram_or_wider() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::sliding_window_largest_rectangle(&histogram, 6),
+        vec![10]
+    );
+    assert_eq!(
+        square_search::sliding_window_largest_rectangle(&histogram, 7),
+        vec![10]
+    );
+    let empty = ConcreteHistogram::new(vec![]);
+    assert!(square_search::sliding_window_largest_rectangle(&empty, 3).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "window size must be non-zero")]
+fn test_sliding_window_of_zero_bars_panics() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    square_search::sliding_window_largest_rectangle(&histogram, 0);
+}
+
+#[test]
+fn test_sliding_window_matches_naive_search() {
+    let mut rng = Rng::new(22);
+    for _ in 0..300 {
+        let bars = rng.bars(40, 8);
+        let histogram = ConcreteHistogram::new(bars.clone());
+        for window in 1..=bars.len() + 1 {
+            assert_eq!(
+                square_search::sliding_window_largest_rectangle(&histogram, window),
+                naive_sliding_window(&bars, window),
+                "{bars:?} with window {window}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_best_two_disjoint_rectangles_prefers_two_columns() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 2]);
+    assert_eq!(
+        square_search::best_two_disjoint_rectangles(&histogram),
+        Some((
+            Rectangle {
+                left: 0,
+                right: 0,
+                height: 2,
+                area: 2
+            },
+            Rectangle {
+                left: 2,
+                right: 2,
+                height: 2,
+                area: 2
+            }
+        ))
+    );
+}
+
+#[test]
+fn test_best_two_disjoint_rectangles_needs_two_positive_rectangles() {
+    for bars in [vec![], vec![5], vec![5, 0], vec![0, 0, 0]] {
+        let histogram = ConcreteHistogram::new(bars);
+        assert_eq!(
+            square_search::best_two_disjoint_rectangles(&histogram),
+            None
+        );
+    }
+}
+
+#[test]
+fn test_best_two_disjoint_rectangles_matches_brute_force() {
+    for bars in all_histograms(6, 3) {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let best_total = (1..bars.len())
+            .filter_map(|split| {
+                let left = square_search::compute_largest_rectangle(&ConcreteHistogram::new(
+                    bars[..split].to_vec(),
+                ))?;
+                let right = square_search::compute_largest_rectangle(&ConcreteHistogram::new(
+                    bars[split..].to_vec(),
+                ))?;
+                Some(left.area + right.area)
+            })
+            .max();
+        let pair = square_search::best_two_disjoint_rectangles(&histogram);
+        assert_eq!(
+            pair.map(|(left, right)| left.area + right.area),
+            best_total,
+            "{bars:?}"
+        );
+        if let Some((left, right)) = pair {
+            assert!(left.right < right.left, "{bars:?}");
+            for rectangle in [left, right] {
+                let lowest = bars[rectangle.left..=rectangle.right].iter().min().unwrap();
+                assert!(rectangle.height <= *lowest, "{bars:?}");
+                assert_eq!(
+                    rectangle.area,
+                    rectangle.height as u64 * rectangle.width() as u64
+                );
+            }
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::single_range_in_vec_init)]
+fn test_excluding_banned_range_finds_next_best_region() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_excluding(&histogram, &[2..4]),
+        Some(Rectangle {
+            left: 4,
+            right: 5,
+            height: 2,
+            area: 4
+        })
+    );
+}
+
+#[test]
+fn test_excluding_several_banned_ranges() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_excluding(&histogram, &[3..4, 5..9, 0..0]),
+        Some(Rectangle {
+            left: 2,
+            right: 2,
+            height: 5,
+            area: 5
+        })
+    );
+    assert_eq!(
+        square_search::compute_largest_rectangle_excluding(&histogram, &[0..3, 2..6]),
+        None
+    );
+}
+
+#[test]
+fn test_excluding_nothing_matches_unconstrained_search() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_excluding(&histogram, &[]),
+        square_search::compute_largest_rectangle(&histogram)
+    );
+}
+
+#[test]
+fn test_excluding_matches_search_over_remaining_pieces() {
+    let mut rng = Rng::new(25);
+    for _ in 0..500 {
+        let bars = rng.bars(12, 5);
+        let banned: Vec<_> = (0..rng.below(3))
+            .map(|_| {
+                let start = rng.below(bars.len() + 2);
+                start..start + rng.below(4)
+            })
+            .collect();
+        let is_banned = |x: usize| banned.iter().any(|range| range.contains(&x));
+        let expected = (0..bars.len())
+            .filter(|&start| !is_banned(start))
+            .flat_map(|start| {
+                (start..bars.len())
+                    .take_while(|&end| !is_banned(end))
+                    .map(move |end| (start, end))
+            })
+            .map(|(start, end)| {
+                *bars[start..=end].iter().min().unwrap() as u64 * (end - start + 1) as u64
+            })
+            .max()
+            .unwrap_or(0);
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let found = square_search::compute_largest_rectangle_excluding(&histogram, &banned);
+        assert_eq!(
+            found.map_or(0, |rectangle| rectangle.area),
+            expected,
+            "{bars:?} {banned:?}"
+        );
+        if let Some(rectangle) = found {
+            assert!((rectangle.left..=rectangle.right).all(|x| !is_banned(x)));
+        }
+    }
+}
+
+#[test]
+fn test_circular_search_wraps_around_the_seam() {
+    let histogram = ConcreteHistogram::new(vec![5, 1, 1, 5]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_circular(&histogram),
+        Some(Rectangle {
+            left: 3,
+            right: 0,
+            height: 5,
+            area: 10
+        })
+    );
+}
+
+#[test]
+fn test_circular_search_without_a_better_wrapping_rectangle() {
+    let histogram = ConcreteHistogram::new(vec![1, 5, 1, 5]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_circular(&histogram),
+        square_search::compute_largest_rectangle(&histogram)
+    );
+}
+
+#[test]
+fn test_circular_search_never_goes_round_more_than_once() {
+    let histogram = ConcreteHistogram::new(vec![3, 3, 3]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_circular(&histogram),
+        Some(Rectangle {
+            left: 0,
+            right: 2,
+            height: 3,
+            area: 9
+        })
+    );
+    let empty = ConcreteHistogram::new(vec![]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_circular(&empty),
+        None
+    );
+}
+
+#[test]
+fn test_circular_search_matches_brute_force() {
+    for bars in all_histograms(6, 3) {
+        let width = bars.len();
+        let expected = (0..width)
+            .flat_map(|start| (1..=width).map(move |span| (start, span)))
+            .map(|(start, span)| {
+                let lowest = (start..start + span)
+                    .map(|x| bars[x % width])
+                    .min()
+                    .unwrap();
+                lowest as u64 * span as u64
+            })
+            .max()
+            .unwrap_or(0);
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let found = square_search::compute_largest_rectangle_circular(&histogram);
+        assert_eq!(
+            found.map_or(0, |rectangle| rectangle.area),
+            expected,
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_negative_heights_are_treated_as_zero() {
+    let cases = [
+        (vec![-1, -2, -3], vec![0, 0, 0]),
+        (vec![2, -1, 2], vec![2, 0, 2]),
+        (vec![3, 3, -5], vec![3, 3, 0]),
+        (vec![-3, 2, 2, -1, 4], vec![0, 2, 2, 0, 4]),
+    ];
+    for (bars, clamped) in cases {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let clamped = ConcreteHistogram::new(clamped);
+        assert_eq!(
+            square_search::compute_largest_rectangle(&histogram),
+            square_search::compute_largest_rectangle(&clamped),
+            "{bars:?}"
+        );
+        assert_eq!(
+            square_search::maximal_rectangles(&histogram).collect::<Vec<_>>(),
+            square_search::maximal_rectangles(&clamped).collect::<Vec<_>>(),
+            "{bars:?}"
+        );
+        assert_eq!(
+            square_search::compute_largest_square(&histogram),
+            square_search::compute_largest_square(&clamped),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_all_negative_heights_give_no_rectangle() {
+    let histogram = ConcreteHistogram::new(vec![-1, -2, -3]);
+    assert_eq!(square_search::compute_largest_rectangle(&histogram), None);
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&histogram),
+        0
+    );
+}
+
+#[test]
+fn test_negative_policy_reject() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, -5, 6, -2, 3]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_with_policy(&histogram, NegativePolicy::Reject),
+        Err(NegativeHeight { bar_index: 2 })
+    );
+    assert_eq!(
+        square_search::compute_largest_rectangle_with_policy(&histogram, NegativePolicy::Clamp),
+        Ok(Some(Rectangle {
+            left: 3,
+            right: 3,
+            height: 6,
+            area: 6
+        }))
+    );
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_with_policy(&histogram, NegativePolicy::Reject),
+        Ok(square_search::compute_largest_rectangle(&histogram))
+    );
+}
+
+#[test]
+fn test_random_negative_heights_match_clamped_heights() {
+    let mut rng = Rng::new(29);
+    for _ in 0..500 {
+        let bars: Vec<i32> = rng.bars(12, 8).into_iter().map(|bar| bar - 3).collect();
+        let clamped: Vec<i32> = bars.iter().map(|&bar| bar.max(0)).collect();
+        assert_eq!(
+            square_search::compute_largest_rectangle(&ConcreteHistogram::new(bars.clone())),
+            square_search::compute_largest_rectangle(&ConcreteHistogram::new(clamped)),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_naive_search_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(naive::compute_area_of_largest_rectangle(&histogram), 10);
+    assert_eq!(
+        naive::compute_largest_rectangle(&histogram),
+        Some(Rectangle {
+            left: 2,
+            right: 3,
+            height: 5,
+            area: 10
+        })
+    );
+    assert_eq!(
+        naive::compute_largest_rectangle(&ConcreteHistogram::new(vec![0, -1])),
+        None
+    );
+}
+
+#[test]
+fn test_search_matches_naive_on_every_small_histogram() {
+    for bars in all_histograms(7, 3) {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            square_search::compute_largest_rectangle(&histogram),
+            naive::compute_largest_rectangle(&histogram),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_search_matches_naive_on_random_histograms() {
+    let mut rng = Rng::new(52);
+    for _ in 0..3000 {
+        let mut bars = rng.bars(50, 20);
+        for height in bars.iter_mut().filter(|height| **height == 20) {
+            *height = -5;
+        }
+        let histogram = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            square_search::compute_largest_rectangle(&histogram),
+            naive::compute_largest_rectangle(&histogram),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "paranoid", ignore = "the cross-check reads every bar again")]
+fn test_search_reads_each_bar_once() {
+    let mut rng = Rng::new(54);
+    for _ in 0..500 {
+        let bars = rng.bars(40, 6);
+        let width = bars.len();
+        let histogram = CountingHistogram::new(ConcreteHistogram::new(bars));
+        square_search::compute_largest_rectangle(&histogram);
+        assert!(histogram.height_at_calls.get() <= width + 1);
+        histogram.height_at_calls.set(0);
+        square_search::find_rectangle_with_area_at_least(&histogram, u64::MAX);
+        assert!(histogram.height_at_calls.get() <= width + 1);
+    }
+    let increasing = CountingHistogram::new(ConcreteHistogram::new((1..=1000).collect()));
+    assert_eq!(
+        square_search::compute_area_of_largest_rectangle(&increasing),
+        500 * 501
+    );
+    assert!(increasing.height_at_calls.get() <= 1001);
+}
+
+// Searching two billion bars takes minutes without optimizations.
+#[test]
+#[cfg_attr(debug_assertions, ignore = "slow without optimizations")]
+fn test_histogram_wider_than_i32_max() {
+    let width = i32::MAX as usize + 3;
+    let histogram = FnHistogram::new(width, |x_pos| -> i32 {
+        match x_pos {
+            5..=7 => 4,
+            x_pos if x_pos >= width - 4 => 5,
+            _ => 0,
+        }
+    });
+    assert_eq!(
+        square_search::compute_largest_rectangle(&histogram),
+        Some(Rectangle {
+            left: width - 4,
+            right: width - 1,
+            height: 5,
+            area: 20
+        })
+    );
+}
+
+#[test]
+fn test_searcher_moves_between_small_and_wide_histograms() {
+    let small = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let wide = ConcreteHistogram::new((1..=100).collect());
+    let mut searcher = LargestRectangleSearcher::new(&small);
+    assert_eq!(searcher.compute_area_of_largest_rectangle(), 10);
+    searcher.set_histogram(&wide);
+    assert_eq!(searcher.compute_area_of_largest_rectangle(), 50 * 51);
+    searcher.set_histogram(&small);
+    assert_eq!(searcher.compute_area_of_largest_rectangle(), 10);
+}
+
+#[test]
+fn test_slice_search_matches_generic_search() {
+    let mut rng = Rng::new(60);
+    for max_height in [0, 1, 3, 1000] {
+        for _ in 0..200 {
+            let mut bars = rng.bars(80, max_height);
+            if let Some(bar) = bars.first_mut() {
+                *bar = -*bar;
+            }
+            let histogram = ConcreteHistogram::new(bars.clone());
+            let generic = FnHistogram::new(bars.len(), |x_pos| bars[x_pos]);
+            let expected = square_search::compute_largest_rectangle(&generic);
+            assert_eq!(
+                square_search::compute_largest_rectangle(&histogram),
+                expected
+            );
+            assert_eq!(
+                square_search::compute_largest_rectangle(&bars[..]),
+                expected
+            );
+            let boxed: Box<dyn Histogram> = Box::new(ConcreteHistogram::new(bars.clone()));
+            assert_eq!(square_search::compute_largest_rectangle(&boxed), expected);
+        }
+    }
+}
+
+#[test]
+fn test_slice_search_of_sub_histogram_matches_generic_search() {
+    let bars = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+    let histogram = ConcreteHistogram::new(bars.clone());
+    let generic = FnHistogram::new(bars.len(), |x_pos| bars[x_pos]);
+    for range in [0..0, 2..5, 4..8, 0..11] {
+        assert_eq!(
+            square_search::compute_largest_rectangle(&histogram.slice(range.clone())),
+            square_search::compute_largest_rectangle(&generic.slice(range))
+        );
+    }
+}
+
+#[test]
+fn test_searches_agree_on_every_pattern() {
+    for pattern in Pattern::ALL {
+        for width in [0, 1, 2, 7, 100, 1000] {
+            for max_height in [0, 1, 10, 5000] {
+                let histogram = generate::pattern(pattern, width, max_height);
+                let bars = histogram.heights();
+                let expected = naive::compute_largest_rectangle(&histogram);
+                let context = format!("{pattern:?} of {width} bars up to {max_height}");
+                assert_eq!(
+                    square_search::compute_largest_rectangle(&histogram),
+                    expected,
+                    "{context}"
+                );
+                let generic = FnHistogram::new(width, |x_pos| bars[x_pos]);
+                assert_eq!(
+                    square_search::compute_largest_rectangle(&generic),
+                    expected,
+                    "{context}"
+                );
+                let expected_area = expected.map_or(0, |rectangle| rectangle.area);
+                assert_eq!(
+                    square_search::compute_with(&histogram, Algorithm::DivideAndConquer),
+                    expected_area,
+                    "{context}"
+                );
+                assert_eq!(
+                    square_search::compute_area_parallel_with(&histogram, 3),
+                    expected_area,
+                    "{context}"
+                );
+                let mut searcher = StreamingSearcher::new();
+                for &height in bars {
+                    searcher.push_bar(height);
+                }
+                assert_eq!(searcher.finish(), expected, "{context}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rectangles_with_area_at_least_match_filtered_maximal_rectangles() {
+    let mut rng = Rng::new(80);
+    let random = (0..200).map(|_| rng.bars(40, 12));
+    for bars in all_histograms(5, 3)
+        .into_iter()
+        .chain(random.collect::<Vec<_>>())
+    {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        for threshold in [0, 1, 4, 10, 30] {
+            let mut found: Vec<Rectangle> =
+                square_search::rectangles_with_area_at_least(&histogram, threshold).collect();
+            found.sort_unstable_by_key(|rectangle| (rectangle.left, rectangle.right));
+            let expected: Vec<Rectangle> = square_search::maximal_rectangles(&histogram)
+                .filter(|rectangle| rectangle.area >= threshold)
+                .collect();
+            assert_eq!(found, expected, "{bars:?} at {threshold}");
+        }
+    }
+}
+
+#[test]
+fn test_rectangles_with_area_at_least_on_plateau() {
+    let histogram = ConcreteHistogram::new(vec![3, 3, 3, 1, 1]);
+    assert_eq!(
+        square_search::rectangles_with_area_at_least(&histogram, 1).collect::<Vec<_>>(),
+        vec![
+            Rectangle {
+                left: 0,
+                right: 2,
+                height: 3,
+                area: 9
+            },
+            Rectangle {
+                left: 0,
+                right: 4,
+                height: 1,
+                area: 5
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_rectangles_with_area_at_least_stop_reading_when_dropped() {
+    let histogram = CountingHistogram::new(ConcreteHistogram::new(
+        (0..1000)
+            .map(|x_pos| if x_pos == 10 { 0 } else { 5 })
+            .collect(),
+    ));
+    let first = square_search::rectangles_with_area_at_least(&histogram, 50).next();
+    assert_eq!(
+        first,
+        Some(Rectangle {
+            left: 0,
+            right: 9,
+            height: 5,
+            area: 50
+        })
+    );
+    assert_eq!(histogram.height_at_calls.get(), 11);
+}
+
+fn permutations(bars: &[i32]) -> Vec<Vec<i32>> {
+    if bars.is_empty() {
+        return vec![vec![]];
+    }
+    let mut all = Vec::new();
+    for first in 0..bars.len() {
+        let mut rest = bars.to_vec();
+        let bar = rest.remove(first);
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, bar);
+            all.push(permutation);
+        }
+    }
+    all
+}
+
+#[test]
+fn test_reordered_bars_of_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    assert_eq!(square_search::compute_area_if_reorderable(&histogram), 10);
+    assert_eq!(square_search::compute_best_reordering(&histogram), (10, 2));
+    let histogram = ConcreteHistogram::new(vec![4, 1, 4, 1, 4]);
+    assert_eq!(square_search::compute_best_reordering(&histogram), (12, 3));
+}
+
+#[test]
+fn test_reordering_nothing() {
+    for bars in [vec![], vec![0, -3, 0]] {
+        let histogram = ConcreteHistogram::new(bars);
+        assert_eq!(square_search::compute_best_reordering(&histogram), (0, 0));
+    }
+}
+
+#[test]
+fn test_reordering_matches_best_permutation() {
+    for bars in all_histograms(5, 3) {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let best_permutation = permutations(&bars)
+            .into_iter()
+            .map(|permutation| {
+                square_search::compute_area_of_largest_rectangle(&ConcreteHistogram::new(
+                    permutation,
+                ))
+            })
+            .max()
+            .unwrap();
+        assert_eq!(
+            square_search::compute_area_if_reorderable(&histogram),
+            best_permutation,
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_reordering_never_loses_area() {
+    let mut rng = Rng::new(82);
+    for _ in 0..500 {
+        let mut bars = rng.bars(100, 1000);
+        for bar in bars.iter_mut().step_by(6) {
+            *bar = -*bar;
+        }
+        let histogram = ConcreteHistogram::new(bars.clone());
+        let (area, count) = square_search::compute_best_reordering(&histogram);
+        assert!(
+            area >= square_search::compute_area_of_largest_rectangle(&histogram),
+            "{bars:?}"
+        );
+        assert!(count <= bars.len());
+        assert_eq!(histogram.heights(), &bars[..]);
+    }
+}
+
+#[test]
+fn test_stats_describe_the_search() {
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let (area, stats) = square_search::compute_area_with_stats(&histogram);
+    assert_eq!(area, 10);
+    assert_eq!(stats.width, 6);
+    assert_eq!(stats.max_stack_depth, 3);
+}
+
+#[test]
+fn test_stats_stack_depth_follows_the_shape() {
+    let depth = |bars: Vec<i32>| {
+        square_search::compute_area_with_stats(&ConcreteHistogram::new(bars))
+            .1
+            .max_stack_depth
+    };
+    assert_eq!(depth(vec![]), 0);
+    assert_eq!(depth(vec![1, 2, 3, 4, 5]), 5);
+    assert_eq!(depth(vec![5, 4, 3, 2, 1]), 1);
+}
+
+#[test]
+fn test_stats_count_every_candidate() {
+    let mut rng = Rng::new(99);
+    for _ in 0..200 {
+        let width = rng.below(30);
+        let histogram: ConcreteHistogram = (0..width).map(|_| rng.below(10) as i32).collect();
+        let (area, stats) = square_search::compute_area_with_stats(&histogram);
+        let mut candidates = 0;
+        square_search::for_each_candidate(&histogram, |_| candidates += 1);
+        assert_eq!(
+            area,
+            square_search::compute_area_of_largest_rectangle(&histogram)
+        );
+        assert_eq!(stats.candidates, candidates);
+        assert_eq!(stats.width, width);
+    }
+}
+
+#[test]
+fn test_trace_of_leetcode_example() {
+    use square_search::SearchEvent::{NewBest, Pop, Push, ReplaceTop, Visit};
+    let histogram = ConcreteHistogram::new(vec![2, 1, 5, 6, 2, 3]);
+    let (rectangle, trace) = square_search::compute_with_trace(&histogram);
+    let best = Rectangle {
+        left: 2,
+        right: 3,
+        height: 5,
+        area: 10,
+    };
+    assert_eq!(rectangle, Some(best));
+    assert_eq!(trace.replay(), Some(best));
+    let rect = |left, right, height: i32| Rectangle {
+        left,
+        right,
+        height,
+        area: height as u64 * (right - left + 1) as u64,
+    };
+    assert_eq!(
+        trace.events[..9],
+        [
+            Visit { pos: 0, height: 2 },
+            Push { pos: 0 },
+            Visit { pos: 1, height: 1 },
+            Pop {
+                pos: 0,
+                candidate: rect(0, 0, 2)
+            },
+            NewBest {
+                rectangle: rect(0, 0, 2)
+            },
+            Push { pos: 1 },
+            Visit { pos: 2, height: 5 },
+            Push { pos: 2 },
+            Visit { pos: 3, height: 6 },
+        ]
+    );
+    assert_eq!(trace.events.last(), Some(&Push { pos: 6 }));
+    let visits = trace
+        .events
+        .iter()
+        .filter(|event| matches!(event, Visit { .. }))
+        .count();
+    assert_eq!(visits, 7);
+    assert!(!trace
+        .events
+        .iter()
+        .any(|event| matches!(event, ReplaceTop { .. })));
+}
+
+#[test]
+fn test_trace_replaces_the_top_of_a_plateau() {
+    use square_search::SearchEvent::{Push, ReplaceTop};
+    let (_, trace) = square_search::compute_with_trace(&ConcreteHistogram::new(vec![3, 3, 3]));
+    let stack_events: Vec<_> = trace
+        .events
+        .iter()
+        .filter(|event| matches!(event, Push { .. } | ReplaceTop { .. }))
+        .collect();
+    assert_eq!(
+        stack_events,
+        [
+            &Push { pos: 0 },
+            &ReplaceTop { pos: 1 },
+            &ReplaceTop { pos: 2 },
+            &Push { pos: 3 },
+        ]
+    );
+}
+
+#[test]
+fn test_trace_of_a_histogram_without_a_rectangle() {
+    for bars in [vec![], vec![0, 0]] {
+        let (rectangle, trace) = square_search::compute_with_trace(&ConcreteHistogram::new(bars));
+        assert_eq!(rectangle, None);
+        assert_eq!(trace.replay(), None);
+    }
+}
+
+#[test]
+fn test_trace_replays_to_the_largest_rectangle() {
+    use square_search::SearchEvent::{NewBest, Pop};
+    for histogram in all_histograms(6, 3) {
+        let (rectangle, trace) = square_search::compute_with_trace(&histogram);
+        assert_eq!(
+            rectangle,
+            square_search::compute_largest_rectangle(&histogram)
+        );
+        assert_eq!(trace.replay(), rectangle);
+        let mut candidates = Vec::new();
+        square_search::for_each_candidate(&histogram, |candidate| candidates.push(candidate));
+        let popped: Vec<_> = trace
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                Pop { candidate, .. } => Some(*candidate),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(popped, candidates);
+        let last_best = trace.events.iter().rev().find_map(|event| match event {
+            NewBest { rectangle } => Some(*rectangle),
+            _ => None,
+        });
+        assert_eq!(last_best, rectangle);
+    }
+}
+
+/// Claims more bars than it has, but looks them up without panicking.
+struct OverstatedHistogram {
+    bars: Vec<i32>,
+    claimed_width: usize,
+}
+
+impl Histogram for OverstatedHistogram {
+    fn width(&self) -> usize {
+        self.claimed_width
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        self.bars[horizontal_position]
+    }
+
+    fn try_height_at(&self, horizontal_position: usize) -> Option<i32> {
+        self.bars.get(horizontal_position).copied()
+    }
+}
+
+#[test]
+fn test_try_compute_area_matches_the_search() {
+    for bars in all_histograms(6, 3) {
+        let histogram = ConcreteHistogram::new(bars);
+        let through_trait = FnHistogram::new(histogram.width(), |x_pos| histogram.heights()[x_pos]);
+        let area = square_search::compute_area_of_largest_rectangle(&histogram);
+        assert_eq!(square_search::try_compute_area(&histogram), Ok(area));
+        assert_eq!(square_search::try_compute_area(&through_trait), Ok(area));
+    }
+}
+
+#[test]
+fn test_try_compute_area_reports_a_histogram_that_overstates_its_width() {
+    let histogram = OverstatedHistogram {
+        bars: vec![2, 1, 5],
+        claimed_width: 5,
+    };
+    let error = square_search::try_compute_area(&histogram).unwrap_err();
+    assert_eq!(
+        error,
+        SearchError::MissingBar {
+            horizontal_position: 3,
+            width: 5
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "histogram of width 5 has no bar at position 3"
+    );
+    let honest = OverstatedHistogram {
+        bars: vec![2, 1, 5],
+        claimed_width: 3,
+    };
+    assert_eq!(square_search::try_compute_area(&honest), Ok(5));
+}
+
+/// Has four bars until one of them is read, and two after that.
+struct ShrinkingHistogram {
+    reads: Cell<usize>,
+}
+
+impl Histogram for ShrinkingHistogram {
+    fn width(&self) -> usize {
+        if self.reads.get() == 0 {
+            4
+        } else {
+            2
+        }
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        self.reads.set(self.reads.get() + 1);
+        horizontal_position as i32 + 1
+    }
+}
+
+#[test]
+fn test_try_compute_area_reports_a_width_that_changes() {
+    let histogram = ShrinkingHistogram {
+        reads: Cell::new(0),
+    };
+    assert_eq!(
+        square_search::try_compute_area(&histogram),
+        Err(SearchError::WidthChanged {
+            before: 4,
+            after: 2
+        })
+    );
+    assert_eq!(histogram.reads.get(), 1);
+}
+
+/// Reports a width of six and three on alternate calls, though only six bars
+/// exist, remembering the last width it reported.
+struct AlternatingWidthHistogram {
+    bars: [i32; 6],
+    calls: Cell<usize>,
+    last_width: Cell<usize>,
+}
+
+impl AlternatingWidthHistogram {
+    fn new() -> Self {
+        Self {
+            bars: [2, 1, 5, 6, 2, 3],
+            calls: Cell::new(0),
+            last_width: Cell::new(0),
+        }
+    }
+}
+
+impl Histogram for AlternatingWidthHistogram {
+    fn width(&self) -> usize {
+        let width = if self.calls.get().is_multiple_of(2) {
+            6
+        } else {
+            3
+        };
+        self.calls.set(self.calls.get() + 1);
+        self.last_width.set(width);
+        width
+    }
+
+    fn height_at(&self, horizontal_position: usize) -> i32 {
+        self.bars[horizontal_position]
+    }
+}
+
+#[test]
+fn test_searcher_samples_the_width_once() {
+    let histogram = AlternatingWidthHistogram::new();
+    let mut searcher = LargestRectangleSearcher::new(&histogram);
+    for expected in [(3, 5), (6, 10), (3, 5)] {
+        let calls = histogram.calls.get();
+        let area = searcher.compute_area_of_largest_rectangle();
+        assert_eq!(histogram.calls.get(), calls + 1);
+        assert_eq!((histogram.last_width.get(), area), expected);
+    }
+}
+
+#[test]
+fn test_search_of_a_changing_width_is_well_defined() {
+    let histogram = AlternatingWidthHistogram::new();
+    let rectangle = square_search::compute_largest_rectangle(&histogram);
+    let width = histogram.last_width.get();
+    assert_eq!(
+        rectangle,
+        square_search::compute_largest_rectangle(&histogram.bars[..width])
+    );
+    let mut candidates = Vec::new();
+    square_search::for_each_candidate(&histogram, |candidate| candidates.push(candidate));
+    let width = histogram.last_width.get();
+    assert!(candidates.iter().all(|candidate| candidate.right < width));
+    assert!(matches!(
+        square_search::try_compute_area(&AlternatingWidthHistogram::new()),
+        Err(SearchError::WidthChanged { .. })
+    ));
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "testing")]
+
+use leetcode_largest_rectangle::strategies::{histogram_strategy, Counterexample};
+use leetcode_largest_rectangle::{ConcreteHistogram, Histogram};
+
+#[test]
+fn test_histograms_stay_in_bounds() {
+    let strategy = histogram_strategy(12, 7);
+    for seed in 0..500 {
+        let histogram = strategy.generate(seed);
+        assert!(histogram.width() <= 12, "{seed}");
+        assert!(
+            histogram
+                .heights()
+                .iter()
+                .all(|height| (0..=7).contains(height)),
+            "{seed}"
+        );
+        assert_eq!(strategy.generate(seed).heights(), histogram.heights());
+    }
+    assert!(histogram_strategy(0, 5).generate(3).is_empty());
+    assert!(histogram_strategy(4, 0)
+        .generate(3)
+        .heights()
+        .iter()
+        .all(|&height| height == 0));
+}
+
+#[test]
+fn test_histograms_cover_the_bounds() {
+    let strategy = histogram_strategy(10, 5);
+    let histograms: Vec<ConcreteHistogram> = (0..500).map(|seed| strategy.generate(seed)).collect();
+    for width in 0..=10 {
+        assert!(
+            histograms
+                .iter()
+                .any(|histogram| histogram.width() == width),
+            "{width}"
+        );
+    }
+    for height in 0..=5 {
+        assert!(histograms
+            .iter()
+            .any(|histogram| histogram.heights().contains(&height)));
+    }
+    let plateaus = histograms
+        .iter()
+        .filter(|histogram| {
+            histogram
+                .heights()
+                .windows(3)
+                .any(|run| run[0] == run[1] && run[1] == run[2] && run[0] > 0)
+        })
+        .count();
+    assert!(plateaus > 50, "{plateaus}");
+}
+
+#[test]
+fn test_shrinking_gives_simpler_histograms() {
+    let strategy = histogram_strategy(8, 9);
+    let histogram = ConcreteHistogram::new(vec![4, 0, 9, 2]);
+    let simpler = strategy.shrink(&histogram);
+    assert_eq!(
+        simpler.first().map(|first| first.heights().to_vec()),
+        Some(vec![9, 2])
+    );
+    for candidate in &simpler {
+        let total = |histogram: &ConcreteHistogram| histogram.heights().iter().sum::<i32>();
+        assert!(
+            candidate.width() < histogram.width() || total(candidate) < total(&histogram),
+            "{:?}",
+            candidate.heights()
+        );
+    }
+    assert!(strategy
+        .shrink(&ConcreteHistogram::new(Vec::new()))
+        .is_empty());
+    assert!(strategy
+        .shrink(&ConcreteHistogram::new(vec![0]))
+        .iter()
+        .all(Histogram::is_empty));
+}
+
+#[test]
+fn test_a_property_that_holds_passes() {
+    let strategy = histogram_strategy(16, 100);
+    let property = |histogram: &ConcreteHistogram| histogram.width() <= 16;
+    assert_eq!(strategy.check(300, property), Ok(()));
+    strategy.assert_holds(300, property);
+}
+
+#[test]
+fn test_failing_cases_shrink_to_small_histograms() {
+    let strategy = histogram_strategy(30, 100);
+    let no_tall_bar =
+        |histogram: &ConcreteHistogram| histogram.heights().iter().all(|&height| height < 50);
+    let Err(Counterexample { bars, .. }) = strategy.check(100, no_tall_bar) else {
+        panic!("some histogram has a bar of at least 50");
+    };
+    assert_eq!(bars, vec![50]);
+
+    let no_wide_plateau = |histogram: &ConcreteHistogram| {
+        histogram
+            .heights()
+            .windows(3)
+            .all(|run| run[0] != run[1] || run[1] != run[2] || run[0] == 0)
+    };
+    let Err(counterexample) = strategy.check(100, no_wide_plateau) else {
+        panic!("some histogram has a plateau of three bars");
+    };
+    assert_eq!(counterexample.bars, vec![1, 1, 1]);
+    assert!(counterexample
+        .to_string()
+        .starts_with("property failed for bars [1, 1, 1], shrunk from the histogram of seed "));
+}
+
+#[test]
+#[should_panic(expected = "property failed for bars []")]
+fn test_assert_holds_panics_with_the_shrunk_case() {
+    histogram_strategy(5, 5).assert_holds(10, |_| false);
+}
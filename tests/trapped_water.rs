@@ -0,0 +1,80 @@
+mod common;
+
+use common::{all_histograms, Rng};
+use leetcode_largest_rectangle::square_search;
+use leetcode_largest_rectangle::ConcreteHistogram;
+
+fn brute_force_trapped_water(bars: &[i32]) -> u64 {
+    (0..bars.len())
+        .map(|x_pos| {
+            let left_wall = bars[..=x_pos].iter().max().unwrap().max(&0);
+            let right_wall = bars[x_pos..].iter().max().unwrap().max(&0);
+            (left_wall.min(right_wall) - bars[x_pos].max(0)) as u64
+        })
+        .sum()
+}
+
+#[test]
+fn test_leetcode_example() {
+    let histogram = ConcreteHistogram::new(vec![0, 1, 0, 2, 1, 0, 1, 3, 2, 1, 2, 1]);
+    assert_eq!(square_search::trapped_water(&histogram), 6);
+}
+
+#[test]
+fn test_monotone_histograms_hold_no_water() {
+    let increasing = ConcreteHistogram::new((0..20).collect());
+    assert_eq!(square_search::trapped_water(&increasing), 0);
+    let decreasing = ConcreteHistogram::new((0..20).rev().collect());
+    assert_eq!(square_search::trapped_water(&decreasing), 0);
+}
+
+#[test]
+fn test_empty_histogram_holds_no_water() {
+    let histogram = ConcreteHistogram::new(vec![]);
+    assert_eq!(square_search::trapped_water(&histogram), 0);
+}
+
+#[test]
+fn test_negative_heights_hold_water_as_if_zero() {
+    let histogram = ConcreteHistogram::new(vec![2, -5, 2]);
+    assert_eq!(square_search::trapped_water(&histogram), 2);
+    let histogram = ConcreteHistogram::new(vec![-1, -3, -2]);
+    assert_eq!(square_search::trapped_water(&histogram), 0);
+}
+
+#[test]
+fn test_matches_brute_force() {
+    for bars in all_histograms(6, 3) {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            square_search::trapped_water(&histogram),
+            brute_force_trapped_water(&bars),
+            "{bars:?}"
+        );
+    }
+    let mut rng = Rng::new(66);
+    for _ in 0..200 {
+        let mut bars = rng.bars(100, 1000);
+        for bar in bars.iter_mut().step_by(11) {
+            *bar = -*bar;
+        }
+        let histogram = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            square_search::trapped_water(&histogram),
+            brute_force_trapped_water(&bars),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_tall_walls_around_wide_basin() {
+    let mut bars = vec![0; 100_000];
+    bars[0] = i32::MAX;
+    bars[99_999] = i32::MAX;
+    let histogram = ConcreteHistogram::new(bars);
+    assert_eq!(
+        square_search::trapped_water(&histogram),
+        i32::MAX as u64 * 99_998
+    );
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use common::{all_histograms, Rng};
+use leetcode_largest_rectangle::square_search;
+use leetcode_largest_rectangle::{ConcreteHistogram, Histogram, Rectangle};
+
+/// The largest area over every span, at the height that all but `violations`
+/// of its bars reach.
+fn brute_force_area(bars: &[i32], violations: usize) -> u64 {
+    let mut best = 0;
+    for left in 0..bars.len() {
+        for right in left..bars.len() {
+            let mut span: Vec<i32> = bars[left..=right].iter().map(|&bar| bar.max(0)).collect();
+            span.sort_unstable();
+            let height = span[violations.min(span.len() - 1)];
+            best = best.max(height as u64 * span.len() as u64);
+        }
+    }
+    best
+}
+
+#[test]
+fn test_no_violations_matches_standard_search() {
+    for bars in all_histograms(6, 3) {
+        let histogram = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            square_search::compute_largest_rectangle_with_violations(&histogram, 0),
+            square_search::compute_largest_rectangle(&histogram),
+            "{bars:?}"
+        );
+    }
+    let mut rng = Rng::new(73);
+    for _ in 0..300 {
+        let mut bars = rng.bars(100, 50);
+        for bar in bars.iter_mut().step_by(13) {
+            *bar = -*bar;
+        }
+        let histogram = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            square_search::compute_largest_rectangle_with_violations(&histogram, 0),
+            square_search::compute_largest_rectangle(&histogram),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_glitches_are_bridged() {
+    let histogram = ConcreteHistogram::new(vec![5, 5, 0, 5, 5, 1, 5, 5]);
+    assert_eq!(
+        square_search::compute_largest_rectangle_with_violations(&histogram, 1),
+        Some(Rectangle {
+            left: 0,
+            right: 4,
+            height: 5,
+            area: 25
+        })
+    );
+    assert_eq!(
+        square_search::compute_largest_rectangle_with_violations(&histogram, 2),
+        Some(Rectangle {
+            left: 0,
+            right: 7,
+            height: 5,
+            area: 40
+        })
+    );
+}
+
+#[test]
+fn test_matches_brute_force() {
+    let mut rng = Rng::new(731);
+    for violations in 1..=3 {
+        for bars in all_histograms(5, 2) {
+            let histogram = ConcreteHistogram::new(bars.clone());
+            let found =
+                square_search::compute_largest_rectangle_with_violations(&histogram, violations);
+            assert_eq!(
+                found.map_or(0, |rectangle| rectangle.area),
+                brute_force_area(&bars, violations),
+                "{bars:?} with {violations} violations"
+            );
+        }
+        for _ in 0..300 {
+            let bars = rng.bars(30, 20);
+            let histogram = ConcreteHistogram::new(bars.clone());
+            let found =
+                square_search::compute_largest_rectangle_with_violations(&histogram, violations);
+            assert_eq!(
+                found.map_or(0, |rectangle| rectangle.area),
+                brute_force_area(&bars, violations),
+                "{bars:?} with {violations} violations"
+            );
+            if let Some(rectangle) = found {
+                let shorter = bars[rectangle.left..=rectangle.right]
+                    .iter()
+                    .filter(|&&bar| bar < rectangle.height)
+                    .count();
+                assert!(
+                    shorter <= violations,
+                    "{bars:?} with {violations} violations"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_enough_violations_span_whole_histogram_at_max_height() {
+    let bars = vec![3, 9, 1, 4, 0, 2];
+    let histogram = ConcreteHistogram::new(bars.clone());
+    for violations in [bars.len(), bars.len() + 5, usize::MAX] {
+        let rectangle =
+            square_search::compute_largest_rectangle_with_violations(&histogram, violations)
+                .unwrap();
+        assert_eq!(
+            rectangle.area,
+            histogram.max_height().unwrap() as u64 * histogram.width() as u64
+        );
+        assert_eq!((rectangle.left, rectangle.right), (0, bars.len() - 1));
+    }
+}
+
+#[test]
+fn test_empty_and_all_zero_histograms() {
+    for bars in [vec![], vec![0, 0, 0]] {
+        let histogram = ConcreteHistogram::new(bars);
+        assert_eq!(
+            square_search::compute_largest_rectangle_with_violations(&histogram, 2),
+            None
+        );
+    }
+}
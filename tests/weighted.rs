@@ -0,0 +1,83 @@
+mod common;
+
+use common::Rng;
+use leetcode_largest_rectangle::square_search::{self, compute_area_of_largest_rectangle_weighted};
+use leetcode_largest_rectangle::{ConcreteHistogram, WeightedConcreteHistogram};
+
+#[test]
+fn test_weighted_leetcode_example() {
+    let histogram =
+        WeightedConcreteHistogram::new(vec![(2, 1), (1, 1), (5, 1), (6, 1), (2, 1), (3, 1)]);
+    assert_eq!(compute_area_of_largest_rectangle_weighted(&histogram), 10);
+}
+
+#[test]
+fn test_weighted_wide_bar_beats_tall_narrow_ones() {
+    let histogram = WeightedConcreteHistogram::new(vec![(2, 10), (5, 1), (6, 1), (2, 1)]);
+    assert_eq!(compute_area_of_largest_rectangle_weighted(&histogram), 26);
+}
+
+#[test]
+fn test_weighted_empty_and_zero_width_bars() {
+    assert_eq!(
+        compute_area_of_largest_rectangle_weighted(&WeightedConcreteHistogram::new(vec![])),
+        0
+    );
+    let histogram = WeightedConcreteHistogram::new(vec![(4, 2), (1, 0), (4, 2)]);
+    assert_eq!(compute_area_of_largest_rectangle_weighted(&histogram), 16);
+    let histogram = WeightedConcreteHistogram::new(vec![(4, 2), (2, 0), (4, 1)]);
+    assert_eq!(compute_area_of_largest_rectangle_weighted(&histogram), 12);
+    let histogram = WeightedConcreteHistogram::new(vec![(3, 0), (0, 0)]);
+    assert_eq!(compute_area_of_largest_rectangle_weighted(&histogram), 0);
+}
+
+#[test]
+fn test_weighted_areas_beyond_u64() {
+    let histogram =
+        WeightedConcreteHistogram::new(vec![(i32::MAX, u64::MAX), (i32::MAX, u64::MAX)]);
+    assert_eq!(
+        compute_area_of_largest_rectangle_weighted(&histogram),
+        i32::MAX as u128 * 2 * u64::MAX as u128
+    );
+}
+
+#[test]
+fn test_unit_widths_match_unweighted_search() {
+    let mut rng = Rng::new(27);
+    for _ in 0..500 {
+        let bars = rng.bars(20, 10);
+        let weighted =
+            WeightedConcreteHistogram::new(bars.iter().map(|&height| (height, 1)).collect());
+        let unweighted = ConcreteHistogram::new(bars.clone());
+        assert_eq!(
+            compute_area_of_largest_rectangle_weighted(&weighted),
+            u128::from(square_search::compute_area_of_largest_rectangle(
+                &unweighted
+            )),
+            "{bars:?}"
+        );
+    }
+}
+
+#[test]
+fn test_widths_match_repeated_unit_bars() {
+    let mut rng = Rng::new(2727);
+    for _ in 0..500 {
+        let bars: Vec<(i32, u64)> = (0..rng.below(8))
+            .map(|_| (rng.below(10) as i32, rng.below(3) as u64))
+            .collect();
+        let expanded: Vec<i32> = bars
+            .iter()
+            .flat_map(|&(height, width)| std::iter::repeat_n(height, width as usize))
+            .collect();
+        assert_eq!(
+            compute_area_of_largest_rectangle_weighted(&WeightedConcreteHistogram::new(
+                bars.clone()
+            )),
+            u128::from(square_search::compute_area_of_largest_rectangle(
+                &ConcreteHistogram::new(expanded)
+            )),
+            "{bars:?}"
+        );
+    }
+}